@@ -0,0 +1,602 @@
+use crate::{GenericAddressAccess, GenericAddressStructure};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The instructions an ERST or EINJ instruction entry can carry.
+///
+/// EINJ only uses `ReadRegister` to `Noop`; the rest are ERST only.
+pub enum APEIInstruction {
+    /// Reads the register (masked) into the action's return value.
+    ReadRegister,
+    /// Reads the register (masked) and returns whether it equals `value`.
+    ReadRegisterValue,
+    /// Writes the action's input value (masked) to the register.
+    WriteRegister,
+    /// Writes `value` (masked) to the register.
+    WriteRegisterValue,
+    Noop,
+    /// Reads the register (masked) into the first variable.
+    LoadVar1,
+    /// Reads the register (masked) into the second variable.
+    LoadVar2,
+    /// Writes the first variable (masked) to the register.
+    StoreVar1,
+    /// var1 += var2
+    Add,
+    /// var1 -= var2
+    Subtract,
+    /// register += `value`
+    AddValue,
+    /// register -= `value`
+    SubtractValue,
+    /// Stalls for `value` microseconds.
+    Stall,
+    /// Stalls for the first variable's microseconds while the register (masked) equals `value`.
+    StallWhileTrue,
+    /// Skips the next instruction if the register (masked) equals `value`.
+    SkipNextInstructionIfTrue,
+    /// Jumps to the instruction at index `value` of the action.
+    Goto,
+    /// Reads the register (masked) as the source address for `MoveData`.
+    SetSrcAddressBase,
+    /// Reads the register (masked) as the destination address for `MoveData`.
+    SetDstAddressBase,
+    /// Reads the register (masked) as an offset, and copies the second variable's bytes from source base + offset to destination base + offset.
+    MoveData,
+    Unknown(u8),
+}
+impl APEIInstruction {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0x00 => Self::ReadRegister,
+            0x01 => Self::ReadRegisterValue,
+            0x02 => Self::WriteRegister,
+            0x03 => Self::WriteRegisterValue,
+            0x04 => Self::Noop,
+            0x05 => Self::LoadVar1,
+            0x06 => Self::LoadVar2,
+            0x07 => Self::StoreVar1,
+            0x08 => Self::Add,
+            0x09 => Self::Subtract,
+            0x0A => Self::AddValue,
+            0x0B => Self::SubtractValue,
+            0x0C => Self::Stall,
+            0x0D => Self::StallWhileTrue,
+            0x0E => Self::SkipNextInstructionIfTrue,
+            0x0F => Self::Goto,
+            0x10 => Self::SetSrcAddressBase,
+            0x11 => Self::SetDstAddressBase,
+            0x12 => Self::MoveData,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct APEIInstructionFlags(u8);
+impl APEIInstructionFlags {
+    /// If set, writes to the register only change the bits in `mask`, keeping the others as they were (a read-modify-write).
+    pub const fn preserve_register(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## APEI Instruction Entry
+///
+/// One step of an ERST serialization action or an EINJ injection action.
+/// An action is carried out by running, in order, every instruction entry with that action.
+pub struct APEIInstructionEntry {
+    /// The ERST serialization action or EINJ injection action this instruction is part of.
+    pub action: u8,
+    pub instruction: u8,
+    pub flags: APEIInstructionFlags,
+    reserved: u8,
+    /// The register the instruction works on.
+    pub register_region: GenericAddressStructure,
+    /// The value used by the instruction (compared to, written, added...).
+    pub value: u64,
+    /// The bits of the register the instruction works on.
+    pub mask: u64,
+}
+impl APEIInstructionEntry {
+    pub const fn instruction(&self) -> APEIInstruction {
+        APEIInstruction::from_u8(self.instruction)
+    }
+    /// Reads the register, keeping only the bits in `mask`.
+    ///
+    /// `GenericAddressAccess` hands back values right-aligned, so the GAS bit offset the spec shifts by is already taken care of.
+    pub fn read_register(&self, access: &mut impl GenericAddressAccess) -> u64 {
+        let register = self.register_region;
+        access.read(&register) & self.mask
+    }
+    /// Writes `value` (masked) to the register, keeping the other bits if the entry asks to preserve them.
+    pub fn write_register(&self, access: &mut impl GenericAddressAccess, value: u64) {
+        let register = self.register_region;
+        let mask = self.mask;
+        let value = if self.flags.preserve_register() {
+            (access.read(&register) & !mask) | (value & mask)
+        } else {
+            value & mask
+        };
+        access.write(&register, value)
+    }
+}
+
+/// The `count` instruction entries `offset` bytes into `table`, cut down to the ones that fit in its `length`.
+pub(crate) const fn instruction_entries<T>(
+    table: &T,
+    offset: usize,
+    length: usize,
+    count: usize,
+) -> &[APEIInstructionEntry] {
+    let fits = length.saturating_sub(offset) / core::mem::size_of::<APEIInstructionEntry>();
+    // SAFETY: the entries lie within the table's length, which bounds the count.
+    unsafe {
+        core::slice::from_raw_parts(
+            (table as *const T as *const u8).add(offset) as *const APEIInstructionEntry,
+            if count < fits { count } else { fits },
+        )
+    }
+}
+
+/// The number of instructions an `APEIInterpreter` runs for one action before giving up, unless told otherwise.
+pub const DEFAULT_INSTRUCTION_LIMIT: u32 = 100_000;
+/// The most instruction entries one action can have.  Firmware uses a handful per action; this is plenty.
+pub const MAX_ACTION_INSTRUCTIONS: usize = 256;
+
+/// What an `APEIInterpreter` needs from the platform, on top of register access.
+pub trait APEIPlatform: GenericAddressAccess {
+    /// Waits for (at least) `microseconds` microseconds.
+    fn stall(&mut self, microseconds: u64);
+    /// Reads `buffer.len()` bytes of physical memory at `address`.
+    fn read_memory(&mut self, address: u64, buffer: &mut [u8]);
+    /// Writes `data` to physical memory at `address`.
+    fn write_memory(&mut self, address: u64, data: &[u8]);
+    /// Copies `length` bytes of physical memory from `source` to `destination`; the ranges may overlap.
+    ///
+    /// The default goes through `read_memory`/`write_memory` in small chunks.
+    fn move_data(&mut self, destination: u64, source: u64, length: u64) {
+        let mut chunk = [0u8; 64];
+        let mut done = 0;
+        while done < length {
+            let size = (length - done).min(chunk.len() as u64);
+            // Copy back to front if the destination overlaps the end of the source.
+            let offset = if destination > source {
+                length - done - size
+            } else {
+                done
+            };
+            let chunk = &mut chunk[..size as usize];
+            self.read_memory(source + offset, chunk);
+            self.write_memory(destination + offset, chunk);
+            done += size;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the APEI instruction interpreter can run into.
+pub enum APEIError {
+    /// An instruction entry has an instruction this interpreter doesn't know.
+    UnknownInstruction(u8),
+    /// A `Goto` points past the end of the action.
+    InvalidGoto(u64),
+    /// The action ran more than the instruction limit (a `StallWhileTrue` that never ends, or a `Goto` loop).
+    InstructionLimit,
+    /// The action has more than `MAX_ACTION_INSTRUCTIONS` instruction entries.
+    TooManyInstructions,
+}
+
+/// ## APEI Instruction Interpreter
+///
+/// Runs the instruction entries of an ERST serialization action or an EINJ injection action.
+///
+/// The variables and address bases are kept between actions, like firmware expects.
+pub struct APEIInterpreter {
+    pub var1: u64,
+    pub var2: u64,
+    /// Set by `SetSrcAddressBase`, used by `MoveData`.
+    pub src_base: u64,
+    /// Set by `SetDstAddressBase`, used by `MoveData`.
+    pub dst_base: u64,
+    /// How many instructions (and `StallWhileTrue` polls) to run for one action before returning `APEIError::InstructionLimit`.
+    pub instruction_limit: u32,
+}
+impl Default for APEIInterpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl APEIInterpreter {
+    pub const fn new() -> Self {
+        Self {
+            var1: 0,
+            var2: 0,
+            src_base: 0,
+            dst_base: 0,
+            instruction_limit: DEFAULT_INSTRUCTION_LIMIT,
+        }
+    }
+    /// Runs every entry of `entries` with the action `action`, in order.
+    ///
+    /// `input` is the value `WriteRegister` writes.
+    /// Returns the value the last `ReadRegister` or `ReadRegisterValue` produced (0 if there was none).
+    pub fn run(
+        &mut self,
+        platform: &mut impl APEIPlatform,
+        entries: &[APEIInstructionEntry],
+        action: u8,
+        input: u64,
+    ) -> Result<u64, APEIError> {
+        // Gather the action's entries once, so `Goto` and the instruction pointer can index them.
+        let mut indices = [0u32; MAX_ACTION_INSTRUCTIONS];
+        let mut count = 0;
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.action == action {
+                if count == MAX_ACTION_INSTRUCTIONS {
+                    return Err(APEIError::TooManyInstructions);
+                }
+                indices[count] = index as u32;
+                count += 1;
+            }
+        }
+        let mut ip = 0;
+        let mut executed = 0;
+        let mut output = 0;
+        while ip < count {
+            let entry = &entries[indices[ip] as usize];
+            ip += 1;
+            executed += 1;
+            if executed > self.instruction_limit {
+                return Err(APEIError::InstructionLimit);
+            }
+            let value = entry.value;
+            match entry.instruction() {
+                APEIInstruction::ReadRegister => output = entry.read_register(platform),
+                APEIInstruction::ReadRegisterValue => {
+                    output = (entry.read_register(platform) == value) as u64
+                }
+                APEIInstruction::WriteRegister => entry.write_register(platform, input),
+                APEIInstruction::WriteRegisterValue => entry.write_register(platform, value),
+                APEIInstruction::Noop => {}
+                APEIInstruction::LoadVar1 => self.var1 = entry.read_register(platform),
+                APEIInstruction::LoadVar2 => self.var2 = entry.read_register(platform),
+                APEIInstruction::StoreVar1 => entry.write_register(platform, self.var1),
+                APEIInstruction::Add => self.var1 = self.var1.wrapping_add(self.var2),
+                APEIInstruction::Subtract => self.var1 = self.var1.wrapping_sub(self.var2),
+                APEIInstruction::AddValue => {
+                    let result = entry.read_register(platform).wrapping_add(value);
+                    entry.write_register(platform, result)
+                }
+                APEIInstruction::SubtractValue => {
+                    let result = entry.read_register(platform).wrapping_sub(value);
+                    entry.write_register(platform, result)
+                }
+                APEIInstruction::Stall => platform.stall(value),
+                APEIInstruction::StallWhileTrue => {
+                    while entry.read_register(platform) == value {
+                        executed += 1;
+                        if executed > self.instruction_limit {
+                            return Err(APEIError::InstructionLimit);
+                        }
+                        platform.stall(self.var1);
+                    }
+                }
+                APEIInstruction::SkipNextInstructionIfTrue => {
+                    if entry.read_register(platform) == value {
+                        ip += 1;
+                    }
+                }
+                APEIInstruction::Goto => {
+                    if value > count as u64 {
+                        return Err(APEIError::InvalidGoto(value));
+                    }
+                    ip = value as usize;
+                }
+                APEIInstruction::SetSrcAddressBase => self.src_base = entry.read_register(platform),
+                APEIInstruction::SetDstAddressBase => self.dst_base = entry.read_register(platform),
+                APEIInstruction::MoveData => {
+                    let offset = entry.read_register(platform);
+                    platform.move_data(
+                        self.dst_base.wrapping_add(offset),
+                        self.src_base.wrapping_add(offset),
+                        self.var2,
+                    )
+                }
+                APEIInstruction::Unknown(instruction) => {
+                    return Err(APEIError::UnknownInstruction(instruction));
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// A simulated platform for testing the APEI users (ERST, EINJ) without hardware.
+#[cfg(test)]
+pub(crate) mod fake {
+    use super::*;
+
+    /// Where the simulated physical memory starts.
+    pub(crate) const MEMORY_BASE: u64 = 0x1000;
+    pub(crate) const MEMORY_SIZE: usize = 1024;
+    pub(crate) const REGISTERS: usize = 16;
+
+    // Instruction codes, for building entries.
+    pub(crate) const READ_REGISTER: u8 = 0x00;
+    pub(crate) const READ_REGISTER_VALUE: u8 = 0x01;
+    pub(crate) const WRITE_REGISTER: u8 = 0x02;
+    pub(crate) const WRITE_REGISTER_VALUE: u8 = 0x03;
+    pub(crate) const LOAD_VAR1: u8 = 0x05;
+    pub(crate) const ADD_VALUE: u8 = 0x0A;
+    pub(crate) const SUBTRACT_VALUE: u8 = 0x0B;
+    pub(crate) const STALL_WHILE_TRUE: u8 = 0x0D;
+    pub(crate) const SKIP_NEXT_INSTRUCTION_IF_TRUE: u8 = 0x0E;
+    pub(crate) const GOTO: u8 = 0x0F;
+
+    /// The firmware behind the registers of a `FakePlatform`, reacting to what OSPM does.
+    pub(crate) trait Firmware {
+        /// Called before OSPM reads register `index`.
+        fn reading(&mut self, _: &mut [u64; REGISTERS], _: &mut [u8; MEMORY_SIZE], _: usize) {}
+        /// Called after OSPM writes register `index`.
+        fn written(&mut self, _: &mut [u64; REGISTERS], _: &mut [u8; MEMORY_SIZE], _: usize) {}
+    }
+    impl Firmware for () {}
+
+    /// Registers are System I/O addresses 0 to `REGISTERS - 1`; memory is `MEMORY_SIZE` bytes at `MEMORY_BASE`.
+    pub(crate) struct FakePlatform<F: Firmware> {
+        pub registers: [u64; REGISTERS],
+        pub memory: [u8; MEMORY_SIZE],
+        /// Total microseconds stalled.
+        pub stalled: u64,
+        pub firmware: F,
+    }
+    impl<F: Firmware> FakePlatform<F> {
+        pub(crate) fn new(firmware: F) -> Self {
+            Self {
+                registers: [0; REGISTERS],
+                memory: [0; MEMORY_SIZE],
+                stalled: 0,
+                firmware,
+            }
+        }
+        fn memory(&mut self, address: u64, length: usize) -> &mut [u8] {
+            let start = (address - MEMORY_BASE) as usize;
+            &mut self.memory[start..start + length]
+        }
+    }
+    impl<F: Firmware> GenericAddressAccess for FakePlatform<F> {
+        fn read(&mut self, gas: &GenericAddressStructure) -> u64 {
+            let index = gas.address as usize;
+            self.firmware
+                .reading(&mut self.registers, &mut self.memory, index);
+            self.registers[index]
+        }
+        fn write(&mut self, gas: &GenericAddressStructure, value: u64) {
+            let index = gas.address as usize;
+            self.registers[index] = value;
+            self.firmware
+                .written(&mut self.registers, &mut self.memory, index);
+        }
+    }
+    impl<F: Firmware> APEIPlatform for FakePlatform<F> {
+        fn stall(&mut self, microseconds: u64) {
+            self.stalled += microseconds;
+        }
+        fn read_memory(&mut self, address: u64, buffer: &mut [u8]) {
+            buffer.copy_from_slice(self.memory(address, buffer.len()));
+        }
+        fn write_memory(&mut self, address: u64, data: &[u8]) {
+            self.memory(address, data.len()).copy_from_slice(data);
+        }
+    }
+
+    /// An instruction entry working on the whole 64 bits of `register`.
+    pub(crate) const fn entry(
+        action: u8,
+        instruction: u8,
+        register: u64,
+        value: u64,
+    ) -> APEIInstructionEntry {
+        APEIInstructionEntry {
+            action,
+            instruction,
+            flags: APEIInstructionFlags(0),
+            reserved: 0,
+            register_region: GenericAddressStructure {
+                address_space_id: 0x01,
+                reg_bit_width: 64,
+                reg_bit_offset: 0,
+                access_size: 4,
+                address: register,
+            },
+            value,
+            mask: u64::MAX,
+        }
+    }
+    /// Like `entry`, but only on the bits in `mask`, preserving the others if `preserve` is set.
+    pub(crate) const fn masked_entry(
+        action: u8,
+        instruction: u8,
+        register: u64,
+        value: u64,
+        mask: u64,
+        preserve: bool,
+    ) -> APEIInstructionEntry {
+        let mut entry = entry(action, instruction, register, value);
+        entry.mask = mask;
+        entry.flags = APEIInstructionFlags(preserve as u8);
+        entry
+    }
+
+    /// Lays out a table: `header`, then `entries`, in an 8-byte aligned buffer.
+    pub(crate) fn table(header: &[u8], entries: &[APEIInstructionEntry]) -> [u64; 128] {
+        let mut table = [0u64; 128];
+        let size = core::mem::size_of::<APEIInstructionEntry>();
+        // SAFETY: the buffer is 1024 bytes, and the tests keep their tables smaller.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(table.as_mut_ptr() as *mut u8, 1024) };
+        bytes[..header.len()].copy_from_slice(header);
+        for (i, entry) in entries.iter().enumerate() {
+            // SAFETY: APEIInstructionEntry is plain old data.
+            let entry =
+                unsafe { core::slice::from_raw_parts(entry as *const _ as *const u8, size) };
+            let start = header.len() + i * size;
+            bytes[start..start + size].copy_from_slice(entry);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::*;
+    use super::*;
+
+    /// Register 4 reads 1 until it has been read `countdown` times.
+    struct Busy {
+        countdown: u32,
+    }
+    impl Firmware for Busy {
+        fn reading(
+            &mut self,
+            registers: &mut [u64; REGISTERS],
+            _: &mut [u8; MEMORY_SIZE],
+            index: usize,
+        ) {
+            if index == 4 {
+                self.countdown = self.countdown.saturating_sub(1);
+                registers[4] = (self.countdown > 0) as u64;
+            }
+        }
+    }
+
+    fn run(
+        platform: &mut impl APEIPlatform,
+        entries: &[APEIInstructionEntry],
+        action: u8,
+    ) -> Result<u64, APEIError> {
+        APEIInterpreter::new().run(platform, entries, action, 0)
+    }
+
+    #[test]
+    fn read_register_value() {
+        let mut platform = FakePlatform::new(());
+        platform.registers[0] = 0x105;
+        let entries = [
+            masked_entry(0, READ_REGISTER_VALUE, 0, 0x05, 0xFF, false),
+            masked_entry(1, READ_REGISTER_VALUE, 0, 0x06, 0xFF, false),
+            masked_entry(2, READ_REGISTER, 0, 0, 0xFF, false),
+        ];
+        assert_eq!(run(&mut platform, &entries, 0), Ok(1));
+        assert_eq!(run(&mut platform, &entries, 1), Ok(0));
+        assert_eq!(run(&mut platform, &entries, 2), Ok(0x05));
+    }
+
+    #[test]
+    fn write_register() {
+        let mut platform = FakePlatform::new(());
+        platform.registers[1] = 0xAB00;
+        let entries = [
+            entry(0, WRITE_REGISTER, 0, 0),
+            masked_entry(0, WRITE_REGISTER_VALUE, 1, 0x1CD, 0xFF, true),
+        ];
+        APEIInterpreter::new()
+            .run(&mut platform, &entries, 0, 0x1234)
+            .unwrap();
+        assert_eq!(platform.registers[0], 0x1234);
+        assert_eq!(platform.registers[1], 0xABCD);
+    }
+
+    #[test]
+    fn skip_next_instruction_if_true() {
+        let entries = [
+            entry(0, SKIP_NEXT_INSTRUCTION_IF_TRUE, 0, 5),
+            entry(0, WRITE_REGISTER_VALUE, 1, 0xAA),
+            entry(0, WRITE_REGISTER_VALUE, 2, 0xBB),
+        ];
+        let mut platform = FakePlatform::new(());
+        platform.registers[0] = 5;
+        run(&mut platform, &entries, 0).unwrap();
+        assert_eq!(platform.registers[1..3], [0, 0xBB]);
+
+        let mut platform = FakePlatform::new(());
+        platform.registers[0] = 4;
+        run(&mut platform, &entries, 0).unwrap();
+        assert_eq!(platform.registers[1..3], [0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn goto_loop() {
+        // Entries of other actions are interleaved; Goto indexes count only this action's.
+        let entries = [
+            entry(1, ADD_VALUE, 0, 1),
+            entry(0, WRITE_REGISTER_VALUE, 1, 0xFF),
+            entry(1, SKIP_NEXT_INSTRUCTION_IF_TRUE, 0, 3),
+            entry(1, GOTO, 0, 0),
+            entry(1, WRITE_REGISTER_VALUE, 2, 1),
+        ];
+        let mut platform = FakePlatform::new(());
+        run(&mut platform, &entries, 1).unwrap();
+        assert_eq!(platform.registers[0..3], [3, 0, 1]);
+
+        let entries = [entry(0, GOTO, 0, 2)];
+        assert_eq!(
+            run(&mut FakePlatform::new(()), &entries, 0),
+            Err(APEIError::InvalidGoto(2))
+        );
+    }
+
+    #[test]
+    fn add_and_subtract_value() {
+        let mut platform = FakePlatform::new(());
+        platform.registers[0] = 0x1F0;
+        platform.registers[1] = 10;
+        let entries = [
+            masked_entry(0, ADD_VALUE, 0, 0x20, 0xFF, true),
+            entry(0, SUBTRACT_VALUE, 1, 3),
+        ];
+        run(&mut platform, &entries, 0).unwrap();
+        // 0xF0 + 0x20 wraps within the mask, and the bits outside it are kept.
+        assert_eq!(platform.registers[0], 0x110);
+        assert_eq!(platform.registers[1], 7);
+    }
+
+    #[test]
+    fn stall_while_true() {
+        let mut platform = FakePlatform::new(Busy { countdown: 3 });
+        platform.registers[5] = 10;
+        let entries = [entry(0, LOAD_VAR1, 5, 0), entry(0, STALL_WHILE_TRUE, 4, 1)];
+        run(&mut platform, &entries, 0).unwrap();
+        assert_eq!(platform.stalled, 20);
+    }
+
+    #[test]
+    fn instruction_limit() {
+        let mut interpreter = APEIInterpreter::new();
+        interpreter.instruction_limit = 50;
+        let mut platform = FakePlatform::new(Busy {
+            countdown: u32::MAX,
+        });
+        let entries = [entry(0, STALL_WHILE_TRUE, 4, 1)];
+        assert_eq!(
+            interpreter.run(&mut platform, &entries, 0, 0),
+            Err(APEIError::InstructionLimit)
+        );
+        let entries = [entry(0, GOTO, 0, 0)];
+        assert_eq!(
+            interpreter.run(&mut platform, &entries, 0, 0),
+            Err(APEIError::InstructionLimit)
+        );
+    }
+
+    #[test]
+    fn unknown_instruction() {
+        let entries = [entry(0, 0x42, 0, 0)];
+        assert_eq!(
+            run(&mut FakePlatform::new(()), &entries, 0),
+            Err(APEIError::UnknownInstruction(0x42))
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use crate::SDTHeader;
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Boot Error Record Table (BERT)
+///
+/// Hands OSPM the error records of errors that happened before (or during) the previous boot, which the platform couldn't report at the time.
+/// The records are in the Boot Error Region, laid out as a Generic Error Status Block.
+pub struct BERT {
+    /// - **Signature** - "BERT"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    /// Length, in bytes, of the Boot Error Region.
+    pub boot_error_region_length: u32,
+    /// Physical address of the Boot Error Region.
+    pub boot_error_region: u64,
+}
@@ -0,0 +1,552 @@
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    srat::{SRAT, affinity::generic_port::GenericPortAffinity},
+};
+
+/// The _HID of a CXL host bridge.
+pub const CXL_HOST_BRIDGE_HID: &[u8] = b"ACPI0016";
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct CEDTStructureHeader {
+    pub r#type: u8,
+    reserved: u8,
+    /// Length, in bytes, of the whole structure.
+    pub record_length: u16,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CXLVersion {
+    /// A CXL 1.1 host bridge (Restricted CXL Host); `base` points to its RCRB.
+    RCH,
+    /// A CXL 2.0 (or later) host bridge; `base` points to its Component Registers.
+    CXL2,
+    Unknown(u32),
+}
+impl CXLVersion {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::RCH,
+            1 => Self::CXL2,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u32(&self) -> u32 {
+        match *self {
+            Self::RCH => 0,
+            Self::CXL2 => 1,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CXL Host Bridge Structure (CHBS)
+///
+/// Where the registers of a CXL host bridge are, tied to its ACPI0016 device by _UID.
+pub struct CHBS {
+    /// - **Type** - 0
+    /// - **Record Length** - 32
+    pub header: CEDTStructureHeader,
+    /// The _UID of the host bridge's ACPI0016 device.
+    pub uid: u32,
+    pub cxl_version: u32,
+    reserved: u32,
+    /// Base address of the RCRB (CXL 1.1) or the CHBCR (CXL 2.0).
+    pub base: u64,
+    /// Length, in bytes, of the register block: 8 KiB for an RCRB, 64 KiB for a CHBCR.
+    pub length: u64,
+}
+impl CHBS {
+    pub const fn version(&self) -> CXLVersion {
+        CXLVersion::from_u32(self.cxl_version)
+    }
+    /// The SRAT Generic Port Affinity structure of this host bridge (an ACPI device handle with the ACPI0016 _HID and the same _UID).
+    pub fn generic_port_affinity<'s>(&self, srat: &'s SRAT) -> Option<&'s GenericPortAffinity> {
+        let uid = self.uid;
+        srat.generic_port_affinities().find(|affinity| {
+            if affinity.device_handle_type != 0 || !({ affinity.flags }).enabled() {
+                return false;
+            }
+            // SAFETY: a device handle type of 0 means the handle is an ACPI one.
+            let handle = unsafe { affinity.device_handle.acpi };
+            handle.acpi_uid == uid && handle.hid().matches(CXL_HOST_BRIDGE_HID)
+        })
+    }
+    /// The proximity domain of this host bridge, from its SRAT Generic Port Affinity structure.
+    pub fn proximity_domain(&self, srat: &SRAT) -> Option<u32> {
+        self.generic_port_affinity(srat)
+            .map(|affinity| affinity.proximity_domain)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InterleaveArithmetic {
+    /// Standard modulo arithmetic.
+    Modulo,
+    /// Modulo arithmetic combined with XOR; the XOR maps are in the CXIMS with the same granularity.
+    XOR,
+    Unknown(u8),
+}
+impl InterleaveArithmetic {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Modulo,
+            1 => Self::XOR,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::Modulo => 0,
+            Self::XOR => 1,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct WindowRestrictions(u16);
+impl WindowRestrictions {
+    /// The window can map device coherent memory (CXL Type 2 devices).
+    pub const fn device_coherent(&self) -> bool {
+        self.0 & 0b00001 != 0
+    }
+    /// The window can map host-only coherent memory (CXL Type 3 devices).
+    pub const fn host_only_coherent(&self) -> bool {
+        self.0 & 0b00010 != 0
+    }
+    /// The window can map volatile memory.
+    pub const fn volatile(&self) -> bool {
+        self.0 & 0b00100 != 0
+    }
+    /// The window can map persistent memory.
+    pub const fn persistent(&self) -> bool {
+        self.0 & 0b01000 != 0
+    }
+    /// The configuration of the devices in the window is fixed by firmware; OSPM must not change it.
+    pub const fn fixed_device_configuration(&self) -> bool {
+        self.0 & 0b10000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CXL Fixed Memory Window Structure (CFMWS)
+///
+/// A range of host physical addresses (HPA) that the platform routes to CXL host bridges, interleaved across the ones in its target list.
+pub struct CFMWS {
+    /// - **Type** - 1
+    /// - **Record Length** - 36 + 4 * the number of interleave ways
+    pub header: CEDTStructureHeader,
+    reserved0: u32,
+    pub base_hpa: u64,
+    /// Size, in bytes, of the window (a multiple of the number of interleave ways * 256 MiB).
+    pub window_size: u64,
+    /// Encoded Number of Interleave Ways (ENIW): 0-4 are 2^ENIW ways, 8-10 are 3 * 2^(ENIW - 8) ways.
+    pub encoded_interleave_ways: u8,
+    pub interleave_arithmetic: u8,
+    reserved1: u16,
+    /// Host Bridge Interleave Granularity (HBIG): the interleave granularity is 256 << HBIG bytes.
+    pub host_bridge_interleave_granularity: u32,
+    pub window_restrictions: WindowRestrictions,
+    /// The QoS Throttling Group of the window.
+    pub qtg_id: u16,
+    pub interleave_target_list: [u32; 0],
+}
+impl CFMWS {
+    /// The number of host bridges the window is interleaved across, or None if `encoded_interleave_ways` is reserved.
+    pub const fn interleave_ways(&self) -> Option<usize> {
+        match self.encoded_interleave_ways {
+            eniw @ 0..=4 => Some(1 << eniw),
+            eniw @ 8..=10 => Some(3 << (eniw - 8)),
+            _ => None,
+        }
+    }
+    /// The interleave granularity in bytes, or None if `host_bridge_interleave_granularity` is reserved.
+    pub const fn interleave_granularity(&self) -> Option<u64> {
+        match self.host_bridge_interleave_granularity {
+            hbig @ 0..=6 => Some(256 << hbig),
+            _ => None,
+        }
+    }
+    pub const fn arithmetic(&self) -> InterleaveArithmetic {
+        InterleaveArithmetic::from_u8(self.interleave_arithmetic)
+    }
+    /// The _UIDs of the host bridges the window is interleaved across, in interleave order.
+    pub const fn interleave_targets(&self) -> &[u32] {
+        let fits = (self.header.record_length as usize).saturating_sub(36) / 4;
+        let count = match self.interleave_ways() {
+            Some(ways) if ways <= fits => ways,
+            _ => 0,
+        };
+        // SAFETY: the targets lie within the record's length, which bounds the count, and `CEDTStructureIter` checked that length against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(36) as *const u32,
+                count,
+            )
+        }
+    }
+    pub const fn contains(&self, hpa: u64) -> bool {
+        hpa >= self.base_hpa && hpa - self.base_hpa < self.window_size
+    }
+    /// The position, in the interleave target list, of the host bridge that `hpa` is decoded to.
+    ///
+    /// With XOR arithmetic, `cxims` has to be the CXIMS with the same granularity as the window (see `CEDT::xor_math`).
+    ///
+    /// The address is split into a power of 2 part (the lowest bits above the granularity, one per factor of 2 in the ways) and, for 3, 6 and 12 ways,
+    /// a modulo 3 part (every bit above those, mod 3). The position is `power_of_2_part + (modulo_3_part << bits_in_power_of_2_part)`.
+    /// With XOR arithmetic, each bit of the power of 2 part is the parity of the address ANDed with the matching XOR map instead.
+    pub fn interleave_position(&self, hpa: u64, cxims: Option<&CXIMS>) -> Option<usize> {
+        if !self.contains(hpa) {
+            return None;
+        }
+        let ways = self.interleave_ways()?;
+        let granularity_shift = self.interleave_granularity()?.trailing_zeros();
+        let (power_of_2_ways, modulo_3) = if ways % 3 == 0 {
+            (ways / 3, true)
+        } else {
+            (ways, false)
+        };
+        let bits = power_of_2_ways.trailing_zeros();
+        let low = match self.arithmetic() {
+            InterleaveArithmetic::Modulo => {
+                (hpa >> granularity_shift) as usize & (power_of_2_ways - 1)
+            }
+            InterleaveArithmetic::XOR => {
+                let cxims = cxims?;
+                if cxims.host_bridge_interleave_granularity as u32
+                    != self.host_bridge_interleave_granularity
+                {
+                    return None;
+                }
+                let mut position = 0;
+                for bit in 0..bits as usize {
+                    position |= ((hpa & cxims.xormap(bit)?).count_ones() as usize & 1) << bit;
+                }
+                position
+            }
+            InterleaveArithmetic::Unknown(_) => return None,
+        };
+        let high = if modulo_3 {
+            ((hpa >> (granularity_shift + bits)) % 3) as usize
+        } else {
+            0
+        };
+        Some(low + (high << bits))
+    }
+    /// The _UID of the host bridge that `hpa` is decoded to (see `interleave_position`).
+    pub fn interleave_target(&self, hpa: u64, cxims: Option<&CXIMS>) -> Option<u32> {
+        let position = self.interleave_position(hpa, cxims)?;
+        self.interleave_targets().get(position).copied()
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CXL XOR Interleave Math Structure (CXIMS)
+///
+/// The XOR maps that windows using XOR interleave arithmetic (with the same granularity) pick their host bridge with.
+pub struct CXIMS {
+    /// - **Type** - 2
+    /// - **Record Length** - 8 + 8 * `number_of_bitmap_entries`
+    pub header: CEDTStructureHeader,
+    reserved: u16,
+    /// Host Bridge Interleave Granularity (HBIG), same encoding as in the CFMWS.
+    pub host_bridge_interleave_granularity: u8,
+    pub number_of_bitmap_entries: u8,
+    pub xormap_list: [u64; 0],
+}
+impl CXIMS {
+    /// The map of bit `index` of the interleave position.
+    ///
+    /// CEDT structures are only 4-byte aligned, so the list can't be handed out as a `&[u64]`; each map is read on its own instead.
+    pub fn xormap(&self, index: usize) -> Option<u64> {
+        if index >= self.number_of_bitmap_entries as usize
+            || 8 + (index + 1) * 8 > self.header.record_length as usize
+        {
+            return None;
+        }
+        // SAFETY: the map lies within the structure's record length, checked above.
+        unsafe {
+            Some(core::ptr::read_unaligned(
+                (self as *const _ as *const u8).add(8 + index * 8) as *const u64,
+            ))
+        }
+    }
+    /// One map per bit of the interleave position, lowest bit first (the ones that fit in the record length).
+    pub fn xormaps(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.number_of_bitmap_entries as usize).map_while(move |i| self.xormap(i))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RDPASProtocol {
+    /// CXL.io
+    IO,
+    /// CXL.cachemem
+    CacheMem,
+    Unknown(u8),
+}
+impl RDPASProtocol {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::IO,
+            1 => Self::CacheMem,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::IO => 0,
+            Self::CacheMem => 1,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RCEC Downstream Port Association Structure (RDPAS)
+///
+/// Ties the downstream port of a CXL 1.1 host bridge to the Root Complex Event Collector (RCEC) that reports its errors.
+pub struct RDPAS {
+    /// - **Type** - 3
+    pub header: CEDTStructureHeader,
+    /// PCI segment of the RCEC.
+    pub rcec_segment: u16,
+    /// Bus (bits 15:8), device (bits 7:3) and function (bits 2:0) of the RCEC.
+    pub rcec_bdf: u16,
+    pub protocol_type: u8,
+    /// The RCRB base address of the downstream port (for CXL.io), or its component register base (for CXL.cachemem).
+    pub base_address: u64,
+}
+impl RDPAS {
+    pub const fn protocol(&self) -> RDPASProtocol {
+        RDPASProtocol::from_u8(self.protocol_type)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum CEDTStructure<'a> {
+    CHBS(&'a CHBS),
+    CFMWS(&'a CFMWS),
+    CXIMS(&'a CXIMS),
+    RDPAS(&'a RDPAS),
+    /// A structure type this library doesn't know about (or a reserved one).
+    Unknown(&'a CEDTStructureHeader),
+}
+
+/// Iterator over the structures of a CEDT.
+pub struct CEDTStructureIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _cedt: core::marker::PhantomData<&'a CEDT>,
+}
+impl<'a> Iterator for CEDTStructureIter<'a> {
+    type Item = CEDTStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const CEDTStructureHeader) };
+        let length = header.record_length as usize;
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 4 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        let fits = |size: usize| length >= size;
+        // SAFETY: every structure is checked against its length before the cast.
+        Some(unsafe {
+            match header.r#type {
+                0 if fits(core::mem::size_of::<CHBS>()) => CEDTStructure::CHBS(&*(ptr as *const _)),
+                1 if fits(core::mem::size_of::<CFMWS>()) => {
+                    CEDTStructure::CFMWS(&*(ptr as *const _))
+                }
+                2 if fits(core::mem::size_of::<CXIMS>()) => {
+                    CEDTStructure::CXIMS(&*(ptr as *const _))
+                }
+                3 if fits(core::mem::size_of::<RDPAS>()) => {
+                    CEDTStructure::RDPAS(&*(ptr as *const _))
+                }
+                _ => CEDTStructure::Unknown(header),
+            }
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CXL Early Discovery Table (CEDT)
+///
+/// Describes the CXL host bridges of the platform and the fixed memory windows routed to them, before the ACPI namespace is up.
+pub struct CEDT {
+    /// - **Signature** - "CEDT"
+    /// - **Revision** - 1 (CXL 2.0 and later)
+    pub header: SDTHeader,
+}
+impl CEDT {
+    pub const fn structures(&self) -> CEDTStructureIter<'_> {
+        CEDTStructureIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE),
+            _cedt: core::marker::PhantomData,
+        }
+    }
+    pub fn host_bridges(&self) -> impl Iterator<Item = &CHBS> {
+        self.structures().filter_map(|structure| match structure {
+            CEDTStructure::CHBS(chbs) => Some(chbs),
+            _ => None,
+        })
+    }
+    /// The host bridge with the _UID `uid`.
+    pub fn host_bridge(&self, uid: u32) -> Option<&CHBS> {
+        self.host_bridges().find(|chbs| chbs.uid == uid)
+    }
+    pub fn fixed_memory_windows(&self) -> impl Iterator<Item = &CFMWS> {
+        self.structures().filter_map(|structure| match structure {
+            CEDTStructure::CFMWS(cfmws) => Some(cfmws),
+            _ => None,
+        })
+    }
+    /// The CXIMS for the interleave granularity `hbig` (encoded, like in the CFMWS).
+    pub fn xor_math(&self, hbig: u32) -> Option<&CXIMS> {
+        self.structures().find_map(|structure| match structure {
+            CEDTStructure::CXIMS(cxims)
+                if cxims.host_bridge_interleave_granularity as u32 == hbig =>
+            {
+                Some(cxims)
+            }
+            _ => None,
+        })
+    }
+    pub fn rcec_downstream_ports(&self) -> impl Iterator<Item = &RDPAS> {
+        self.structures().filter_map(|structure| match structure {
+            CEDTStructure::RDPAS(rdpas) => Some(rdpas),
+            _ => None,
+        })
+    }
+    /// The fixed memory window containing `hpa`.
+    pub fn fixed_memory_window(&self, hpa: u64) -> Option<&CFMWS> {
+        self.fixed_memory_windows()
+            .find(|cfmws| cfmws.contains(hpa))
+    }
+    /// The host bridge that `hpa` is decoded to, going through the fixed memory window containing it.
+    pub fn host_bridge_for(&self, hpa: u64) -> Option<&CHBS> {
+        let cfmws = self.fixed_memory_window(hpa)?;
+        let cxims = self.xor_math(cfmws.host_bridge_interleave_granularity);
+        let uid = cfmws.interleave_target(hpa, cxims)?;
+        self.host_bridge(uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4 GiB window at 0 with a 256 byte granularity.
+    fn window(buffer: &mut [u64; 11], eniw: u8, arithmetic: InterleaveArithmetic) -> &CFMWS {
+        // SAFETY: the buffer is 88 bytes, enough for a 12-way window.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 88) };
+        bytes[0] = 1;
+        bytes[2..4].copy_from_slice(&84u16.to_le_bytes());
+        bytes[16..24].copy_from_slice(&0x1_0000_0000u64.to_le_bytes());
+        bytes[24] = eniw;
+        bytes[25] = arithmetic.as_u8();
+        // SAFETY: the window is packed, and lies within the buffer.
+        unsafe { &*(buffer.as_ptr() as *const CFMWS) }
+    }
+
+    /// A CXIMS with a 256 byte granularity and `maps`, 4 bytes into the buffer so the maps aren't 8-byte aligned.
+    fn xor_math<'a>(buffer: &'a mut [u64; 4], maps: &[u64]) -> &'a CXIMS {
+        // SAFETY: the buffer is 32 bytes, enough for 3 maps after the offset.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 32) };
+        let structure = &mut bytes[4..];
+        structure[0] = 2;
+        structure[2..4].copy_from_slice(&(8 + 8 * maps.len() as u16).to_le_bytes());
+        structure[7] = 3;
+        for (i, map) in maps.iter().enumerate() {
+            structure[8 + i * 8..][..8].copy_from_slice(&map.to_le_bytes());
+        }
+        // SAFETY: the structure is packed, and lies within the buffer.
+        unsafe { &*(structure.as_ptr() as *const CXIMS) }
+    }
+
+    #[test]
+    fn modulo_interleave_position() {
+        // (ENIW, HPA, position)
+        let cases = [
+            (0, 0x1234_5600, 0),
+            (2, 0x300, 3),
+            (2, 0x500, 1),
+            // 3 ways: no power of 2 part, the whole address above the granularity mod 3.
+            (8, 0x400, 1),
+            (8, 0x500, 2),
+            // 6 ways: 1 power of 2 bit, then mod 3 of the rest.
+            (9, 0x700, 1),
+            (9, 0xB00, 5),
+            // 12 ways: 2 power of 2 bits, then mod 3 of the rest.
+            (10, 0x1700, 11),
+            (10, 0x2400, 0),
+        ];
+        for &(eniw, hpa, position) in cases.iter() {
+            let mut buffer = [0; 11];
+            let window = window(&mut buffer, eniw, InterleaveArithmetic::Modulo);
+            assert_eq!(
+                window.interleave_position(hpa, None),
+                Some(position),
+                "ENIW {} HPA {:#x}",
+                eniw,
+                hpa
+            );
+        }
+    }
+
+    #[test]
+    fn xor_interleave_position() {
+        let maps = [0x1100, 0x2200];
+        // (ENIW, HPA, position)
+        let cases = [
+            (2, 0x1100, 0),
+            (2, 0x1000, 1),
+            (2, 0x2300, 1),
+            (2, 0x3000, 3),
+            (9, 0x800, 2),
+            (9, 0x500, 5),
+        ];
+        for &(eniw, hpa, position) in cases.iter() {
+            let mut buffer = [0; 11];
+            let window = window(&mut buffer, eniw, InterleaveArithmetic::XOR);
+            let mut buffer = [0; 4];
+            let cxims = xor_math(&mut buffer, &maps);
+            assert_eq!(
+                window.interleave_position(hpa, Some(cxims)),
+                Some(position),
+                "ENIW {} HPA {:#x}",
+                eniw,
+                hpa
+            );
+        }
+    }
+
+    #[test]
+    fn interleave_position_failures() {
+        let mut buffer = [0; 11];
+        let window = window(&mut buffer, 2, InterleaveArithmetic::XOR);
+        assert_eq!(window.interleave_position(0x1_0000_0000, None), None);
+        assert_eq!(window.interleave_position(0x100, None), None);
+        // The CXIMS claims 3 maps but its record length only has room for 1.
+        let mut buffer = [0; 4];
+        let cxims = xor_math(&mut buffer, &[0x100]);
+        assert!(cxims.xormaps().eq([0x100]));
+        assert_eq!(window.interleave_position(0x100, Some(cxims)), None);
+    }
+}
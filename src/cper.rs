@@ -0,0 +1,1149 @@
+use crate::uuid;
+use core::fmt;
+
+/// Processor Generic Error Section
+pub const PROCESSOR_GENERIC_SECTION: [u8; 16] = uuid("9876CCAD-47B4-4BDB-B65E-16F193C4F3DB");
+/// IA32/X64 Processor Error Section
+pub const IA32_X64_PROCESSOR_SECTION: [u8; 16] = uuid("DC3EA0B0-A144-4797-B95B-53FA242B6E1D");
+/// Arm Processor Error Section
+pub const ARM_PROCESSOR_SECTION: [u8; 16] = uuid("E19E3D16-BC11-11E4-9CAA-C2051D5D46B0");
+/// Platform Memory Error Section
+pub const PLATFORM_MEMORY_SECTION: [u8; 16] = uuid("A5BC1114-6F64-4EDE-B863-3E83ED7C83B1");
+/// Platform Memory Error Section 2
+pub const PLATFORM_MEMORY_2_SECTION: [u8; 16] = uuid("61EC04FC-48E6-D813-25C9-8DAA44750B12");
+/// PCI Express Error Section
+pub const PCIE_SECTION: [u8; 16] = uuid("D995E954-BBC1-430F-AD91-B44DCB3C6F35");
+/// Firmware Error Record Reference Section
+pub const FIRMWARE_ERROR_RECORD_REFERENCE_SECTION: [u8; 16] =
+    uuid("81212A96-09ED-4996-9471-8D729C8E69ED");
+/// PCI/PCI-X Bus Error Section
+pub const PCI_BUS_SECTION: [u8; 16] = uuid("C5753963-3B84-4095-BF78-EDDAD3F9C9DD");
+/// PCI Component/Device Error Section
+pub const PCI_COMPONENT_SECTION: [u8; 16] = uuid("EB5E4685-CA66-4769-B6A2-26068B001326");
+/// CXL Protocol Error Section
+pub const CXL_PROTOCOL_SECTION: [u8; 16] = uuid("80B9EFB4-52B5-4DE3-A777-68784B771048");
+
+// Prints a GUID stored in EFI_GUID byte order in its usual text form.
+fn write_guid(f: &mut fmt::Formatter<'_>, guid: &[u8; 16]) -> fmt::Result {
+    write!(
+        f,
+        "{:08X}-{:04X}-{:04X}-",
+        u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+        u16::from_le_bytes([guid[4], guid[5]]),
+        u16::from_le_bytes([guid[6], guid[7]])
+    )?;
+    for (i, byte) in guid[8..].iter().enumerate() {
+        if i == 2 {
+            f.write_str("-")?;
+        }
+        write!(f, "{:02X}", byte)?;
+    }
+    Ok(())
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorSeverity {
+    /// Recoverable (also called non-fatal uncorrected).
+    Recoverable,
+    Fatal,
+    Corrected,
+    /// Informational; no error.
+    None,
+    Unknown(u32),
+}
+impl ErrorSeverity {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::Recoverable,
+            1 => Self::Fatal,
+            2 => Self::Corrected,
+            3 => Self::None,
+            other => Self::Unknown(other),
+        }
+    }
+}
+impl fmt::Display for ErrorSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Recoverable => f.write_str("recoverable"),
+            Self::Fatal => f.write_str("fatal"),
+            Self::Corrected => f.write_str("corrected"),
+            Self::None => f.write_str("informational"),
+            Self::Unknown(value) => write!(f, "severity {}", value),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct BlockStatus(u32);
+impl BlockStatus {
+    /// Set if an uncorrectable error is recorded in the block.
+    pub const fn uncorrectable_error_valid(&self) -> bool {
+        self.0 & 0b0001 != 0
+    }
+    /// Set if a correctable error is recorded in the block.
+    pub const fn correctable_error_valid(&self) -> bool {
+        self.0 & 0b0010 != 0
+    }
+    /// Set if more than one uncorrectable error happened (only the first may be recorded).
+    pub const fn multiple_uncorrectable_errors(&self) -> bool {
+        self.0 & 0b0100 != 0
+    }
+    /// Set if more than one correctable error happened (only the first may be recorded).
+    pub const fn multiple_correctable_errors(&self) -> bool {
+        self.0 & 0b1000 != 0
+    }
+    /// The number of Generic Error Data Entries in the block.
+    pub const fn error_data_entry_count(&self) -> u16 {
+        ((self.0 >> 4) & 0x3FF) as u16
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Generic Error Status Block
+///
+/// The block a Generic Hardware Error Source (or the BERT's Boot Error Region) records errors in:
+/// a header followed by Generic Error Data Entries, each holding one CPER section.
+///
+/// A `block_status` of 0 means the block is empty.
+pub struct GenericErrorStatusBlock {
+    pub block_status: BlockStatus,
+    /// Offset, in bytes, of the raw error data from the start of this block.
+    pub raw_data_offset: u32,
+    pub raw_data_length: u32,
+    /// Length, in bytes, of the Generic Error Data Entries.
+    pub data_length: u32,
+    pub error_severity: u32,
+    pub generic_error_data: [u8; 0],
+}
+impl GenericErrorStatusBlock {
+    pub const fn error_severity(&self) -> ErrorSeverity {
+        ErrorSeverity::from_u32(self.error_severity)
+    }
+    pub fn is_empty(&self) -> bool {
+        let status = self.block_status;
+        status.0 == 0
+    }
+    pub const fn entries(&self) -> GenericErrorDataEntryIter<'_> {
+        GenericErrorDataEntryIter {
+            // SAFETY: the block header is 20 bytes, so this is at most one past its end.
+            next: unsafe { (self as *const _ as *const u8).add(20) },
+            remaining: self.data_length as usize,
+            _block: core::marker::PhantomData,
+        }
+    }
+    /// The raw error data, in a format specific to the error source.
+    ///
+    /// Empty if the raw data overlaps the header or the entries.
+    pub const fn raw_data(&self) -> &[u8] {
+        if self.raw_data_length == 0
+            || (self.raw_data_offset as usize) < 20 + self.data_length as usize
+        {
+            return &[];
+        }
+        // SAFETY: the raw data is past the entries (checked above); firmware sizes the block (`error_block_length` in the HEST)
+        // to hold the header, the entries and the raw data, and that's how much of it gets mapped.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(self.raw_data_offset as usize),
+                self.raw_data_length as usize,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct DataEntryValidationBits(u8);
+impl DataEntryValidationBits {
+    pub const fn fru_id_valid(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    pub const fn fru_string_valid(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    pub const fn timestamp_valid(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Generic Error Data Entry
+///
+/// One CPER section inside a Generic Error Status Block, with its section type and severity.
+///
+/// The section data starts right after this header: at byte 64, or byte 72 for revision 0x300 and up (which adds `timestamp`).
+pub struct GenericErrorDataEntry {
+    /// Which CPER section the data is (one of the `*_SECTION` GUIDs in this module).
+    pub section_type: [u8; 16],
+    pub error_severity: u32,
+    /// 0x300
+    pub revision: u16,
+    pub validation_bits: DataEntryValidationBits,
+    /// - **Bit 0** - Primary
+    /// - **Bit 1** - Containment warning
+    /// - **Bit 2** - Reset
+    /// - **Bit 3** - Error threshold exceeded
+    /// - **Bit 4** - Resource not accessible
+    /// - **Bit 5** - Latent error
+    pub flags: u8,
+    /// Length, in bytes, of the section data.
+    pub error_data_length: u32,
+    /// Identifies the Field Replaceable Unit.
+    pub fru_id: [u8; 16],
+    /// An ASCII string identifying the Field Replaceable Unit.
+    pub fru_text: [u8; 20],
+    /// This field is only there for revision 0x300 and up; use `timestamp()`.
+    pub timestamp: u64,
+}
+impl GenericErrorDataEntry {
+    pub const fn error_severity(&self) -> ErrorSeverity {
+        ErrorSeverity::from_u32(self.error_severity)
+    }
+    const fn header_length(&self) -> usize {
+        if self.revision >= 0x300 { 72 } else { 64 }
+    }
+    /// The size of this entry, section data included.
+    pub const fn length(&self) -> usize {
+        self.header_length() + self.error_data_length as usize
+    }
+    pub const fn fru_id(&self) -> Option<[u8; 16]> {
+        if self.validation_bits.fru_id_valid() {
+            Some(self.fru_id)
+        } else {
+            None
+        }
+    }
+    /// The FRU text, without the null padding.
+    pub fn fru_text(&self) -> Option<&str> {
+        if !self.validation_bits.fru_string_valid() {
+            return None;
+        }
+        let len = self.fru_text.iter().position(|&b| b == 0).unwrap_or(20);
+        core::str::from_utf8(&self.fru_text[..len]).ok()
+    }
+    /// The time the error happened, in the CPER timestamp format (BCD seconds, minutes, hours, a precise bit, then day, month, year, century).
+    pub const fn timestamp(&self) -> Option<u64> {
+        if self.revision >= 0x300 && self.validation_bits.timestamp_valid() {
+            Some(self.timestamp)
+        } else {
+            None
+        }
+    }
+    /// The raw section data.
+    pub const fn data(&self) -> &[u8] {
+        // SAFETY: entries only come from `GenericErrorDataEntryIter`, which checks that the header and the data lie within the block's `data_length`.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(self.header_length()),
+                self.error_data_length as usize,
+            )
+        }
+    }
+    /// The section data, decoded by its section type.
+    ///
+    /// A section too short for its type comes back as `Unknown`.
+    pub fn section(&self) -> CPERSection<'_> {
+        let data = self.data();
+        let ptr = data.as_ptr();
+        // SAFETY: every section type is checked against the data length before the cast.
+        unsafe {
+            match self.section_type {
+                PROCESSOR_GENERIC_SECTION
+                    if data.len() >= core::mem::size_of::<ProcessorGenericErrorSection>() =>
+                {
+                    CPERSection::ProcessorGeneric(&*(ptr as *const _))
+                }
+                PLATFORM_MEMORY_SECTION
+                    if data.len() >= core::mem::size_of::<MemoryErrorSection>() =>
+                {
+                    CPERSection::Memory(&*(ptr as *const _))
+                }
+                PCIE_SECTION if data.len() >= core::mem::size_of::<PCIeErrorSection>() => {
+                    CPERSection::PCIe(&*(ptr as *const _))
+                }
+                ARM_PROCESSOR_SECTION
+                    if data.len() >= core::mem::size_of::<ArmProcessorErrorSection>()
+                        && (*(ptr as *const ArmProcessorErrorSection)).section_length as usize
+                            <= data.len() =>
+                {
+                    CPERSection::ArmProcessor(&*(ptr as *const _))
+                }
+                FIRMWARE_ERROR_RECORD_REFERENCE_SECTION
+                    if data.len() >= core::mem::size_of::<FirmwareErrorRecordReference>() =>
+                {
+                    CPERSection::FirmwareErrorRecordReference {
+                        reference: &*(ptr as *const _),
+                        data,
+                    }
+                }
+                section_type => CPERSection::Unknown { section_type, data },
+            }
+        }
+    }
+}
+impl fmt::Display for GenericErrorDataEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.error_severity(), self.section())?;
+        if let Some(fru) = self.fru_text() {
+            write!(f, " (FRU: {})", fru)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the Generic Error Data Entries of a Generic Error Status Block.
+pub struct GenericErrorDataEntryIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _block: core::marker::PhantomData<&'a GenericErrorStatusBlock>,
+}
+impl<'a> Iterator for GenericErrorDataEntryIter<'a> {
+    type Item = &'a GenericErrorDataEntry;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 64 {
+            return None;
+        }
+        // Revision 0x300 and up have a 72-byte header, so look at the revision before making a reference to the whole header.
+        // SAFETY: the revision lies within the first 64 bytes, checked above.
+        let revision = unsafe { core::ptr::read_unaligned(self.next.add(20) as *const u16) };
+        if revision >= 0x300 && self.remaining < 72 {
+            self.remaining = 0;
+            return None;
+        }
+        // SAFETY: the header lies within the block's data, checked above.
+        let entry = unsafe { &*(self.next as *const GenericErrorDataEntry) };
+        let length = entry.length();
+        if length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.next = unsafe { self.next.add(length) };
+        self.remaining -= length;
+        Some(entry)
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## CPER Section
+///
+/// The section data of a Generic Error Data Entry, decoded by its section type.
+///
+/// `Display` gives a one-line summary of the valid fields, for error logs.
+pub enum CPERSection<'a> {
+    ProcessorGeneric(&'a ProcessorGenericErrorSection),
+    Memory(&'a MemoryErrorSection),
+    PCIe(&'a PCIeErrorSection),
+    ArmProcessor(&'a ArmProcessorErrorSection),
+    /// The reference and the whole section data, which holds the record identifier GUID too if it's 32 bytes or more.
+    ///
+    /// Use `record_identifier_guid()` to get the GUID.
+    FirmwareErrorRecordReference {
+        reference: &'a FirmwareErrorRecordReference,
+        data: &'a [u8],
+    },
+    /// A section type this library doesn't decode.
+    Unknown {
+        section_type: [u8; 16],
+        data: &'a [u8],
+    },
+}
+impl CPERSection<'_> {
+    /// The record identifier GUID of a Firmware Error Record Reference section, if the section is long enough to have one.
+    pub fn record_identifier_guid(&self) -> Option<[u8; 16]> {
+        match *self {
+            Self::FirmwareErrorRecordReference { data, .. } if data.len() >= 32 => {
+                let mut guid = [0; 16];
+                guid.copy_from_slice(&data[16..32]);
+                Some(guid)
+            }
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for CPERSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::ProcessorGeneric(section) => section.fmt(f),
+            Self::Memory(section) => section.fmt(f),
+            Self::PCIe(section) => section.fmt(f),
+            Self::ArmProcessor(section) => section.fmt(f),
+            Self::FirmwareErrorRecordReference { reference, .. } => {
+                write!(
+                    f,
+                    "firmware error record reference: type {}, record {:#x}",
+                    reference.firmware_error_record_type,
+                    { reference.record_identifier }
+                )?;
+                if let Some(guid) = self.record_identifier_guid() {
+                    f.write_str(", ")?;
+                    write_guid(f, &guid)?;
+                }
+                Ok(())
+            }
+            Self::Unknown { section_type, data } => {
+                f.write_str("section ")?;
+                write_guid(f, &section_type)?;
+                write!(f, " ({} bytes)", data.len())
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ProcessorGenericValidationBits(u64);
+impl ProcessorGenericValidationBits {
+    pub const fn processor_type_valid(&self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+    pub const fn processor_isa_valid(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+    pub const fn processor_error_type_valid(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+    pub const fn operation_valid(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+    pub const fn flags_valid(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+    pub const fn level_valid(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+    pub const fn cpu_version_valid(&self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+    pub const fn cpu_brand_string_valid(&self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+    pub const fn processor_id_valid(&self) -> bool {
+        self.0 & (1 << 8) != 0
+    }
+    pub const fn target_address_valid(&self) -> bool {
+        self.0 & (1 << 9) != 0
+    }
+    pub const fn requestor_id_valid(&self) -> bool {
+        self.0 & (1 << 10) != 0
+    }
+    pub const fn responder_id_valid(&self) -> bool {
+        self.0 & (1 << 11) != 0
+    }
+    pub const fn instruction_ip_valid(&self) -> bool {
+        self.0 & (1 << 12) != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Processor Generic Error Section
+///
+/// Every field is only meaningful if its bit in `validation_bits` is set.
+pub struct ProcessorGenericErrorSection {
+    pub validation_bits: ProcessorGenericValidationBits,
+    /// - **0** - IA32/X64
+    /// - **1** - IA64
+    /// - **2** - ARM
+    pub processor_type: u8,
+    /// - **0** - IA32
+    /// - **1** - IA64
+    /// - **2** - X64
+    /// - **3** - ARM A32/T32
+    /// - **4** - ARM A64
+    pub processor_isa: u8,
+    /// - **0x00** - Unknown
+    /// - **0x01** - Cache Error
+    /// - **0x02** - TLB Error
+    /// - **0x04** - Bus Error
+    /// - **0x08** - Micro-Architectural Error
+    pub processor_error_type: u8,
+    /// - **0** - Unknown or generic
+    /// - **1** - Data Read
+    /// - **2** - Data Write
+    /// - **3** - Instruction Execution
+    pub operation: u8,
+    /// - **Bit 0** - Restartable
+    /// - **Bit 1** - Precise IP
+    /// - **Bit 2** - Overflow
+    /// - **Bit 3** - Corrected
+    pub flags: u8,
+    /// The cache or TLB level of the error.
+    pub level: u8,
+    reserved: u16,
+    /// CPUID information (EAX, EBX, ECX, EDX of leaf 1 on IA32/X64; MIDR on ARM).
+    pub cpu_version_info: u64,
+    /// A null-terminated ASCII processor brand string.
+    pub cpu_brand_string: [u8; 128],
+    /// The processor's local APIC ID (IA32/X64), LID (IA64) or MPIDR (ARM).
+    pub processor_id: u64,
+    pub target_address: u64,
+    pub requestor_id: u64,
+    pub responder_id: u64,
+    pub instruction_ip: u64,
+}
+impl fmt::Display for ProcessorGenericErrorSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid = self.validation_bits;
+        f.write_str("processor generic error")?;
+        if valid.processor_error_type_valid() {
+            f.write_str(match self.processor_error_type {
+                0x01 => ": cache error",
+                0x02 => ": TLB error",
+                0x04 => ": bus error",
+                0x08 => ": micro-architectural error",
+                _ => ": unknown error",
+            })?;
+        }
+        if valid.operation_valid() {
+            f.write_str(match self.operation {
+                1 => ", data read",
+                2 => ", data write",
+                3 => ", instruction execution",
+                _ => ", generic operation",
+            })?;
+        }
+        if valid.level_valid() {
+            write!(f, ", level {}", self.level)?;
+        }
+        if valid.flags_valid() {
+            for (bit, name) in [
+                (0b0001, ", restartable"),
+                (0b0010, ", precise IP"),
+                (0b0100, ", overflow"),
+                (0b1000, ", corrected"),
+            ] {
+                if self.flags & bit != 0 {
+                    f.write_str(name)?;
+                }
+            }
+        }
+        if valid.processor_id_valid() {
+            write!(f, ", processor {:#x}", { self.processor_id })?;
+        }
+        if valid.target_address_valid() {
+            write!(f, ", target {:#x}", { self.target_address })?;
+        }
+        if valid.instruction_ip_valid() {
+            write!(f, ", IP {:#x}", { self.instruction_ip })?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct MemoryErrorValidationBits(u64);
+impl MemoryErrorValidationBits {
+    pub const fn error_status_valid(&self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+    pub const fn physical_address_valid(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+    pub const fn physical_address_mask_valid(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+    pub const fn node_valid(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+    pub const fn card_valid(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+    pub const fn module_valid(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+    pub const fn bank_valid(&self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+    pub const fn device_valid(&self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+    pub const fn row_valid(&self) -> bool {
+        self.0 & (1 << 8) != 0
+    }
+    pub const fn column_valid(&self) -> bool {
+        self.0 & (1 << 9) != 0
+    }
+    pub const fn bit_position_valid(&self) -> bool {
+        self.0 & (1 << 10) != 0
+    }
+    pub const fn requestor_id_valid(&self) -> bool {
+        self.0 & (1 << 11) != 0
+    }
+    pub const fn responder_id_valid(&self) -> bool {
+        self.0 & (1 << 12) != 0
+    }
+    pub const fn target_id_valid(&self) -> bool {
+        self.0 & (1 << 13) != 0
+    }
+    pub const fn memory_error_type_valid(&self) -> bool {
+        self.0 & (1 << 14) != 0
+    }
+    pub const fn rank_number_valid(&self) -> bool {
+        self.0 & (1 << 15) != 0
+    }
+    pub const fn card_handle_valid(&self) -> bool {
+        self.0 & (1 << 16) != 0
+    }
+    pub const fn module_handle_valid(&self) -> bool {
+        self.0 & (1 << 17) != 0
+    }
+    pub const fn extended_row_valid(&self) -> bool {
+        self.0 & (1 << 18) != 0
+    }
+    pub const fn bank_group_valid(&self) -> bool {
+        self.0 & (1 << 19) != 0
+    }
+    pub const fn bank_address_valid(&self) -> bool {
+        self.0 & (1 << 20) != 0
+    }
+    pub const fn chip_identification_valid(&self) -> bool {
+        self.0 & (1 << 21) != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryErrorType {
+    Unknown,
+    NoError,
+    SingleBitECC,
+    MultiBitECC,
+    SingleSymbolChipKillECC,
+    MultiSymbolChipKillECC,
+    MasterAbort,
+    TargetAbort,
+    ParityError,
+    WatchdogTimeout,
+    InvalidAddress,
+    MirrorBroken,
+    MemorySparing,
+    ScrubCorrectedError,
+    ScrubUncorrectedError,
+    PhysicalMemoryMapOutEvent,
+    Reserved(u8),
+}
+impl MemoryErrorType {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Unknown,
+            1 => Self::NoError,
+            2 => Self::SingleBitECC,
+            3 => Self::MultiBitECC,
+            4 => Self::SingleSymbolChipKillECC,
+            5 => Self::MultiSymbolChipKillECC,
+            6 => Self::MasterAbort,
+            7 => Self::TargetAbort,
+            8 => Self::ParityError,
+            9 => Self::WatchdogTimeout,
+            10 => Self::InvalidAddress,
+            11 => Self::MirrorBroken,
+            12 => Self::MemorySparing,
+            13 => Self::ScrubCorrectedError,
+            14 => Self::ScrubUncorrectedError,
+            15 => Self::PhysicalMemoryMapOutEvent,
+            other => Self::Reserved(other),
+        }
+    }
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Unknown | Self::Reserved(_) => "unknown",
+            Self::NoError => "no error",
+            Self::SingleBitECC => "single-bit ECC",
+            Self::MultiBitECC => "multi-bit ECC",
+            Self::SingleSymbolChipKillECC => "single-symbol ChipKill ECC",
+            Self::MultiSymbolChipKillECC => "multi-symbol ChipKill ECC",
+            Self::MasterAbort => "master abort",
+            Self::TargetAbort => "target abort",
+            Self::ParityError => "parity error",
+            Self::WatchdogTimeout => "watchdog timeout",
+            Self::InvalidAddress => "invalid address",
+            Self::MirrorBroken => "mirror broken",
+            Self::MemorySparing => "memory sparing",
+            Self::ScrubCorrectedError => "scrub corrected error",
+            Self::ScrubUncorrectedError => "scrub uncorrected error",
+            Self::PhysicalMemoryMapOutEvent => "physical memory map-out event",
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Error Section
+///
+/// Every field is only meaningful if its bit in `validation_bits` is set.
+pub struct MemoryErrorSection {
+    pub validation_bits: MemoryErrorValidationBits,
+    pub error_status: u64,
+    pub physical_address: u64,
+    pub physical_address_mask: u64,
+    pub node: u16,
+    pub card: u16,
+    pub module: u16,
+    pub bank: u16,
+    pub device: u16,
+    pub row: u16,
+    pub column: u16,
+    pub bit_position: u16,
+    pub requestor_id: u64,
+    pub responder_id: u64,
+    pub target_id: u64,
+    pub memory_error_type: u8,
+    /// - **Bit 0** - Bit 16 of the row number
+    /// - **Bit 1** - Bit 17 of the row number
+    /// - **Bits [[7:5]]** - Chip identification
+    pub extended: u8,
+    pub rank_number: u16,
+    /// SMBIOS Type 16 handle of the memory array.
+    pub card_handle: u16,
+    /// SMBIOS Type 17 handle of the memory device.
+    pub module_handle: u16,
+}
+impl MemoryErrorSection {
+    pub const fn memory_error_type(&self) -> Option<MemoryErrorType> {
+        if ({ self.validation_bits }).memory_error_type_valid() {
+            Some(MemoryErrorType::from_u8(self.memory_error_type))
+        } else {
+            None
+        }
+    }
+    /// The row number, with its extended bits 16 and 17 if they're valid.
+    pub const fn row(&self) -> u32 {
+        let mut row = self.row as u32;
+        if ({ self.validation_bits }).extended_row_valid() {
+            row |= ((self.extended & 0b11) as u32) << 16;
+        }
+        row
+    }
+}
+impl fmt::Display for MemoryErrorSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid = self.validation_bits;
+        f.write_str("memory error")?;
+        if let Some(error_type) = self.memory_error_type() {
+            write!(f, ": {}", error_type.name())?;
+        }
+        if valid.physical_address_valid() {
+            write!(f, " at {:#x}", { self.physical_address })?;
+        }
+        let fields = [
+            (valid.node_valid(), "node", self.node as u32),
+            (valid.card_valid(), "card", self.card as u32),
+            (valid.module_valid(), "module", self.module as u32),
+            (valid.rank_number_valid(), "rank", self.rank_number as u32),
+            (valid.bank_valid(), "bank", self.bank as u32),
+            (valid.device_valid(), "device", self.device as u32),
+            (valid.row_valid(), "row", self.row()),
+            (valid.column_valid(), "column", self.column as u32),
+            (valid.bit_position_valid(), "bit", self.bit_position as u32),
+        ];
+        let mut first = true;
+        for (is_valid, name, value) in fields {
+            if is_valid {
+                f.write_str(if first { " (" } else { " " })?;
+                write!(f, "{} {}", name, value)?;
+                first = false;
+            }
+        }
+        if !first {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct PCIeErrorValidationBits(u64);
+impl PCIeErrorValidationBits {
+    pub const fn port_type_valid(&self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+    pub const fn version_valid(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+    pub const fn command_status_valid(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+    pub const fn device_id_valid(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+    pub const fn device_serial_number_valid(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+    pub const fn bridge_control_status_valid(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+    pub const fn capability_structure_valid(&self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+    pub const fn aer_info_valid(&self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCIe Error Section Device ID
+pub struct PCIeErrorDeviceID {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub class_code: [u8; 3],
+    pub function_number: u8,
+    pub device_number: u8,
+    pub segment_number: u16,
+    /// The bus number of the device (the primary bus of a bridge).
+    pub root_port_primary_bus_number: u8,
+    /// The secondary bus number of a bridge.
+    pub secondary_bus_number: u8,
+    /// Bits [[15:3]] are the slot number.
+    pub slot_number: u16,
+    reserved: u8,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express Error Section
+///
+/// Every field is only meaningful if its bit in `validation_bits` is set.
+pub struct PCIeErrorSection {
+    pub validation_bits: PCIeErrorValidationBits,
+    /// - **0** - PCI Express End Point
+    /// - **1** - Legacy PCI End Point
+    /// - **4** - Root Port
+    /// - **5** - Upstream Switch Port
+    /// - **6** - Downstream Switch Port
+    /// - **7** - PCI Express to PCI/PCI-X Bridge
+    /// - **8** - PCI/PCI-X to PCI Express Bridge
+    /// - **9** - Root Complex Integrated Endpoint
+    /// - **10** - Root Complex Event Collector
+    pub port_type: u32,
+    /// Bits [[7:0]] are the minor version and bits [[15:8]] the major version, in BCD.
+    pub version: u32,
+    /// Bits [[15:0]] are the Command register and bits [[31:16]] the Status register.
+    pub command_status: u32,
+    reserved: u32,
+    pub device_id: PCIeErrorDeviceID,
+    pub device_serial_number: u64,
+    /// Bits [[15:0]] are the Secondary Status register and bits [[31:16]] the Bridge Control register.
+    pub bridge_control_status: u32,
+    /// The PCIe Capability Structure of the device.
+    pub capability_structure: [u8; 60],
+    /// The PCIe Advanced Error Reporting Extended Capability Structure of the device.
+    pub aer_info: [u8; 96],
+}
+impl PCIeErrorSection {
+    /// The AER Uncorrectable Error Status register.
+    pub const fn uncorrectable_error_status(&self) -> Option<u32> {
+        if !({ self.validation_bits }).aer_info_valid() {
+            return None;
+        }
+        Some(u32::from_le_bytes([
+            self.aer_info[4],
+            self.aer_info[5],
+            self.aer_info[6],
+            self.aer_info[7],
+        ]))
+    }
+    /// The AER Correctable Error Status register.
+    pub const fn correctable_error_status(&self) -> Option<u32> {
+        if !({ self.validation_bits }).aer_info_valid() {
+            return None;
+        }
+        Some(u32::from_le_bytes([
+            self.aer_info[16],
+            self.aer_info[17],
+            self.aer_info[18],
+            self.aer_info[19],
+        ]))
+    }
+}
+impl fmt::Display for PCIeErrorSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid = self.validation_bits;
+        f.write_str("PCIe error")?;
+        if valid.port_type_valid() {
+            f.write_str(match self.port_type {
+                0 => ": endpoint",
+                1 => ": legacy endpoint",
+                4 => ": root port",
+                5 => ": upstream switch port",
+                6 => ": downstream switch port",
+                7 => ": PCIe to PCI/PCI-X bridge",
+                8 => ": PCI/PCI-X to PCIe bridge",
+                9 => ": root complex integrated endpoint",
+                10 => ": root complex event collector",
+                _ => ": unknown port type",
+            })?;
+        }
+        if valid.device_id_valid() {
+            let id = self.device_id;
+            write!(
+                f,
+                " {:04x}:{:02x}:{:02x}.{} ({:04x}:{:04x})",
+                { id.segment_number },
+                id.root_port_primary_bus_number,
+                id.device_number,
+                id.function_number,
+                { id.vendor_id },
+                { id.device_id }
+            )?;
+        }
+        if valid.command_status_valid() {
+            write!(f, ", status {:#06x}", self.command_status >> 16)?;
+        }
+        if let Some(status) = self.uncorrectable_error_status() {
+            write!(f, ", uncorrectable {:#010x}", status)?;
+        }
+        if let Some(status) = self.correctable_error_status() {
+            write!(f, ", correctable {:#010x}", status)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ArmProcessorValidationBits(u32);
+impl ArmProcessorValidationBits {
+    pub const fn mpidr_valid(&self) -> bool {
+        self.0 & 0b0001 != 0
+    }
+    pub const fn error_affinity_level_valid(&self) -> bool {
+        self.0 & 0b0010 != 0
+    }
+    pub const fn running_state_valid(&self) -> bool {
+        self.0 & 0b0100 != 0
+    }
+    pub const fn vendor_specific_info_valid(&self) -> bool {
+        self.0 & 0b1000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Arm Processor Error Section
+///
+/// Followed by `err_info_num` Arm Processor Error Information structures, `context_info_num` context information structures, and vendor specific data.
+pub struct ArmProcessorErrorSection {
+    pub validation_bits: ArmProcessorValidationBits,
+    /// The number of Arm Processor Error Information structures.
+    pub err_info_num: u16,
+    /// The number of context information structures.
+    pub context_info_num: u16,
+    /// Length, in bytes, of the whole section.
+    pub section_length: u32,
+    /// The affinity level (0 to 3) of the error; an error at a higher level affects more processors.
+    pub error_affinity_level: u8,
+    reserved: [u8; 3],
+    pub mpidr_el1: u64,
+    pub midr_el1: u64,
+    /// Bit 0 is set if the processor is running (not in a low-power state).
+    pub running_state: u32,
+    /// The PSCI state of the processor, if it isn't running.
+    pub psci_state: u32,
+    pub error_information: [ArmProcessorErrorInformation; 0],
+}
+impl ArmProcessorErrorSection {
+    /// The error information structures (the ones that fit in `section_length`).
+    pub const fn error_information(&self) -> &[ArmProcessorErrorInformation] {
+        let fit = (self.section_length as usize).saturating_sub(40)
+            / core::mem::size_of::<ArmProcessorErrorInformation>();
+        let count = if (self.err_info_num as usize) < fit {
+            self.err_info_num as usize
+        } else {
+            fit
+        };
+        // SAFETY: the structures lie within `section_length`, which bounds the count,
+        // and `section()` only hands out sections whose `section_length` fits in their data.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(40) as *const ArmProcessorErrorInformation,
+                count,
+            )
+        }
+    }
+}
+impl fmt::Display for ArmProcessorErrorSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid = self.validation_bits;
+        f.write_str("Arm processor error")?;
+        if valid.mpidr_valid() {
+            write!(f, ": MPIDR {:#x}", { self.mpidr_el1 })?;
+        }
+        if valid.error_affinity_level_valid() {
+            write!(f, ", affinity level {}", self.error_affinity_level)?;
+        }
+        for info in self.error_information() {
+            write!(f, "; {}", info)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Arm Processor Error Information Structure
+pub struct ArmProcessorErrorInformation {
+    pub version: u8,
+    /// 32
+    pub length: u8,
+    /// - **Bit 0** - Multiple error valid
+    /// - **Bit 1** - Flags valid
+    /// - **Bit 2** - Error information valid
+    /// - **Bit 3** - Virtual fault address valid
+    /// - **Bit 4** - Physical fault address valid
+    pub validation_bits: u16,
+    /// - **0** - Cache error
+    /// - **1** - TLB error
+    /// - **2** - Bus error
+    /// - **3** - Micro-architectural error
+    pub r#type: u8,
+    /// The number of times the error happened (0 is a single error, 1 is multiple errors of unknown count).
+    pub multiple_error: u16,
+    /// - **Bit 0** - First error captured
+    /// - **Bit 1** - Last error captured
+    /// - **Bit 2** - Propagated
+    /// - **Bit 3** - Overflow
+    pub flags: u8,
+    /// Details of the error, depending on `type`.
+    pub error_information: u64,
+    pub virtual_fault_address: u64,
+    pub physical_fault_address: u64,
+}
+impl fmt::Display for ArmProcessorErrorInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid = self.validation_bits;
+        f.write_str(match self.r#type {
+            0 => "cache error",
+            1 => "TLB error",
+            2 => "bus error",
+            3 => "micro-architectural error",
+            _ => "unknown error",
+        })?;
+        if valid & 0b00010 != 0 {
+            for (bit, name) in [
+                (0b0001, " first"),
+                (0b0010, " last"),
+                (0b0100, " propagated"),
+                (0b1000, " overflow"),
+            ] {
+                if self.flags & bit != 0 {
+                    f.write_str(name)?;
+                }
+            }
+        }
+        if valid & 0b10000 != 0 {
+            write!(f, " at PA {:#x}", { self.physical_fault_address })?;
+        } else if valid & 0b01000 != 0 {
+            write!(f, " at VA {:#x}", { self.virtual_fault_address })?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Firmware Error Record Reference Section
+///
+/// Points at an error record kept by firmware, instead of carrying the error itself.
+pub struct FirmwareErrorRecordReference {
+    /// - **0** - IPF SAL Error Record
+    /// - **1** - SOC Firmware Error Record Type1 (Legacy CrashLog support)
+    /// - **2** - SOC Firmware Error Record Type2
+    pub firmware_error_record_type: u8,
+    pub revision: u8,
+    reserved: [u8; 6],
+    pub record_identifier: u64,
+    // Revision 2 and up follow this with a 16-byte record identifier GUID, which identifies the format of the record;
+    // it's read through `CPERSection::record_identifier_guid()` since older sections stop here.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A block with one revision 0x300 entry of `section_type`, whose section data is `data`.
+    fn status_block<'a>(
+        buffer: &'a mut [u64; 32],
+        section_type: [u8; 16],
+        data: &[u8],
+    ) -> &'a GenericErrorStatusBlock {
+        // SAFETY: the buffer is 256 bytes.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 256) };
+        bytes[12..16].copy_from_slice(&(72 + data.len() as u32).to_le_bytes());
+        let entry = &mut bytes[20..];
+        entry[..16].copy_from_slice(&section_type);
+        entry[20..22].copy_from_slice(&0x300u16.to_le_bytes());
+        entry[24..28].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        entry[72..][..data.len()].copy_from_slice(data);
+        // SAFETY: the block is packed, and lies within the buffer.
+        unsafe { &*(buffer.as_ptr() as *const GenericErrorStatusBlock) }
+    }
+
+    /// An Arm Processor Error Section claiming `err_info_num` error information structures in `section_length` bytes.
+    fn arm(err_info_num: u16, section_length: u32) -> [u8; 104] {
+        let mut data = [0; 104];
+        data[4..6].copy_from_slice(&err_info_num.to_le_bytes());
+        data[8..12].copy_from_slice(&section_length.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn arm_error_information() {
+        let mut buffer = [0; 32];
+        let block = status_block(&mut buffer, ARM_PROCESSOR_SECTION, &arm(5, 104));
+        let entry = block.entries().next().unwrap();
+        let CPERSection::ArmProcessor(section) = entry.section() else {
+            panic!("not an Arm processor section");
+        };
+        assert_eq!(section.error_information().len(), 2);
+
+        let mut buffer = [0; 32];
+        let block = status_block(&mut buffer, ARM_PROCESSOR_SECTION, &arm(5, 200));
+        let entry = block.entries().next().unwrap();
+        assert!(matches!(entry.section(), CPERSection::Unknown { .. }));
+    }
+
+    #[test]
+    fn firmware_error_record_reference() {
+        let mut data = [0; 32];
+        data[1] = 2;
+        data[8] = 0x42;
+        data[16] = 0xAA;
+
+        let mut buffer = [0; 32];
+        let block = status_block(
+            &mut buffer,
+            FIRMWARE_ERROR_RECORD_REFERENCE_SECTION,
+            &data[..16],
+        );
+        let section = block.entries().next().unwrap().section();
+        let CPERSection::FirmwareErrorRecordReference { reference, .. } = section else {
+            panic!("not a firmware error record reference");
+        };
+        assert_eq!({ reference.record_identifier }, 0x42);
+        assert_eq!(section.record_identifier_guid(), None);
+
+        let mut buffer = [0; 32];
+        let block = status_block(&mut buffer, FIRMWARE_ERROR_RECORD_REFERENCE_SECTION, &data);
+        let section = block.entries().next().unwrap().section();
+        assert_eq!(section.record_identifier_guid().unwrap()[0], 0xAA);
+    }
+
+    #[test]
+    fn entries_fit_in_the_block() {
+        let mut buffer = [0; 32];
+        let block = status_block(&mut buffer, ARM_PROCESSOR_SECTION, &[]);
+        assert_eq!(block.entries().count(), 1);
+
+        // SAFETY: the buffer is 256 bytes.
+        unsafe { *(buffer.as_mut_ptr() as *mut u8).add(12) = 64 };
+        // SAFETY: the block is packed, and lies within the buffer.
+        let block = unsafe { &*(buffer.as_ptr() as *const GenericErrorStatusBlock) };
+        assert_eq!(block.entries().count(), 0);
+    }
+}
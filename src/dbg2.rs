@@ -0,0 +1,295 @@
+use crate::{GenericAddressStructure, SDTHeader, spcr::SerialInterfaceType};
+use core::ffi::CStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The kind of transport a debug port uses.
+pub enum DebugPortType {
+    /// 0x8000 - Serial
+    Serial,
+    /// 0x8001 - 1394
+    IEEE1394,
+    /// 0x8002 - USB
+    USB,
+    /// 0x8003 - Net
+    Net,
+}
+impl DebugPortType {
+    pub const fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            0x8000 => Some(Self::Serial),
+            0x8001 => Some(Self::IEEE1394),
+            0x8002 => Some(Self::USB),
+            0x8003 => Some(Self::Net),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// 1394 debug port subtypes.
+pub enum IEEE1394Subtype {
+    /// IEEE1394 Standard Host Controller Interface.
+    OHCI,
+    Unknown(u16),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// USB debug port subtypes.
+pub enum USBSubtype {
+    /// XHCI-compliant controller with debug interface.
+    XHCI,
+    /// EHCI-compliant controller with debug interface.
+    EHCI,
+    Unknown(u16),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Debug Port
+///
+/// The port type and subtype of a Debug Device Information structure, decoded together.
+pub enum DebugPort {
+    Serial(SerialInterfaceType),
+    IEEE1394(IEEE1394Subtype),
+    USB(USBSubtype),
+    /// For network debug ports, the subtype is the PCI vendor ID of the network controller.
+    Net {
+        vendor_id: u16,
+    },
+    /// A port type this library doesn't know about (or a reserved one).
+    Unknown {
+        port_type: u16,
+        port_subtype: u16,
+    },
+}
+impl DebugPort {
+    pub const fn port_type(&self) -> Option<DebugPortType> {
+        match self {
+            Self::Serial(_) => Some(DebugPortType::Serial),
+            Self::IEEE1394(_) => Some(DebugPortType::IEEE1394),
+            Self::USB(_) => Some(DebugPortType::USB),
+            Self::Net { .. } => Some(DebugPortType::Net),
+            Self::Unknown { .. } => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Debug Device Information Structure
+///
+/// Describes one debug port. The variable-length parts (base address registers, address sizes, namespace string and OEM data)
+/// are found through the offset fields, which are relative to the start of this structure.
+pub struct DebugDeviceInformation {
+    /// 0 - Revision of the Debug Device Information structure.
+    pub revision: u8,
+    /// Length of this structure, including the base address registers, address sizes, namespace string and OEM data.
+    pub length: u16,
+    /// The number of Generic Address Registers in use on this debug device.
+    pub number_of_generic_address_registers: u8,
+    /// Length, in bytes, of the namespace string, including the null terminator.
+    pub namespace_string_length: u16,
+    /// Offset, in bytes, from the beginning of this structure to the namespace string.
+    pub namespace_string_offset: u16,
+    /// Length, in bytes, of the OEM data block.
+    pub oem_data_length: u16,
+    /// Offset, in bytes, to the OEM data block from the beginning of this structure. 0 if there is no OEM data.
+    pub oem_data_offset: u16,
+    /// Debug port type:
+    ///
+    /// - **0x8000** - Serial
+    /// - **0x8001** - 1394
+    /// - **0x8002** - USB
+    /// - **0x8003** - Net
+    ///
+    /// The rest of the values are reserved.
+    pub port_type: u16,
+    /// Debug port subtype, depending on `port_type`.
+    pub port_subtype: u16,
+    reserved: u16,
+    /// Offset, in bytes, from the beginning of this structure to the BaseAddressRegister array.
+    pub base_address_register_offset: u16,
+    /// Offset, in bytes, from the beginning of this structure to the AddressSize array.
+    pub address_size_offset: u16,
+}
+impl DebugDeviceInformation {
+    pub const fn port(&self) -> DebugPort {
+        let port_subtype = self.port_subtype;
+        match DebugPortType::from_u16(self.port_type) {
+            Some(DebugPortType::Serial) => {
+                DebugPort::Serial(SerialInterfaceType::from_u16(port_subtype))
+            }
+            Some(DebugPortType::IEEE1394) => DebugPort::IEEE1394(match port_subtype {
+                0x0000 => IEEE1394Subtype::OHCI,
+                other => IEEE1394Subtype::Unknown(other),
+            }),
+            Some(DebugPortType::USB) => DebugPort::USB(match port_subtype {
+                0x0000 => USBSubtype::XHCI,
+                0x0001 => USBSubtype::EHCI,
+                other => USBSubtype::Unknown(other),
+            }),
+            Some(DebugPortType::Net) => DebugPort::Net {
+                vendor_id: port_subtype,
+            },
+            None => DebugPort::Unknown {
+                port_type: self.port_type,
+                port_subtype,
+            },
+        }
+    }
+    // Whether `length` bytes at `offset` (from the start of this structure) lie within it.
+    const fn fits(&self, offset: u16, length: usize) -> bool {
+        offset as usize + length <= self.length as usize
+    }
+    /// The debug device's registers.
+    pub const fn base_address_registers(&self) -> &[GenericAddressStructure] {
+        let count = self.number_of_generic_address_registers as usize;
+        if !self.fits(
+            self.base_address_register_offset,
+            count * core::mem::size_of::<GenericAddressStructure>(),
+        ) {
+            return &[];
+        }
+        // SAFETY: the registers lie within the structure's length (checked above), and GenericAddressStructure is packed.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(self.base_address_register_offset as usize)
+                    as *const GenericAddressStructure,
+                count,
+            )
+        }
+    }
+    /// The size of each address range in `base_address_registers`, in the same order.
+    ///
+    /// Nothing says this array is 4-byte aligned, so it can't be handed out as a `&[u32]`; each entry is read on its own instead.
+    pub fn address_size(&self, index: usize) -> Option<u32> {
+        if index >= self.number_of_generic_address_registers as usize
+            || !self.fits(self.address_size_offset, (index + 1) * 4)
+        {
+            return None;
+        }
+        // SAFETY: the entry lies within the structure's length, checked above.
+        unsafe {
+            Some(core::ptr::read_unaligned(
+                (self as *const _ as *const u8).add(self.address_size_offset as usize + index * 4)
+                    as *const u32,
+            ))
+        }
+    }
+    /// Each register paired with the size of its address range.
+    pub fn registers(&self) -> impl Iterator<Item = (GenericAddressStructure, u32)> + '_ {
+        self.base_address_registers()
+            .iter()
+            .enumerate()
+            .map(move |(i, gas)| (*gas, self.address_size(i).unwrap_or(0)))
+    }
+    /// The full namespace path of the debug device (for example "\_SB.PCI0.USB0"), or "." if it has no namespace device.
+    ///
+    /// Returns `None` if the string doesn't fit in the structure or isn't null-terminated.
+    pub const fn namespace_string(&self) -> Option<&CStr> {
+        let length = self.namespace_string_length as usize;
+        if !self.fits(self.namespace_string_offset, length) {
+            return None;
+        }
+        // SAFETY: the string lies within the structure's length, checked above.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(self.namespace_string_offset as usize),
+                length,
+            )
+        };
+        match CStr::from_bytes_until_nul(bytes) {
+            Ok(string) => Some(string),
+            Err(_) => None,
+        }
+    }
+    /// Optional, variable-length OEM-specific data.
+    pub const fn oem_data(&self) -> &[u8] {
+        let length = self.oem_data_length as usize;
+        if self.oem_data_offset == 0 || !self.fits(self.oem_data_offset, length) {
+            return &[];
+        }
+        // SAFETY: the data lies within the structure's length, checked above.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(self.oem_data_offset as usize),
+                length,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Debug Port Table 2 (DBG2)
+///
+/// Describes the debug ports the platform makes available to a kernel debugger, each with its own transport, registers and namespace device.
+pub struct DebugPortTable2 {
+    /// - **Signature** - "DBG2"
+    /// - **Revision** - 0
+    pub header: SDTHeader,
+    /// Offset, in bytes, from the beginning of this table to the first Debug Device Information structure entry.
+    pub offset_dbg_device_info: u32,
+    /// Indicates the number of Debug Device Information structure entries.
+    pub number_dbg_device_info: u32,
+}
+impl DebugPortTable2 {
+    pub const fn debug_devices(&self) -> DebugDeviceInformationIter<'_> {
+        let offset = self.offset_dbg_device_info as usize;
+        let length = self.header.length as usize;
+        if offset > length {
+            return DebugDeviceInformationIter {
+                next: self as *const _ as *const u8,
+                remaining: 0,
+                bytes_left: 0,
+                _table: core::marker::PhantomData,
+            };
+        }
+        DebugDeviceInformationIter {
+            // SAFETY: the offset is within the table's length, checked above.
+            next: unsafe { (self as *const _ as *const u8).add(offset) },
+            remaining: self.number_dbg_device_info,
+            bytes_left: length - offset,
+            _table: core::marker::PhantomData,
+        }
+    }
+    /// Picks a debug device for a debugger: the first device of the first type in `order` that has one.
+    ///
+    /// For example, `&[DebugPortType::Net, DebugPortType::USB, DebugPortType::Serial]` prefers network debugging and falls back to serial.
+    pub fn pick(&self, order: &[DebugPortType]) -> Option<&DebugDeviceInformation> {
+        order.iter().find_map(|&kind| {
+            self.debug_devices()
+                .find(|dev| dev.port().port_type() == Some(kind))
+        })
+    }
+}
+
+/// Iterator over the Debug Device Information structures of a DBG2, following each structure's `length`.
+///
+/// Stops after `number_dbg_device_info` structures, or at the first one that doesn't fit in the table.
+pub struct DebugDeviceInformationIter<'a> {
+    next: *const u8,
+    remaining: u32,
+    bytes_left: usize,
+    _table: core::marker::PhantomData<&'a DebugPortTable2>,
+}
+impl<'a> Iterator for DebugDeviceInformationIter<'a> {
+    type Item = &'a DebugDeviceInformation;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 || self.bytes_left < core::mem::size_of::<DebugDeviceInformation>() {
+            return None;
+        }
+        // SAFETY: the structure's fixed part lies within the table, checked above.
+        let device = unsafe { &*(self.next as *const DebugDeviceInformation) };
+        let length = device.length as usize;
+        if length < core::mem::size_of::<DebugDeviceInformation>() || length > self.bytes_left {
+            // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+            self.remaining = 0;
+            return None;
+        }
+        // SAFETY: the whole structure lies within the table, checked above.
+        self.next = unsafe { self.next.add(length) };
+        self.bytes_left -= length;
+        self.remaining -= 1;
+        Some(device)
+    }
+}
@@ -0,0 +1,433 @@
+use crate::AMLEvaluator;
+
+#[derive(Copy, Clone)]
+/// ## Device Status (_STA)
+///
+/// If a device has no _STA object, OSPM assumes all of bits 0 to 3 are set (`DeviceStatus::DEFAULT`).
+pub struct DeviceStatus(u32);
+impl DeviceStatus {
+    /// What OSPM uses for a device without a _STA object: present, enabled, shown in UI and functioning.
+    pub const DEFAULT: Self = Self(0b01111);
+
+    pub const fn new(sta: u32) -> Self {
+        Self(sta)
+    }
+    /// Set if the device is present.
+    pub const fn present(&self) -> bool {
+        self.0 & 0b00001 != 0
+    }
+    /// Set if the device is enabled and decoding its resources.
+    pub const fn enabled(&self) -> bool {
+        self.0 & 0b00010 != 0
+    }
+    /// Set if the device should be shown in the UI.
+    pub const fn shown_in_ui(&self) -> bool {
+        self.0 & 0b00100 != 0
+    }
+    /// Set if the device is functioning properly (cleared if the device failed its diagnostics).
+    pub const fn functioning(&self) -> bool {
+        self.0 & 0b01000 != 0
+    }
+    /// Set if a battery is present.  Only meaningful for Control Method Battery devices.
+    pub const fn battery_present(&self) -> bool {
+        self.0 & 0b10000 != 0
+    }
+    /// Whether OSPM should keep enumerating the device's children.
+    ///
+    /// A device that is not present but functioning can still have children that are present (a dock, for example), so only a device that is neither present nor functioning stops the walk.
+    pub const fn enumerate_children(&self) -> bool {
+        self.present() || self.functioning()
+    }
+    // The rest of the bits are reserved.
+}
+
+/// Decodes a compressed EISA ID (the integer an ASL `EisaId("PNP0A08")` compiles to) into its 7-character form, e.g. "PNP0A08".
+///
+/// The integer is the value as AML reads it, i.e. the 4 bytes of the EISA ID read little-endian.
+pub const fn eisa_id_to_str(id: u32) -> [u8; 7] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let id = id.swap_bytes();
+    [
+        b'@' + ((id >> 26) & 0x1F) as u8,
+        b'@' + ((id >> 21) & 0x1F) as u8,
+        b'@' + ((id >> 16) & 0x1F) as u8,
+        HEX[((id >> 12) & 0xF) as usize],
+        HEX[((id >> 8) & 0xF) as usize],
+        HEX[((id >> 4) & 0xF) as usize],
+        HEX[(id & 0xF) as usize],
+    ]
+}
+
+/// Compresses a 7-character EISA ID ("PNP0A08") into its integer form, the reverse of `eisa_id_to_str`.
+///
+/// Returns `None` if the string isn't three uppercase letters followed by four hex digits.
+pub const fn str_to_eisa_id(id: &[u8]) -> Option<u32> {
+    if id.len() != 7 {
+        return None;
+    }
+    let mut value = 0u32;
+    let mut i = 0;
+    while i < 3 {
+        if !id[i].is_ascii_uppercase() {
+            return None;
+        }
+        value = value << 5 | (id[i] - b'@') as u32;
+        i += 1;
+    }
+    while i < 7 {
+        let nibble = match id[i] {
+            b'0'..=b'9' => id[i] - b'0',
+            b'A'..=b'F' => id[i] - b'A' + 10,
+            b'a'..=b'f' => id[i] - b'a' + 10,
+            _ => return None,
+        };
+        value = value << 4 | nibble as u32;
+        i += 1;
+    }
+    Some(value.swap_bytes())
+}
+
+#[derive(Copy, Clone)]
+/// ## Device Identification (_HID/_CID)
+///
+/// _HID and each _CID entry evaluate to either a compressed EISA ID integer or a string ("ACPI0007", "PNP0A08", "VEN_1234"...).
+pub enum DeviceId<'a> {
+    EisaId(u32),
+    String(&'a [u8]),
+}
+impl DeviceId<'_> {
+    /// Returns true if this ID is `id`, comparing EISA IDs by their decoded form.
+    pub fn matches(&self, id: &[u8]) -> bool {
+        match *self {
+            DeviceId::EisaId(eisa) => eisa_id_to_str(eisa) == id,
+            DeviceId::String(s) => s == id,
+        }
+    }
+}
+
+/// Splits a PCI _ADR value into its (device, function) pair.
+///
+/// A function of 0xFFFF means "all functions of the device".
+pub const fn pci_adr(adr: u64) -> (u16, u16) {
+    ((adr >> 16) as u16, adr as u16)
+}
+
+#[derive(Copy, Clone)]
+/// ## Device Unique ID (_UID)
+///
+/// _UID evaluates to either an integer or a string.
+pub enum DeviceUid<'a> {
+    Integer(u64),
+    String(&'a [u8]),
+}
+
+#[derive(Copy, Clone)]
+/// ## ACPI Device Information
+///
+/// What OSPM knows about one device of the namespace: its identification objects, already evaluated.
+pub struct DeviceInfo<'a> {
+    /// The absolute namespace path of the device, e.g. "\\_SB.PCI0.LPCB.EC0".  It stays the same from boot to boot, so it's a stable name for the device.
+    pub path: &'a [u8],
+    /// _STA, or `DeviceStatus::DEFAULT` if the device doesn't have one.
+    pub status: DeviceStatus,
+    pub hid: Option<DeviceId<'a>>,
+    pub cids: &'a [DeviceId<'a>],
+    pub uid: Option<DeviceUid<'a>>,
+    /// _ADR, for devices on a bus that enumerates them (PCI, USB...).
+    pub adr: Option<u64>,
+    /// _DDN, the DOS device name ("COM1", "LPT1"...).
+    pub ddn: Option<&'a [u8]>,
+}
+impl DeviceInfo<'_> {
+    /// Returns true if the _HID or one of the _CIDs of the device is `id`.
+    pub fn matches(&self, id: &[u8]) -> bool {
+        self.hid.is_some_and(|hid| hid.matches(id)) || self.cids.iter().any(|cid| cid.matches(id))
+    }
+}
+
+/// The longest _HID, _CID, _UID or _DDN string `device_info` keeps; longer ones are cut off.
+pub const MAX_ID_LENGTH: usize = 64;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors evaluating the objects of a device can run into.
+pub enum DeviceError<E> {
+    /// One of the device's methods failed.
+    Method(E),
+    /// The namespace path of the device is too long to append an object name to.
+    PathTooLong,
+}
+
+// Builds the absolute path of object `name` (e.g. "_HID") of the device at `path`.
+fn object_path(path: &[u8], name: &[u8; 4]) -> Option<([u8; 256], usize)> {
+    let mut out = [0u8; 256];
+    let length = path.len() + 5;
+    if length > out.len() {
+        return None;
+    }
+    out[..path.len()].copy_from_slice(path);
+    out[path.len()] = b'.';
+    out[path.len() + 1..length].copy_from_slice(name);
+    Some((out, length))
+}
+
+// What an identification object evaluated to: an integer, or a string of the given length.
+enum IdValue {
+    Integer(u64),
+    String(usize),
+}
+
+fn evaluate_id<E: AMLEvaluator>(
+    evaluator: &mut E,
+    path: &[u8],
+    name: &[u8; 4],
+    out: &mut [u8; MAX_ID_LENGTH],
+) -> Result<Option<IdValue>, DeviceError<E::Error>> {
+    let (object, length) = object_path(path, name).ok_or(DeviceError::PathTooLong)?;
+    let object = &object[..length];
+    if let Some(length) = evaluator
+        .evaluate_buffer(object, &[], out)
+        .map_err(DeviceError::Method)?
+    {
+        return Ok(Some(IdValue::String(length.min(MAX_ID_LENGTH))));
+    }
+    Ok(evaluator
+        .evaluate(object, &[])
+        .map_err(DeviceError::Method)?
+        .map(IdValue::Integer))
+}
+
+// An _HID or _CID value as a DeviceId.  `AMLEvaluator::evaluate` gives 0 for results that aren't integers (a _CID package, say), and no EISA ID is 0.
+fn device_id(value: Option<IdValue>, string: &[u8]) -> Option<DeviceId<'_>> {
+    match value? {
+        IdValue::Integer(0) => None,
+        IdValue::Integer(id) => Some(DeviceId::EisaId(id as u32)),
+        IdValue::String(length) => Some(DeviceId::String(&string[..length])),
+    }
+}
+
+/// Evaluates the _STA of the device at the absolute namespace path `path`, or gives `DeviceStatus::DEFAULT` if it doesn't have one.
+pub fn device_status<E: AMLEvaluator>(
+    evaluator: &mut E,
+    path: &[u8],
+) -> Result<DeviceStatus, DeviceError<E::Error>> {
+    let (object, length) = object_path(path, b"_STA").ok_or(DeviceError::PathTooLong)?;
+    Ok(
+        match evaluator
+            .evaluate(&object[..length], &[])
+            .map_err(DeviceError::Method)?
+        {
+            Some(sta) => DeviceStatus::new(sta as u32),
+            None => DeviceStatus::DEFAULT,
+        },
+    )
+}
+
+/// Evaluates the identification objects (_HID, _CID, _UID, _ADR and _DDN) of the device at `path`, whose _STA was `status`, and hands them to `f`.
+///
+/// Only a _CID with a single ID is picked up; `AMLEvaluator` has no way to return a package of them.
+pub fn device_info<E: AMLEvaluator, R, F: FnOnce(&DeviceInfo) -> R>(
+    evaluator: &mut E,
+    path: &[u8],
+    status: DeviceStatus,
+    f: F,
+) -> Result<R, DeviceError<E::Error>> {
+    let mut hid = [0; MAX_ID_LENGTH];
+    let mut cid = [0; MAX_ID_LENGTH];
+    let mut uid = [0; MAX_ID_LENGTH];
+    let mut ddn = [0; MAX_ID_LENGTH];
+    let hid_value = evaluate_id(evaluator, path, b"_HID", &mut hid)?;
+    let cid_value = evaluate_id(evaluator, path, b"_CID", &mut cid)?;
+    let uid_value = evaluate_id(evaluator, path, b"_UID", &mut uid)?;
+    let ddn_value = evaluate_id(evaluator, path, b"_DDN", &mut ddn)?;
+    let (object, length) = object_path(path, b"_ADR").ok_or(DeviceError::PathTooLong)?;
+    let adr = evaluator
+        .evaluate(&object[..length], &[])
+        .map_err(DeviceError::Method)?;
+
+    let cids = device_id(cid_value, &cid);
+    Ok(f(&DeviceInfo {
+        path,
+        status,
+        hid: device_id(hid_value, &hid),
+        cids: cids.as_slice(),
+        uid: match uid_value {
+            Some(IdValue::Integer(uid)) => Some(DeviceUid::Integer(uid)),
+            Some(IdValue::String(length)) => Some(DeviceUid::String(&uid[..length])),
+            None => None,
+        },
+        adr,
+        ddn: match ddn_value {
+            Some(IdValue::String(length)) => Some(&ddn[..length]),
+            _ => None,
+        },
+    }))
+}
+
+/// ## ACPI Device Namespace
+///
+/// The device tree of the namespace built from the DSDT and SSDTs, as an AML interpreter sees it.
+/// The interpreter implements the walk over the Device objects, and `for_each_device` evaluates their objects through an `AMLEvaluator` the way OSPM has to.
+pub trait DeviceNamespace {
+    /// However the interpreter refers to a node of the namespace.
+    type Node: Copy;
+    /// The node the walk starts from, usually \_SB.
+    fn root(&mut self) -> Self::Node;
+    /// The `index`th Device object right under `parent`, or `None` once there are no more.
+    fn child(&mut self, parent: Self::Node, index: usize) -> Option<Self::Node>;
+    /// The absolute namespace path of `node`, e.g. "\\_SB.PCI0".
+    fn path(&mut self, node: Self::Node) -> &[u8];
+
+    /// Calls `f` on every present device under the root, parents before their children.
+    ///
+    /// A device that isn't present is skipped, and its children are only walked if `DeviceStatus::enumerate_children` allows it.
+    fn for_each_device<E: AMLEvaluator, F: FnMut(Self::Node, &DeviceInfo)>(
+        &mut self,
+        evaluator: &mut E,
+        mut f: F,
+    ) -> Result<(), DeviceError<E::Error>>
+    where
+        Self: Sized,
+    {
+        let root = self.root();
+        walk(self, evaluator, root, &mut f)
+    }
+}
+
+fn walk<N: DeviceNamespace, E: AMLEvaluator, F: FnMut(N::Node, &DeviceInfo)>(
+    namespace: &mut N,
+    evaluator: &mut E,
+    parent: N::Node,
+    f: &mut F,
+) -> Result<(), DeviceError<E::Error>> {
+    let mut index = 0;
+    while let Some(node) = namespace.child(parent, index) {
+        index += 1;
+        let path = namespace.path(node);
+        let status = device_status(evaluator, path)?;
+        if status.present() {
+            device_info(evaluator, path, status, |info| f(node, info))?;
+        }
+        if status.enumerate_children() {
+            walk(namespace, evaluator, node, f)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (path, parent) of every device, in namespace order.
+    const TREE: [(&[u8], usize); 8] = [
+        (b"\\_SB", 0),
+        (b"\\_SB.PCI0", 0),
+        (b"\\_SB.PCI0.LPCB", 1),
+        (b"\\_SB.PCI0.LPCB.EC0", 2),
+        (b"\\_SB.DOCK", 0),
+        (b"\\_SB.DOCK.DEV0", 4),
+        (b"\\_SB.GONE", 0),
+        (b"\\_SB.GONE.KID0", 6),
+    ];
+
+    struct Namespace;
+    impl DeviceNamespace for Namespace {
+        type Node = usize;
+        fn root(&mut self) -> usize {
+            0
+        }
+        fn child(&mut self, parent: usize, index: usize) -> Option<usize> {
+            (1..TREE.len())
+                .filter(|&node| TREE[node].1 == parent)
+                .nth(index)
+        }
+        fn path(&mut self, node: usize) -> &[u8] {
+            TREE[node].0
+        }
+    }
+
+    enum Value {
+        Integer(u64),
+        String(&'static [u8]),
+    }
+
+    struct Evaluator;
+    impl Evaluator {
+        fn object(path: &[u8]) -> Option<Value> {
+            Some(match path {
+                b"\\_SB.PCI0._HID" => Value::Integer(str_to_eisa_id(b"PNP0A08").unwrap() as u64),
+                b"\\_SB.PCI0._CID" => Value::Integer(str_to_eisa_id(b"PNP0A03").unwrap() as u64),
+                b"\\_SB.PCI0._UID" => Value::Integer(0),
+                b"\\_SB.PCI0.LPCB._ADR" => Value::Integer(0x001F_0000),
+                b"\\_SB.PCI0.LPCB.EC0._HID" => {
+                    Value::Integer(str_to_eisa_id(b"PNP0C09").unwrap() as u64)
+                }
+                b"\\_SB.PCI0.LPCB.EC0._UID" => Value::String(b"EC"),
+                b"\\_SB.PCI0.LPCB.EC0._DDN" => Value::String(b"EmbeddedController"),
+                b"\\_SB.DOCK._STA" => Value::Integer(0b01000),
+                b"\\_SB.DOCK.DEV0._HID" => Value::String(b"ACPI0007"),
+                b"\\_SB.GONE._STA" => Value::Integer(0),
+                // A _CID package, which `evaluate` gives 0 for.
+                b"\\_SB.DOCK.DEV0._CID" => Value::Integer(0),
+                _ => return None,
+            })
+        }
+    }
+    impl AMLEvaluator for Evaluator {
+        type Error = ();
+        fn evaluate(&mut self, path: &[u8], _: &[u64]) -> Result<Option<u64>, ()> {
+            Ok(match Self::object(path) {
+                Some(Value::Integer(value)) => Some(value),
+                Some(Value::String(_)) => Some(0),
+                None => None,
+            })
+        }
+        fn evaluate_buffer(
+            &mut self,
+            path: &[u8],
+            _: &[crate::AMLArgument<'_>],
+            out: &mut [u8],
+        ) -> Result<Option<usize>, ()> {
+            match Self::object(path) {
+                Some(Value::String(string)) => {
+                    out[..string.len()].copy_from_slice(string);
+                    Ok(Some(string.len()))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn for_each_device() {
+        let mut seen = 0;
+        Namespace
+            .for_each_device(&mut Evaluator, |node, info| {
+                match node {
+                    1 => {
+                        assert!(info.matches(b"PNP0A08") && info.matches(b"PNP0A03"));
+                        assert!(matches!(info.uid, Some(DeviceUid::Integer(0))));
+                        assert_eq!(info.adr, None);
+                    }
+                    2 => {
+                        assert!(info.hid.is_none() && info.cids.is_empty());
+                        assert_eq!(info.adr.map(pci_adr), Some((0x1F, 0)));
+                    }
+                    3 => {
+                        assert!(info.matches(b"PNP0C09"));
+                        assert!(matches!(info.uid, Some(DeviceUid::String(b"EC"))));
+                        assert_eq!(info.ddn, Some(&b"EmbeddedController"[..]));
+                    }
+                    5 => {
+                        assert_eq!(info.path, b"\\_SB.DOCK.DEV0");
+                        assert!(info.matches(b"ACPI0007") && info.cids.is_empty());
+                        assert!(info.status.present());
+                    }
+                    _ => panic!("{} shouldn't be enumerated", node),
+                }
+                seen = seen << 4 | node;
+            })
+            .unwrap();
+        assert_eq!(seen, 0x1235);
+    }
+}
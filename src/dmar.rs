@@ -0,0 +1,576 @@
+use crate::{
+    GenericAddressAccess, GenericAddressStructure, SDT_HEADER_SIZE, SDTHeader, mcfg::MCFG,
+};
+use core::ffi::CStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A PCI device, by segment, bus, device and function.
+pub struct PCIDeviceAddress {
+    pub segment: u16,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+impl PCIDeviceAddress {
+    pub const fn new(segment: u16, bus: u8, device: u8, function: u8) -> Self {
+        Self {
+            segment,
+            bus,
+            device,
+            function,
+        }
+    }
+    /// The requester ID (bus/device/function) the device tags its DMA with.
+    pub const fn requester_id(&self) -> u16 {
+        (self.bus as u16) << 8 | (self.device as u16 & 0x1F) << 3 | (self.function as u16 & 0x7)
+    }
+}
+
+// Reads `width` (8 or 16) bits of a device's configuration space through ECAM; None if the MCFG doesn't cover the device.
+fn config_read(
+    mcfg: &MCFG,
+    access: &mut impl GenericAddressAccess,
+    device: PCIDeviceAddress,
+    offset: u16,
+    width: u8,
+) -> Option<u64> {
+    let address = mcfg.config_address(
+        device.segment,
+        device.bus,
+        device.device,
+        device.function,
+        offset,
+    )?;
+    let gas = GenericAddressStructure {
+        address_space_id: 0x00,
+        reg_bit_width: width,
+        reg_bit_offset: 0,
+        // Byte access for 8 bits, word access for 16.
+        access_size: width / 8,
+        address,
+    };
+    Some(access.read(&gas))
+}
+
+// Reads a byte of a device's configuration space through ECAM; None if nothing decodes it (its Vendor ID reads back all ones).
+//
+// The byte itself is returned as-is: 0xFF is a perfectly good bus number.
+fn config_read_u8(
+    mcfg: &MCFG,
+    access: &mut impl GenericAddressAccess,
+    device: PCIDeviceAddress,
+    offset: u16,
+) -> Option<u8> {
+    if config_read(mcfg, access, device, 0x00, 16)? as u16 == 0xFFFF {
+        return None;
+    }
+    Some(config_read(mcfg, access, device, offset, 8)? as u8)
+}
+
+#[derive(Copy, Clone)]
+pub struct DMARFlags(u8);
+impl DMARFlags {
+    /// If set, the platform supports interrupt remapping.
+    pub const fn intr_remap(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// If set, firmware asks OSPM not to turn on x2APIC mode (while still allowing interrupt remapping).
+    pub const fn x2apic_opt_out(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// If set, firmware set up DMA protection that stays on until OSPM takes over the remapping hardware.
+    pub const fn dma_ctrl_platform_opt_in(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeviceScopeType {
+    /// A PCI endpoint device.
+    PCIEndpoint,
+    /// A PCI-PCI bridge and every device below it.
+    PCISubHierarchy,
+    /// An I/O APIC (its ID is the enumeration ID).
+    IOAPIC,
+    /// An MSI capable HPET (its number is the enumeration ID).
+    HPET,
+    /// An ACPI namespace device (its ACPI device number is the enumeration ID, see `ANDD`).
+    ACPINamespaceDevice,
+    Unknown(u8),
+}
+impl DeviceScopeType {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0x01 => Self::PCIEndpoint,
+            0x02 => Self::PCISubHierarchy,
+            0x03 => Self::IOAPIC,
+            0x04 => Self::HPET,
+            0x05 => Self::ACPINamespaceDevice,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// One hop of a device scope path: a device and function on the current bus.
+pub struct DevicePathEntry {
+    pub device: u8,
+    pub function: u8,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Device Scope Structure
+///
+/// Names a device (or a hierarchy of them) by the path to it from `start_bus_number`:
+/// every hop but the last is a PCI-PCI bridge, and the bus of the next hop is that bridge's secondary bus.
+pub struct DeviceScope {
+    pub r#type: u8,
+    /// Length, in bytes, of this structure (path included).
+    pub length: u8,
+    pub flags: u8,
+    reserved: u8,
+    /// The I/O APIC ID, HPET number or ACPI device number, depending on `type`.
+    pub enumeration_id: u8,
+    /// The bus the path starts on.
+    pub start_bus_number: u8,
+    pub path: [DevicePathEntry; 0],
+}
+impl DeviceScope {
+    pub const fn scope_type(&self) -> DeviceScopeType {
+        DeviceScopeType::from_u8(self.r#type)
+    }
+    pub const fn path(&self) -> &[DevicePathEntry] {
+        // SAFETY: the path lies within the scope's length, which `DeviceScopeIter` checked against its structure.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(6) as *const DevicePathEntry,
+                (self.length as usize).saturating_sub(6) / 2,
+            )
+        }
+    }
+    /// Walks the path through the bridges on it, returning the device at its end.
+    ///
+    /// Reads the secondary bus number of every bridge on the way through ECAM, so it only works once the buses are numbered.
+    pub fn resolve(
+        &self,
+        segment: u16,
+        mcfg: &MCFG,
+        access: &mut impl GenericAddressAccess,
+    ) -> Option<PCIDeviceAddress> {
+        let path = self.path();
+        let (last, bridges) = path.split_last()?;
+        let mut bus = self.start_bus_number;
+        for hop in bridges {
+            let bridge = PCIDeviceAddress::new(segment, bus, hop.device, hop.function);
+            // Secondary Bus Number of the bridge's type 1 header.
+            bus = config_read_u8(mcfg, access, bridge, 0x19)?;
+        }
+        Some(PCIDeviceAddress::new(
+            segment,
+            bus,
+            last.device,
+            last.function,
+        ))
+    }
+    /// Returns true if `device` is in this scope: the endpoint itself, or the bridge of a sub-hierarchy or anything below it.
+    pub fn covers(
+        &self,
+        device: PCIDeviceAddress,
+        mcfg: &MCFG,
+        access: &mut impl GenericAddressAccess,
+    ) -> bool {
+        let Some(scope) = self.resolve(device.segment, mcfg, access) else {
+            return false;
+        };
+        match self.scope_type() {
+            DeviceScopeType::PCIEndpoint => scope == device,
+            DeviceScopeType::PCISubHierarchy => {
+                if scope == device {
+                    return true;
+                }
+                // Secondary and Subordinate Bus Numbers of the bridge.
+                match (
+                    config_read_u8(mcfg, access, scope, 0x19),
+                    config_read_u8(mcfg, access, scope, 0x1A),
+                ) {
+                    (Some(secondary), Some(subordinate)) => {
+                        device.bus >= secondary && device.bus <= subordinate
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Iterator over the device scopes at the end of a remapping structure.
+pub struct DeviceScopeIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _structure: core::marker::PhantomData<&'a DeviceScope>,
+}
+impl<'a> DeviceScopeIter<'a> {
+    // The device scopes of the structure at `structure`, starting `offset` bytes in.
+    const fn new(structure: &'a RemappingStructureHeader, offset: usize) -> Self {
+        Self {
+            next: (structure as *const _ as *const u8).wrapping_add(offset),
+            remaining: (structure.length as usize).saturating_sub(offset),
+            _structure: core::marker::PhantomData,
+        }
+    }
+}
+impl<'a> Iterator for DeviceScopeIter<'a> {
+    type Item = &'a DeviceScope;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 6 {
+            return None;
+        }
+        // SAFETY: the scope's fixed part lies within the structure, checked above.
+        let scope = unsafe { &*(self.next as *const DeviceScope) };
+        let length = scope.length as usize;
+        // A zero length would loop forever, and a longer one runs off the structure; treat either as the end of the list.
+        if length < 6 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        Some(scope)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct RemappingStructureHeader {
+    pub r#type: u16,
+    /// Length, in bytes, of the whole structure (device scopes included).
+    pub length: u16,
+}
+
+#[derive(Copy, Clone)]
+pub struct DRHDFlags(u8);
+impl DRHDFlags {
+    /// If set, this unit covers every PCI device of its segment that no other unit lists (and its device scopes are only I/O APICs and HPETs).
+    pub const fn include_pci_all(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## DMA Remapping Hardware Unit Definition Structure (DRHD)
+///
+/// One remapping hardware unit, and the devices behind it.
+pub struct DRHD {
+    /// - **Type** - 0
+    pub header: RemappingStructureHeader,
+    pub flags: DRHDFlags,
+    /// Bits [[3:0]] are N: the register set is 2^N 4KB pages.
+    pub size: u8,
+    pub segment_number: u16,
+    /// Physical address of the unit's register set.
+    pub register_base_address: u64,
+    pub device_scope: [DeviceScope; 0],
+}
+impl DRHD {
+    /// Size, in bytes, of the register set.
+    pub const fn register_set_size(&self) -> u64 {
+        4096 << (self.size & 0xF)
+    }
+    pub const fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(&self.header, 16)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Reserved Memory Region Reporting Structure (RMRR)
+///
+/// Memory that firmware-owned DMA (USB legacy emulation, for example) keeps using: it has to be identity mapped for the listed devices.
+pub struct RMRR {
+    /// - **Type** - 1
+    pub header: RemappingStructureHeader,
+    reserved: u16,
+    pub segment_number: u16,
+    /// 4KB aligned.
+    pub reserved_memory_region_base_address: u64,
+    /// The last address of the region (inclusive); the region is a multiple of 4KB.
+    pub reserved_memory_region_limit_address: u64,
+    pub device_scope: [DeviceScope; 0],
+}
+impl RMRR {
+    pub const fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(&self.header, 24)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ATSRFlags(u8);
+impl ATSRFlags {
+    /// If set, every root port of the segment supports ATS (and the device scopes are empty).
+    pub const fn all_ports(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Root Port ATS Capability Reporting Structure (ATSR)
+///
+/// The root ports of a segment that support Address Translation Services.
+pub struct ATSR {
+    /// - **Type** - 2
+    pub header: RemappingStructureHeader,
+    pub flags: ATSRFlags,
+    reserved: u8,
+    pub segment_number: u16,
+    pub device_scope: [DeviceScope; 0],
+}
+impl ATSR {
+    pub const fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(&self.header, 8)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Remapping Hardware Static Affinity Structure (RHSA)
+///
+/// The proximity domain (as in the SRAT) a remapping hardware unit belongs to.
+pub struct RHSA {
+    /// - **Type** - 3
+    pub header: RemappingStructureHeader,
+    reserved: u32,
+    /// The `register_base_address` of the DRHD this is about.
+    pub register_base_address: u64,
+    pub proximity_domain: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ACPI Name-space Device Declaration Structure (ANDD)
+///
+/// Names the ACPI namespace device that `ACPINamespaceDevice` device scopes with this `acpi_device_number` refer to.
+pub struct ANDD {
+    /// - **Type** - 4
+    pub header: RemappingStructureHeader,
+    reserved: [u8; 3],
+    pub acpi_device_number: u8,
+    /// A null-terminated ASCII string: the full path of the device in the namespace (like "\_SB.PCI0.I2C0").
+    pub object_name: [u8; 0],
+}
+impl ANDD {
+    pub fn object_name(&self) -> &CStr {
+        // SAFETY: the name lies within the structure's length, which `RemappingStructureIter` checked against the table.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(8),
+                (self.header.length as usize).saturating_sub(8),
+            )
+        };
+        CStr::from_bytes_until_nul(bytes).unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SATCFlags(u8);
+impl SATCFlags {
+    /// If set, the listed devices need ATS turned on to work correctly (they're integrated accelerators with device TLBs).
+    pub const fn atc_required(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SoC Integrated Address Translation Cache Reporting Structure (SATC)
+///
+/// SoC integrated devices that have an Address Translation Cache.
+pub struct SATC {
+    /// - **Type** - 5
+    pub header: RemappingStructureHeader,
+    pub flags: SATCFlags,
+    reserved: u8,
+    pub segment_number: u16,
+    pub device_scope: [DeviceScope; 0],
+}
+impl SATC {
+    pub const fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(&self.header, 8)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum RemappingStructure<'a> {
+    DRHD(&'a DRHD),
+    RMRR(&'a RMRR),
+    ATSR(&'a ATSR),
+    RHSA(&'a RHSA),
+    ANDD(&'a ANDD),
+    SATC(&'a SATC),
+    /// A structure type this library doesn't know about (or a reserved one).
+    Unknown(&'a RemappingStructureHeader),
+}
+
+/// Iterator over the remapping structures of a DMAR.
+pub struct RemappingStructureIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _dmar: core::marker::PhantomData<&'a DMAR>,
+}
+impl<'a> Iterator for RemappingStructureIter<'a> {
+    type Item = RemappingStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const RemappingStructureHeader) };
+        let length = header.length as usize;
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 4 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        let fits = |size: usize| length >= size;
+        // SAFETY: the structure lies within the table (checked above), and is only cast to a type it's long enough for.
+        Some(unsafe {
+            match header.r#type {
+                0 if fits(core::mem::size_of::<DRHD>()) => {
+                    RemappingStructure::DRHD(&*(ptr as *const _))
+                }
+                1 if fits(core::mem::size_of::<RMRR>()) => {
+                    RemappingStructure::RMRR(&*(ptr as *const _))
+                }
+                2 if fits(core::mem::size_of::<ATSR>()) => {
+                    RemappingStructure::ATSR(&*(ptr as *const _))
+                }
+                3 if fits(core::mem::size_of::<RHSA>()) => {
+                    RemappingStructure::RHSA(&*(ptr as *const _))
+                }
+                4 if fits(core::mem::size_of::<ANDD>()) => {
+                    RemappingStructure::ANDD(&*(ptr as *const _))
+                }
+                5 if fits(core::mem::size_of::<SATC>()) => {
+                    RemappingStructure::SATC(&*(ptr as *const _))
+                }
+                _ => RemappingStructure::Unknown(header),
+            }
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## DMA Remapping Reporting Table (DMAR)
+///
+/// Defined by the Intel Virtualization Technology for Directed I/O specification, this table describes the DMA (and interrupt) remapping hardware units of the platform,
+/// which devices are behind each of them, and the memory firmware still needs DMA to reach.
+pub struct DMAR {
+    /// - **Signature** - "DMAR"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    /// The DMA physical addressability of the platform, minus one.
+    pub host_address_width: u8,
+    pub flags: DMARFlags,
+    reserved: [u8; 10],
+}
+impl DMAR {
+    /// The number of physical address bits DMA can reach.
+    pub const fn address_width(&self) -> u8 {
+        self.host_address_width + 1
+    }
+    pub const fn remapping_structures(&self) -> RemappingStructureIter<'_> {
+        RemappingStructureIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 12),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 12),
+            _dmar: core::marker::PhantomData,
+        }
+    }
+    pub fn remapping_units(&self) -> impl Iterator<Item = &DRHD> {
+        self.remapping_structures()
+            .filter_map(|structure| match structure {
+                RemappingStructure::DRHD(drhd) => Some(drhd),
+                _ => None,
+            })
+    }
+    pub fn reserved_memory_regions(&self) -> impl Iterator<Item = &RMRR> {
+        self.remapping_structures()
+            .filter_map(|structure| match structure {
+                RemappingStructure::RMRR(rmrr) => Some(rmrr),
+                _ => None,
+            })
+    }
+    /// The proximity domain of `unit`, if an RHSA gives it one.
+    pub fn proximity_domain(&self, unit: &DRHD) -> Option<u32> {
+        let base = unit.register_base_address;
+        self.remapping_structures()
+            .find_map(|structure| match structure {
+                RemappingStructure::RHSA(rhsa) if rhsa.register_base_address == base => {
+                    Some(rhsa.proximity_domain)
+                }
+                _ => None,
+            })
+    }
+    /// The ANDD of ACPI device number `number` (the enumeration ID of an `ACPINamespaceDevice` device scope).
+    pub fn namespace_device(&self, number: u8) -> Option<&ANDD> {
+        self.remapping_structures()
+            .find_map(|structure| match structure {
+                RemappingStructure::ANDD(andd) if andd.acpi_device_number == number => Some(andd),
+                _ => None,
+            })
+    }
+    /// The remapping hardware unit `device` is behind: the one whose device scopes cover it, or else the `include_pci_all` unit of its segment.
+    pub fn remapping_unit_for(
+        &self,
+        device: PCIDeviceAddress,
+        mcfg: &MCFG,
+        access: &mut impl GenericAddressAccess,
+    ) -> Option<&DRHD> {
+        let mut catch_all = None;
+        for unit in self.remapping_units() {
+            if unit.segment_number != device.segment {
+                continue;
+            }
+            if unit.flags.include_pci_all() {
+                catch_all = catch_all.or(Some(unit));
+                continue;
+            }
+            if unit
+                .device_scopes()
+                .any(|scope| scope.covers(device, mcfg, access))
+            {
+                return Some(unit);
+            }
+        }
+        catch_all
+    }
+    /// Returns true if ATS is supported for `device`: its root port is listed in an ATSR of its segment (or the ATSR covers all ports).
+    pub fn ats_supported(
+        &self,
+        device: PCIDeviceAddress,
+        mcfg: &MCFG,
+        access: &mut impl GenericAddressAccess,
+    ) -> bool {
+        self.remapping_structures()
+            .any(|structure| match structure {
+                RemappingStructure::ATSR(atsr) if atsr.segment_number == device.segment => {
+                    atsr.flags.all_ports()
+                        || atsr
+                            .device_scopes()
+                            .any(|scope| scope.covers(device, mcfg, access))
+                }
+                _ => false,
+            })
+    }
+}
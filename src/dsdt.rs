@@ -10,7 +10,7 @@ use crate::SDTHeader;
 pub struct DifferentiatedSystemDescriptionTable {
     /// - **Signature** - "DSDT"
     /// - **Revision** - This field also sets the global integer width for the AML interpreter.
-    /// Values less than two will cause the interpreter to use 32-bit integers and math. Values of two and greater will cause the interpreter to use full 64-bit integers and math.
+    ///   Values less than two will cause the interpreter to use 32-bit integers and math. Values of two and greater will cause the interpreter to use full 64-bit integers and math.
     pub header: SDTHeader,
     /// The bytes of AML code.
     pub def_block: [u8; 0],
@@ -0,0 +1,542 @@
+use crate::{
+    GenericAddressAccess, GenericAddressStructure, ecdt::EmbeddedControllerBootResourcesTable,
+};
+
+/// Read Embedded Controller. Reads a byte in the address space of the EC.
+pub const RD_EC: u8 = 0x80;
+/// Write Embedded Controller. Writes a byte in the address space of the EC.
+pub const WR_EC: u8 = 0x81;
+/// Burst Enable Embedded Controller. Allows OSPM dedicated access to the EC.
+pub const BE_EC: u8 = 0x82;
+/// Burst Disable Embedded Controller. Releases OSPM's dedicated access to the EC.
+pub const BD_EC: u8 = 0x83;
+/// Query Embedded Controller. Returns the query value of the pending event.
+pub const QR_EC: u8 = 0x84;
+/// The byte the EC returns in the data register to acknowledge a BE_EC command.
+pub const BURST_ACK: u8 = 0x90;
+
+/// The number of status register polls an `EmbeddedController` makes before giving up, unless told otherwise.
+pub const DEFAULT_POLL_LIMIT: u32 = 100_000;
+
+#[derive(Copy, Clone)]
+/// ## Embedded Controller Status Register (EC_SC)
+pub struct EmbeddedControllerStatus(u8);
+impl EmbeddedControllerStatus {
+    /// Output Buffer Full.
+    ///
+    /// Set when the EC has written a byte of data into the data register and OSPM has not read it yet.
+    pub const fn obf(&self) -> bool {
+        self.0 & 0b00000001 != 0
+    }
+    /// Input Buffer Full.
+    ///
+    /// Set when OSPM has written a command or data byte into the EC and the EC has not read it yet.
+    pub const fn ibf(&self) -> bool {
+        self.0 & 0b00000010 != 0
+    }
+    /// - 0 - The input buffer contains a data byte.
+    /// - 1 - The input buffer contains a command byte.
+    pub const fn cmd(&self) -> bool {
+        self.0 & 0b00001000 != 0
+    }
+    /// Set when the EC is in burst mode.
+    pub const fn burst(&self) -> bool {
+        self.0 & 0b00010000 != 0
+    }
+    /// Set when an SCI event is pending.  OSPM responds with a QR_EC command.
+    pub const fn sci_evt(&self) -> bool {
+        self.0 & 0b00100000 != 0
+    }
+    /// Set when an SMI event is pending.
+    pub const fn smi_evt(&self) -> bool {
+        self.0 & 0b01000000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the Embedded Controller driver can run into.
+pub enum EmbeddedControllerError {
+    /// IBF never cleared (or OBF never set) within the poll limit.
+    Timeout,
+    /// The EC answered BE_EC with something other than `BURST_ACK`.
+    BurstNotAcknowledged(u8),
+    /// An Embedded Controller operation region access was wider than 64 bits or not a whole number of bytes.
+    InvalidAccessWidth(u32),
+    /// An Embedded Controller operation region access of `length` bytes at `offset` goes past the 256 bytes of EC space.
+    OutOfBounds { offset: u64, length: u32 },
+}
+
+/// ## EmbeddedControl Operation Region Handler
+///
+/// What an AML interpreter calls for every access to a field of an `OperationRegion(..., EmbeddedControl, ...)`.
+/// `EmbeddedController` implements it, so the interpreter installs the driver as its handler for region space 0x03.
+pub trait EmbeddedControlRegionHandler {
+    type Error;
+    /// Reads a field of `bit_width` bits at byte `offset` in EC space.
+    fn region_read(&mut self, offset: u64, bit_width: u32) -> Result<u64, Self::Error>;
+    /// Writes a field of `bit_width` bits at byte `offset` in EC space.
+    fn region_write(&mut self, offset: u64, bit_width: u32, value: u64) -> Result<(), Self::Error>;
+}
+
+/// ## ACPI Embedded Controller Driver
+///
+/// Implements the ACPI EC interface: a command/status register and a data register, handshaked with the IBF/OBF status bits.
+/// Because the ECDT already hands out both registers, this driver can run before the namespace is loaded.
+///
+/// There is no AML interpreter in this library.  The interpreter installs this driver as its EmbeddedControl operation region handler
+/// (see `EmbeddedControlRegionHandler`), and its SCI handler runs the _Qxx methods (see `event::dispatch_ec_query`).
+pub struct EmbeddedController<A: GenericAddressAccess> {
+    /// The EC_SC register.  Reads return the status, writes send a command.
+    pub ec_control: GenericAddressStructure,
+    /// The EC_DATA register.
+    pub ec_data: GenericAddressStructure,
+    /// The GPE bit the EC raises its SCI on.
+    pub gpe_bit: u8,
+    /// The _UID of the EC device in the namespace.
+    pub uid: u32,
+    /// How many times to poll the status register before returning `EmbeddedControllerError::Timeout`.
+    pub poll_limit: u32,
+    access: A,
+}
+impl<A: GenericAddressAccess> EmbeddedController<A> {
+    pub fn new(
+        ec_control: GenericAddressStructure,
+        ec_data: GenericAddressStructure,
+        gpe_bit: u8,
+        uid: u32,
+        access: A,
+    ) -> Self {
+        Self {
+            ec_control,
+            ec_data,
+            gpe_bit,
+            uid,
+            poll_limit: DEFAULT_POLL_LIMIT,
+            access,
+        }
+    }
+    /// Seeds the driver from the ECDT so EC space is usable before the namespace is evaluated.
+    pub fn from_ecdt(ecdt: &EmbeddedControllerBootResourcesTable, access: A) -> Self {
+        Self::new(
+            ecdt.ec_control,
+            ecdt.ec_data,
+            ecdt.gpe_bit,
+            ecdt.uid,
+            access,
+        )
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.access
+    }
+
+    /// Reads the EC_SC register.
+    pub fn status(&mut self) -> EmbeddedControllerStatus {
+        EmbeddedControllerStatus(self.access.read(&self.ec_control) as u8)
+    }
+    fn wait_ibf_clear(&mut self) -> Result<(), EmbeddedControllerError> {
+        for _ in 0..self.poll_limit {
+            if !self.status().ibf() {
+                return Ok(());
+            }
+            core::hint::spin_loop();
+        }
+        Err(EmbeddedControllerError::Timeout)
+    }
+    fn wait_obf_set(&mut self) -> Result<(), EmbeddedControllerError> {
+        for _ in 0..self.poll_limit {
+            if self.status().obf() {
+                return Ok(());
+            }
+            core::hint::spin_loop();
+        }
+        Err(EmbeddedControllerError::Timeout)
+    }
+    fn send_command(&mut self, command: u8) -> Result<(), EmbeddedControllerError> {
+        self.wait_ibf_clear()?;
+        let control = self.ec_control;
+        self.access.write(&control, command as u64);
+        Ok(())
+    }
+    fn send_data(&mut self, data: u8) -> Result<(), EmbeddedControllerError> {
+        self.wait_ibf_clear()?;
+        let data_reg = self.ec_data;
+        self.access.write(&data_reg, data as u64);
+        Ok(())
+    }
+    fn receive_data(&mut self) -> Result<u8, EmbeddedControllerError> {
+        self.wait_obf_set()?;
+        Ok(self.access.read(&self.ec_data) as u8)
+    }
+
+    /// RD_EC: reads the byte at `address` in EC space.
+    pub fn read(&mut self, address: u8) -> Result<u8, EmbeddedControllerError> {
+        self.send_command(RD_EC)?;
+        self.send_data(address)?;
+        self.receive_data()
+    }
+    /// WR_EC: writes `value` to the byte at `address` in EC space.
+    pub fn write(&mut self, address: u8, value: u8) -> Result<(), EmbeddedControllerError> {
+        self.send_command(WR_EC)?;
+        self.send_data(address)?;
+        self.send_data(value)?;
+        self.wait_ibf_clear()
+    }
+    /// BE_EC: puts the EC in burst mode so a run of accesses isn't interleaved with the EC's own work.
+    pub fn burst_enable(&mut self) -> Result<(), EmbeddedControllerError> {
+        self.send_command(BE_EC)?;
+        match self.receive_data()? {
+            BURST_ACK => Ok(()),
+            other => Err(EmbeddedControllerError::BurstNotAcknowledged(other)),
+        }
+    }
+    /// BD_EC: takes the EC out of burst mode.
+    pub fn burst_disable(&mut self) -> Result<(), EmbeddedControllerError> {
+        self.send_command(BD_EC)?;
+        self.wait_ibf_clear()
+    }
+    /// Returns true if the EC has an SCI event waiting to be queried.
+    pub fn event_pending(&mut self) -> bool {
+        self.status().sci_evt()
+    }
+    /// QR_EC: fetches the query value of the pending event, which names the _Qxx method to run.
+    ///
+    /// Returns `None` if the EC reports no outstanding event (query value 0).
+    pub fn query(&mut self) -> Result<Option<u8>, EmbeddedControllerError> {
+        self.send_command(QR_EC)?;
+        match self.receive_data()? {
+            0 => Ok(None),
+            value => Ok(Some(value)),
+        }
+    }
+
+    /// Reads an EmbeddedControl operation region field of `bit_width` bits at byte `address`.
+    ///
+    /// Multi-byte fields are read little-endian under burst mode.
+    pub fn read_byte(
+        &mut self,
+        address: u8,
+        bit_width: u32,
+    ) -> Result<u64, EmbeddedControllerError> {
+        let bytes = Self::region_bytes(address as u64, bit_width)?;
+        if bytes == 1 {
+            return self.read(address).map(|v| v as u64);
+        }
+        self.burst_enable()?;
+        let mut value = 0;
+        let mut result = Ok(());
+        for i in 0..bytes {
+            match self.read(address + i as u8) {
+                Ok(byte) => value |= (byte as u64) << (i * 8),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        // Leave burst mode either way, but report the access error over the burst disable one.
+        let disabled = self.burst_disable();
+        result.and(disabled).map(|_| value)
+    }
+    /// Writes an EmbeddedControl operation region field of `bit_width` bits at byte `address`.
+    ///
+    /// Multi-byte fields are written little-endian under burst mode.
+    pub fn write_byte(
+        &mut self,
+        address: u8,
+        bit_width: u32,
+        value: u64,
+    ) -> Result<(), EmbeddedControllerError> {
+        let bytes = Self::region_bytes(address as u64, bit_width)?;
+        if bytes == 1 {
+            return self.write(address, value as u8);
+        }
+        self.burst_enable()?;
+        let mut result = Ok(());
+        for i in 0..bytes {
+            result = self.write(address + i as u8, (value >> (i * 8)) as u8);
+            if result.is_err() {
+                break;
+            }
+        }
+        // Leave burst mode either way, but report the access error over the burst disable one.
+        let disabled = self.burst_disable();
+        result.and(disabled)
+    }
+    /// Checks the width of a region access at `offset`, returning how many bytes it covers.
+    fn region_bytes(offset: u64, bit_width: u32) -> Result<u32, EmbeddedControllerError> {
+        let bytes = match bit_width {
+            8 | 16 | 24 | 32 | 40 | 48 | 56 | 64 => bit_width / 8,
+            _ => return Err(EmbeddedControllerError::InvalidAccessWidth(bit_width)),
+        };
+        if offset.saturating_add(bytes as u64) > 256 {
+            return Err(EmbeddedControllerError::OutOfBounds {
+                offset,
+                length: bytes,
+            });
+        }
+        Ok(bytes)
+    }
+}
+impl<A: GenericAddressAccess> EmbeddedControlRegionHandler for EmbeddedController<A> {
+    type Error = EmbeddedControllerError;
+    fn region_read(&mut self, offset: u64, bit_width: u32) -> Result<u64, Self::Error> {
+        Self::region_bytes(offset, bit_width)?;
+        self.read_byte(offset as u8, bit_width)
+    }
+    fn region_write(&mut self, offset: u64, bit_width: u32, value: u64) -> Result<(), Self::Error> {
+        Self::region_bytes(offset, bit_width)?;
+        self.write_byte(offset as u8, bit_width, value)
+    }
+}
+
+/// Builds the name of the query method for a QR_EC value, e.g. 0x1A gives "_Q1A".
+pub const fn query_method_name(query: u8) -> [u8; 4] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    [
+        b'_',
+        b'Q',
+        HEX[(query >> 4) as usize],
+        HEX[(query & 0xF) as usize],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EC_SC: u64 = 0x66;
+    const EC_DATA: u64 = 0x62;
+
+    const OBF: u8 = 0b00000001;
+    const IBF: u8 = 0b00000010;
+    const CMD: u8 = 0b00001000;
+    const BURST: u8 = 0b00010000;
+    const SCI_EVT: u8 = 0b00100000;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    enum State {
+        Idle,
+        ReadAddress,
+        WriteAddress,
+        WriteData(u8),
+    }
+
+    /// A simulated EC: it takes a byte out of the input buffer on the `delay`th status read after it was written, like a slow EC firmware would.
+    struct FakeEC {
+        ram: [u8; 256],
+        status: u8,
+        input: u8,
+        output: u8,
+        state: State,
+        queries: [u8; 4],
+        pending: usize,
+        delay: u32,
+        countdown: u32,
+        /// Never takes anything out of the input buffer.
+        stuck: bool,
+        /// What to answer BE_EC with.
+        burst_ack: u8,
+        bursts: u32,
+    }
+    impl FakeEC {
+        fn new() -> Self {
+            Self {
+                ram: [0; 256],
+                status: 0,
+                input: 0,
+                output: 0,
+                state: State::Idle,
+                queries: [0; 4],
+                pending: 0,
+                delay: 3,
+                countdown: 0,
+                stuck: false,
+                burst_ack: BURST_ACK,
+                bursts: 0,
+            }
+        }
+        fn raise_query(&mut self, query: u8) {
+            self.queries[self.pending] = query;
+            self.pending += 1;
+            self.status |= SCI_EVT;
+        }
+        fn respond(&mut self, value: u8) {
+            self.output = value;
+            self.status |= OBF;
+        }
+        fn step(&mut self) {
+            if self.status & IBF == 0 || self.stuck {
+                return;
+            }
+            if self.countdown > 0 {
+                self.countdown -= 1;
+                return;
+            }
+            self.status &= !IBF;
+            let byte = self.input;
+            if self.status & CMD != 0 {
+                match byte {
+                    RD_EC => self.state = State::ReadAddress,
+                    WR_EC => self.state = State::WriteAddress,
+                    BE_EC => {
+                        self.status |= BURST;
+                        self.bursts += 1;
+                        self.respond(self.burst_ack);
+                    }
+                    BD_EC => self.status &= !BURST,
+                    QR_EC => {
+                        let query = if self.pending > 0 {
+                            self.pending -= 1;
+                            self.queries[self.pending]
+                        } else {
+                            0
+                        };
+                        if self.pending == 0 {
+                            self.status &= !SCI_EVT;
+                        }
+                        self.respond(query);
+                    }
+                    _ => {}
+                }
+                return;
+            }
+            match self.state {
+                State::ReadAddress => {
+                    self.state = State::Idle;
+                    self.respond(self.ram[byte as usize]);
+                }
+                State::WriteAddress => self.state = State::WriteData(byte),
+                State::WriteData(address) => {
+                    self.state = State::Idle;
+                    self.ram[address as usize] = byte;
+                }
+                State::Idle => {}
+            }
+        }
+    }
+    impl GenericAddressAccess for FakeEC {
+        fn read(&mut self, gas: &GenericAddressStructure) -> u64 {
+            match gas.address {
+                EC_SC => {
+                    self.step();
+                    self.status as u64
+                }
+                EC_DATA => {
+                    self.status &= !OBF;
+                    self.output as u64
+                }
+                _ => unreachable!(),
+            }
+        }
+        fn write(&mut self, gas: &GenericAddressStructure, value: u64) {
+            assert_eq!(self.status & IBF, 0, "wrote with the input buffer full");
+            self.input = value as u8;
+            self.countdown = self.delay;
+            self.status |= IBF;
+            match gas.address {
+                EC_SC => self.status |= CMD,
+                EC_DATA => self.status &= !CMD,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    fn controller(fake: FakeEC) -> EmbeddedController<FakeEC> {
+        let port = |address| GenericAddressStructure {
+            address_space_id: 0x01,
+            reg_bit_width: 8,
+            reg_bit_offset: 0,
+            access_size: 1,
+            address,
+        };
+        EmbeddedController::new(port(EC_SC), port(EC_DATA), 0x16, 0, fake)
+    }
+
+    #[test]
+    fn read_write() {
+        let mut ec = controller(FakeEC::new());
+        ec.write(0x40, 0xA5).unwrap();
+        assert_eq!(ec.read(0x40), Ok(0xA5));
+        assert_eq!(ec.read(0x41), Ok(0));
+        assert_eq!(ec.into_inner().ram[0x40], 0xA5);
+    }
+
+    #[test]
+    fn region_access_uses_burst() {
+        let mut ec = controller(FakeEC::new());
+        ec.region_write(0xFC, 32, 0x1234_5678).unwrap();
+        assert_eq!(ec.region_read(0xFC, 32), Ok(0x1234_5678));
+        assert_eq!(ec.read_byte(0xFD, 8), Ok(0x56));
+        assert!(!ec.status().burst());
+        let fake = ec.into_inner();
+        assert_eq!(fake.ram[0xFC..], [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(fake.bursts, 2);
+    }
+
+    #[test]
+    fn region_access_out_of_bounds() {
+        let mut ec = controller(FakeEC::new());
+        let error = EmbeddedControllerError::OutOfBounds {
+            offset: 0xFF,
+            length: 2,
+        };
+        assert_eq!(ec.read_byte(0xFF, 16), Err(error));
+        assert_eq!(ec.write_byte(0xFF, 16, 0), Err(error));
+        assert_eq!(
+            ec.region_read(0x100, 8),
+            Err(EmbeddedControllerError::OutOfBounds {
+                offset: 0x100,
+                length: 1,
+            })
+        );
+        assert_eq!(
+            ec.read_byte(0, 12),
+            Err(EmbeddedControllerError::InvalidAccessWidth(12))
+        );
+        assert_eq!(ec.into_inner().bursts, 0);
+    }
+
+    #[test]
+    fn query() {
+        let mut fake = FakeEC::new();
+        fake.raise_query(0x1A);
+        let mut ec = controller(fake);
+        assert!(ec.event_pending());
+        assert_eq!(ec.query(), Ok(Some(0x1A)));
+        assert_eq!(&query_method_name(0x1A), b"_Q1A");
+        assert!(!ec.event_pending());
+        assert_eq!(ec.query(), Ok(None));
+    }
+
+    #[test]
+    fn burst_not_acknowledged() {
+        let mut fake = FakeEC::new();
+        fake.burst_ack = 0x00;
+        let mut ec = controller(fake);
+        assert_eq!(
+            ec.burst_enable(),
+            Err(EmbeddedControllerError::BurstNotAcknowledged(0x00))
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        let mut fake = FakeEC::new();
+        fake.delay = 20;
+        let mut ec = controller(fake);
+        ec.poll_limit = 10;
+        assert_eq!(ec.read(0x40), Err(EmbeddedControllerError::Timeout));
+
+        let mut fake = FakeEC::new();
+        fake.stuck = true;
+        let mut ec = controller(fake);
+        ec.poll_limit = 1000;
+        ec.write(0x40, 1).unwrap_err();
+        assert_eq!(
+            ec.read_byte(0x40, 16),
+            Err(EmbeddedControllerError::Timeout)
+        );
+    }
+}
@@ -0,0 +1,684 @@
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    apei::{
+        APEIError, APEIInstruction, APEIInstructionEntry, APEIInterpreter, APEIPlatform,
+        instruction_entries,
+    },
+};
+
+/// The number of `CheckBusyStatus` polls an `ErrorInjector` makes before giving up, unless told otherwise.
+pub const DEFAULT_BUSY_POLL_LIMIT: u32 = 100_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Error Injection Actions
+pub enum InjectionAction {
+    /// Starts an injection operation.
+    BeginInjectionOperation,
+    /// Returns the physical address of the Trigger Error Action Table.
+    GetTriggerErrorActionTable,
+    /// Sets the type of error to inject (a single bit of the error type bitmap).
+    SetErrorType,
+    /// Returns the bitmap of error types the platform can inject.
+    GetErrorType,
+    /// Ends the current operation.
+    EndOperation,
+    /// Tells the platform to carry out the injection.
+    ExecuteOperation,
+    /// Returns 1 while the platform is still busy with the operation.
+    CheckBusyStatus,
+    /// Returns the status of the last operation.
+    GetCommandStatus,
+    /// Sets the type of error to inject, along with where to inject it.
+    /// The register region of its entry is the SET_ERROR_TYPE_WITH_ADDRESS data structure (see `SetErrorTypeWithAddress`).
+    SetErrorTypeWithAddress,
+    /// Returns the nominal and maximum execution time of `ExecuteOperation`.
+    GetExecuteOperationTimings,
+    /// Only used in the Trigger Error Action Table: the instructions that make the injected error happen.
+    TriggerError,
+    Unknown(u8),
+}
+impl InjectionAction {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0x00 => Self::BeginInjectionOperation,
+            0x01 => Self::GetTriggerErrorActionTable,
+            0x02 => Self::SetErrorType,
+            0x03 => Self::GetErrorType,
+            0x04 => Self::EndOperation,
+            0x05 => Self::ExecuteOperation,
+            0x06 => Self::CheckBusyStatus,
+            0x07 => Self::GetCommandStatus,
+            0x08 => Self::SetErrorTypeWithAddress,
+            0x09 => Self::GetExecuteOperationTimings,
+            0xFF => Self::TriggerError,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::BeginInjectionOperation => 0x00,
+            Self::GetTriggerErrorActionTable => 0x01,
+            Self::SetErrorType => 0x02,
+            Self::GetErrorType => 0x03,
+            Self::EndOperation => 0x04,
+            Self::ExecuteOperation => 0x05,
+            Self::CheckBusyStatus => 0x06,
+            Self::GetCommandStatus => 0x07,
+            Self::SetErrorTypeWithAddress => 0x08,
+            Self::GetExecuteOperationTimings => 0x09,
+            Self::TriggerError => 0xFF,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// What `GetCommandStatus` returns.
+pub enum InjectionCommandStatus {
+    Success,
+    UnknownFailure,
+    InvalidAccess,
+    Unknown(u64),
+}
+impl InjectionCommandStatus {
+    pub const fn from_u64(value: u64) -> Self {
+        match value {
+            0x00 => Self::Success,
+            0x01 => Self::UnknownFailure,
+            0x02 => Self::InvalidAccess,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// The error type bitmap, as `GetErrorType` returns it and `SetErrorType` takes it.
+///
+/// `GetErrorType` sets every type the platform supports; an injection sets exactly one.
+pub struct ErrorTypes(u32);
+impl ErrorTypes {
+    pub const PROCESSOR_CORRECTABLE: Self = Self(1 << 0);
+    pub const PROCESSOR_UNCORRECTABLE_NON_FATAL: Self = Self(1 << 1);
+    pub const PROCESSOR_UNCORRECTABLE_FATAL: Self = Self(1 << 2);
+    pub const MEMORY_CORRECTABLE: Self = Self(1 << 3);
+    pub const MEMORY_UNCORRECTABLE_NON_FATAL: Self = Self(1 << 4);
+    pub const MEMORY_UNCORRECTABLE_FATAL: Self = Self(1 << 5);
+    pub const PCIE_CORRECTABLE: Self = Self(1 << 6);
+    pub const PCIE_UNCORRECTABLE_NON_FATAL: Self = Self(1 << 7);
+    pub const PCIE_UNCORRECTABLE_FATAL: Self = Self(1 << 8);
+    pub const PLATFORM_CORRECTABLE: Self = Self(1 << 9);
+    pub const PLATFORM_UNCORRECTABLE_NON_FATAL: Self = Self(1 << 10);
+    pub const PLATFORM_UNCORRECTABLE_FATAL: Self = Self(1 << 11);
+    /// The error type is described by the vendor error type extension structure.
+    pub const VENDOR_DEFINED: Self = Self(1 << 31);
+
+    pub const fn new(value: u32) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    /// Returns true if every type set in `other` is set here too.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn processor_correctable(&self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+    pub const fn processor_uncorrectable_non_fatal(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+    pub const fn processor_uncorrectable_fatal(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+    pub const fn memory_correctable(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+    pub const fn memory_uncorrectable_non_fatal(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+    pub const fn memory_uncorrectable_fatal(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+    pub const fn pcie_correctable(&self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+    pub const fn pcie_uncorrectable_non_fatal(&self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+    pub const fn pcie_uncorrectable_fatal(&self) -> bool {
+        self.0 & (1 << 8) != 0
+    }
+    pub const fn platform_correctable(&self) -> bool {
+        self.0 & (1 << 9) != 0
+    }
+    pub const fn platform_uncorrectable_non_fatal(&self) -> bool {
+        self.0 & (1 << 10) != 0
+    }
+    pub const fn platform_uncorrectable_fatal(&self) -> bool {
+        self.0 & (1 << 11) != 0
+    }
+    pub const fn vendor_defined(&self) -> bool {
+        self.0 & (1 << 31) != 0
+    }
+    // The rest of the bits are reserved.
+}
+impl core::fmt::Debug for ErrorTypes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ErrorTypes({:#x})", self.0)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SetErrorTypeWithAddressFlags(u32);
+impl SetErrorTypeWithAddressFlags {
+    pub const fn new(value: u32) -> Self {
+        Self(value)
+    }
+    /// `apic_id` is valid.
+    pub const fn processor_valid(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// `memory_address` and `memory_address_range` are valid.
+    pub const fn memory_valid(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// `pcie_sbdf` is valid.
+    pub const fn pcie_valid(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SET_ERROR_TYPE_WITH_ADDRESS Data Structure
+///
+/// Where OSPM puts the error type and its target, for the `SetErrorTypeWithAddress` action.
+/// It lives at the register region address of that action's entry.
+pub struct SetErrorTypeWithAddress {
+    /// The error type to inject (exactly one bit).
+    pub error_type: ErrorTypes,
+    /// Offset, from the start of this structure, of the vendor error type extension structure (0 if there isn't one).
+    ///
+    /// Firmware fills this one in; leave it as it is.
+    pub vendor_error_type_extension_offset: u32,
+    pub flags: SetErrorTypeWithAddressFlags,
+    /// The APIC ID of the processor to inject the error on.
+    pub apic_id: u32,
+    /// The physical address to inject a memory error at.
+    pub memory_address: u64,
+    /// The mask of the address bits that matter (a range of addresses to inject at).
+    pub memory_address_range: u64,
+    /// The segment, bus, device and function (bits [[31:24]], [[23:16]], [[15:11]] and [[10:8]]) of the PCIe device to inject at.
+    pub pcie_sbdf: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Trigger Error Action Table
+///
+/// Found at the address `GetTriggerErrorActionTable` returns.
+/// Its instruction entries (all with the `TriggerError` action) make the injected error actually happen, and it may have none.
+pub struct TriggerErrorActionTable {
+    /// 16
+    pub header_size: u32,
+    pub revision: u32,
+    /// Length, in bytes, of the whole table.
+    pub table_size: u32,
+    pub entry_count: u32,
+    pub trigger_actions: [APEIInstructionEntry; 0],
+}
+impl TriggerErrorActionTable {
+    pub const fn instruction_entries(&self) -> &[APEIInstructionEntry] {
+        instruction_entries(
+            self,
+            16,
+            self.table_size as usize,
+            self.entry_count as usize,
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Error Injection Table (EINJ)
+///
+/// Describes how OSPM injects hardware errors into the platform, so error handling can be tested without waiting for real errors.
+///
+/// Every injection action is a sequence of instruction entries working on Generic Address Structure registers.
+pub struct EINJ {
+    /// - **Signature** - "EINJ"
+    /// - **Revision** - 2
+    pub header: SDTHeader,
+    /// Length, in bytes, of the injection header (the fields after the SDT header, up to the instruction entries).
+    pub injection_header_size: u32,
+    /// Reserved, must be 0.
+    pub injection_flags: u8,
+    reserved: [u8; 3],
+    pub injection_entry_count: u32,
+    /// The injection instruction entries.
+    pub injection_instruction_entries: [APEIInstructionEntry; 0],
+}
+impl EINJ {
+    pub const fn instruction_entries(&self) -> &[APEIInstructionEntry] {
+        instruction_entries(
+            self,
+            SDT_HEADER_SIZE + 12,
+            self.header.length as usize,
+            self.injection_entry_count as usize,
+        )
+    }
+    /// The instruction entries of `action`, in the order they need to be run.
+    pub fn action_entries(
+        &self,
+        action: InjectionAction,
+    ) -> impl Iterator<Item = &APEIInstructionEntry> + '_ {
+        self.instruction_entries()
+            .iter()
+            .filter(move |entry| entry.action == action.as_u8())
+    }
+    /// The physical address of the SET_ERROR_TYPE_WITH_ADDRESS data structure, if the platform has one.
+    pub fn set_error_type_with_address(&self) -> Option<u64> {
+        self.action_entries(InjectionAction::SetErrorTypeWithAddress)
+            .find(|entry| {
+                entry.instruction() == APEIInstruction::WriteRegister
+                    && entry.register_region.address_space_id == 0
+            })
+            .map(|entry| entry.register_region.address)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the EINJ error injector can run into.
+pub enum EINJError {
+    /// Running an action's instructions failed.
+    Instruction(APEIError),
+    /// `CheckBusyStatus` never cleared within the poll limit.
+    Timeout,
+    /// The platform finished the operation with a status other than `Success`.
+    Status(InjectionCommandStatus),
+    /// The platform can't inject this error type (or it isn't exactly one type).
+    UnsupportedErrorType(ErrorTypes),
+    /// The injection has a target, but the platform has no SET_ERROR_TYPE_WITH_ADDRESS data structure.
+    TargetNotSupported,
+}
+impl From<APEIError> for EINJError {
+    fn from(error: APEIError) -> Self {
+        Self::Instruction(error)
+    }
+}
+
+#[derive(Copy, Clone)]
+/// What to inject, and (optionally) where.
+pub struct ErrorInjection {
+    /// Exactly one error type.
+    pub error_type: ErrorTypes,
+    /// The APIC ID of the processor to inject on.
+    pub apic_id: Option<u32>,
+    /// The physical address and address mask to inject at.
+    pub memory: Option<(u64, u64)>,
+    /// The SBDF of the PCIe device to inject at (see `SetErrorTypeWithAddress::pcie_sbdf`).
+    pub pcie_sbdf: Option<u32>,
+}
+impl ErrorInjection {
+    pub const fn new(error_type: ErrorTypes) -> Self {
+        Self {
+            error_type,
+            apic_id: None,
+            memory: None,
+            pcie_sbdf: None,
+        }
+    }
+    const fn has_target(&self) -> bool {
+        self.apic_id.is_some() || self.memory.is_some() || self.pcie_sbdf.is_some()
+    }
+}
+
+/// ## EINJ Error Injector
+///
+/// Injects errors through the EINJ injection actions.
+///
+/// An injection is two steps: `inject` sets the error up and has the platform execute it, handing back the Trigger Error Action Table address.
+/// Map that table and pass it to `trigger`, which makes the error happen and ends the operation.
+pub struct ErrorInjector<'a, P: APEIPlatform> {
+    einj: &'a EINJ,
+    interpreter: APEIInterpreter,
+    platform: P,
+    /// How many times to poll `CheckBusyStatus` before returning `EINJError::Timeout`.
+    pub busy_poll_limit: u32,
+}
+impl<'a, P: APEIPlatform> ErrorInjector<'a, P> {
+    pub fn new(einj: &'a EINJ, platform: P) -> Self {
+        Self {
+            einj,
+            interpreter: APEIInterpreter::new(),
+            platform,
+            busy_poll_limit: DEFAULT_BUSY_POLL_LIMIT,
+        }
+    }
+    /// Gives back the platform this injector was built with.
+    pub fn into_inner(self) -> P {
+        self.platform
+    }
+    /// The interpreter running the actions (to tune its instruction limit, for example).
+    pub fn interpreter(&mut self) -> &mut APEIInterpreter {
+        &mut self.interpreter
+    }
+
+    /// Runs the instruction entries of `action` with `input`, returning what they read.
+    pub fn run(&mut self, action: InjectionAction, input: u64) -> Result<u64, EINJError> {
+        let entries = self.einj.instruction_entries();
+        Ok(self
+            .interpreter
+            .run(&mut self.platform, entries, action.as_u8(), input)?)
+    }
+    /// The error types the platform can inject.
+    pub fn error_types(&mut self) -> Result<ErrorTypes, EINJError> {
+        Ok(ErrorTypes(
+            self.run(InjectionAction::GetErrorType, 0)? as u32
+        ))
+    }
+
+    /// Sets up `injection` and has the platform execute it, returning the physical address of the Trigger Error Action Table.
+    ///
+    /// On success, the operation is still open: call `trigger` next.
+    pub fn inject(&mut self, injection: &ErrorInjection) -> Result<u64, EINJError> {
+        let error_type = injection.error_type;
+        if error_type.0.count_ones() != 1 || !self.error_types()?.contains(error_type) {
+            return Err(EINJError::UnsupportedErrorType(error_type));
+        }
+        let with_address = self.einj.set_error_type_with_address();
+        if injection.has_target() && with_address.is_none() {
+            return Err(EINJError::TargetNotSupported);
+        }
+
+        self.run(InjectionAction::BeginInjectionOperation, 0)?;
+        let result = self.set_up(injection, with_address);
+        if result.is_err() {
+            // The setup's error says more than EndOperation's.
+            let _ = self.run(InjectionAction::EndOperation, 0);
+        }
+        result
+    }
+    fn set_up(
+        &mut self,
+        injection: &ErrorInjection,
+        with_address: Option<u64>,
+    ) -> Result<u64, EINJError> {
+        match with_address {
+            Some(address) => {
+                let size = core::mem::size_of::<SetErrorTypeWithAddress>();
+                let mut bytes = [0u8; core::mem::size_of::<SetErrorTypeWithAddress>()];
+                self.platform.read_memory(address, &mut bytes);
+                // SAFETY: the buffer is exactly one packed SetErrorTypeWithAddress.
+                let mut structure: SetErrorTypeWithAddress =
+                    unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const _) };
+                let mut flags = 0;
+                structure.error_type = injection.error_type;
+                if let Some(apic_id) = injection.apic_id {
+                    structure.apic_id = apic_id;
+                    flags |= 0b001;
+                }
+                if let Some((address, mask)) = injection.memory {
+                    structure.memory_address = address;
+                    structure.memory_address_range = mask;
+                    flags |= 0b010;
+                }
+                if let Some(sbdf) = injection.pcie_sbdf {
+                    structure.pcie_sbdf = sbdf;
+                    flags |= 0b100;
+                }
+                structure.flags = SetErrorTypeWithAddressFlags(flags);
+                // SAFETY: SetErrorTypeWithAddress is plain old data.
+                let bytes = unsafe {
+                    core::slice::from_raw_parts(&structure as *const _ as *const u8, size)
+                };
+                self.platform.write_memory(address, bytes);
+            }
+            None => {
+                self.run(InjectionAction::SetErrorType, injection.error_type.0 as u64)?;
+            }
+        }
+
+        self.run(InjectionAction::ExecuteOperation, 0)?;
+        let mut polls = 0;
+        while self.run(InjectionAction::CheckBusyStatus, 0)? & 1 != 0 {
+            polls += 1;
+            if polls >= self.busy_poll_limit {
+                return Err(EINJError::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+        match InjectionCommandStatus::from_u64(self.run(InjectionAction::GetCommandStatus, 0)?) {
+            InjectionCommandStatus::Success => {}
+            status => return Err(EINJError::Status(status)),
+        }
+        self.run(InjectionAction::GetTriggerErrorActionTable, 0)
+    }
+    /// Runs the trigger actions of `table` (the one `inject` pointed at), then ends the operation.
+    pub fn trigger(&mut self, table: &TriggerErrorActionTable) -> Result<(), EINJError> {
+        let result = self.interpreter.run(
+            &mut self.platform,
+            table.instruction_entries(),
+            InjectionAction::TriggerError.as_u8(),
+            0,
+        );
+        let ended = self.run(InjectionAction::EndOperation, 0);
+        // The trigger's error says more than EndOperation's.
+        result?;
+        ended?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apei::fake::*;
+
+    // Registers of the simulated injector.
+    const OPERATION: u64 = 0;
+    const ERROR_TYPE: u64 = 1;
+    const EXECUTE: u64 = 2;
+    const BUSY: u64 = 3;
+    const STATUS: u64 = 4;
+    const SUPPORTED: u64 = 5;
+    const TRIGGER_TABLE: u64 = 6;
+    const TRIGGER: u64 = 7;
+
+    /// Where the SET_ERROR_TYPE_WITH_ADDRESS data structure is, in the simulated memory.
+    const WITH_ADDRESS: u64 = MEMORY_BASE + 0x100;
+    const END: usize = 1;
+
+    /// Executing finishes with `status`, or never finishes if `stuck`.
+    struct Injector {
+        status: u64,
+        stuck: bool,
+    }
+    impl Firmware for Injector {
+        fn written(
+            &mut self,
+            registers: &mut [u64; REGISTERS],
+            _: &mut [u8; MEMORY_SIZE],
+            index: usize,
+        ) {
+            if index as u64 == EXECUTE {
+                registers[STATUS as usize] = self.status;
+                registers[BUSY as usize] = self.stuck as u64;
+            }
+        }
+    }
+
+    fn platform(status: u64, stuck: bool) -> FakePlatform<Injector> {
+        let mut platform = FakePlatform::new(Injector { status, stuck });
+        platform.registers[SUPPORTED as usize] =
+            (ErrorTypes::MEMORY_CORRECTABLE.bits() | ErrorTypes::PCIE_CORRECTABLE.bits()) as u64;
+        platform.registers[TRIGGER_TABLE as usize] = 0xCAFE_0000;
+        platform
+    }
+
+    const ENTRIES: [APEIInstructionEntry; 8] = [
+        entry(0x00, WRITE_REGISTER_VALUE, OPERATION, 1),
+        entry(0x04, WRITE_REGISTER_VALUE, OPERATION, 0),
+        entry(0x01, READ_REGISTER, TRIGGER_TABLE, 0),
+        entry(0x02, WRITE_REGISTER, ERROR_TYPE, 0),
+        entry(0x03, READ_REGISTER, SUPPORTED, 0),
+        entry(0x05, WRITE_REGISTER_VALUE, EXECUTE, 1),
+        masked_entry(0x06, READ_REGISTER_VALUE, BUSY, 1, 1, false),
+        entry(0x07, READ_REGISTER, STATUS, 0),
+    ];
+    const WITH_ADDRESS_ENTRY: APEIInstructionEntry = {
+        let mut entry = entry(0x08, WRITE_REGISTER, WITH_ADDRESS, 0);
+        entry.register_region.address_space_id = 0;
+        entry
+    };
+
+    fn einj_table(entries: &[APEIInstructionEntry]) -> [u64; 128] {
+        let mut header = [0u8; SDT_HEADER_SIZE + 12];
+        header[..4].copy_from_slice(b"EINJ");
+        header[SDT_HEADER_SIZE..][..4].copy_from_slice(&12u32.to_le_bytes());
+        header[SDT_HEADER_SIZE + 8..][..4].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+        let length = header.len() + core::mem::size_of_val(entries);
+        header[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+        table(&header, entries)
+    }
+    fn einj(table: &[u64; 128]) -> &EINJ {
+        // SAFETY: the table is laid out like an EINJ, and aligned.
+        unsafe { &*(table.as_ptr() as *const EINJ) }
+    }
+
+    #[test]
+    fn error_types() {
+        let table = einj_table(&ENTRIES);
+        let mut injector = ErrorInjector::new(einj(&table), platform(0, false));
+        let types = injector.error_types().unwrap();
+        assert!(types.memory_correctable() && types.pcie_correctable());
+        assert!(!types.processor_correctable());
+
+        for error_type in [
+            ErrorTypes::PROCESSOR_CORRECTABLE,
+            ErrorTypes::new(0b1001000),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                injector.inject(&ErrorInjection::new(*error_type)),
+                Err(EINJError::UnsupportedErrorType(*error_type))
+            );
+        }
+    }
+
+    #[test]
+    fn inject_error_type() {
+        let table = einj_table(&ENTRIES);
+        let mut injector = ErrorInjector::new(einj(&table), platform(0, false));
+        let mut injection = ErrorInjection::new(ErrorTypes::MEMORY_CORRECTABLE);
+        assert_eq!(injector.inject(&injection), Ok(0xCAFE_0000));
+        injection.apic_id = Some(1);
+        assert_eq!(
+            injector.inject(&injection),
+            Err(EINJError::TargetNotSupported)
+        );
+
+        let platform = injector.into_inner();
+        assert_eq!(
+            platform.registers[ERROR_TYPE as usize],
+            ErrorTypes::MEMORY_CORRECTABLE.bits() as u64
+        );
+        // The operation stays open for `trigger`.
+        assert_eq!(platform.registers[OPERATION as usize], 1);
+    }
+
+    #[test]
+    fn inject_error_type_with_address() {
+        let mut entries = [WITH_ADDRESS_ENTRY; 9];
+        entries[..8].copy_from_slice(&ENTRIES);
+        let table = einj_table(&entries);
+        let mut platform = platform(0, false);
+        // Firmware's vendor error type extension offset.
+        let offset = (WITH_ADDRESS - MEMORY_BASE) as usize + 4;
+        platform.memory[offset..][..4].copy_from_slice(&0x40u32.to_le_bytes());
+        let mut injector = ErrorInjector::new(einj(&table), platform);
+
+        let mut injection = ErrorInjection::new(ErrorTypes::MEMORY_CORRECTABLE);
+        injection.apic_id = Some(3);
+        injection.memory = Some((0x8000_0000, !0xFFF));
+        assert_eq!(injector.inject(&injection), Ok(0xCAFE_0000));
+
+        let mut platform = injector.into_inner();
+        let mut bytes = [0u8; core::mem::size_of::<SetErrorTypeWithAddress>()];
+        platform.read_memory(WITH_ADDRESS, &mut bytes);
+        // SAFETY: the buffer is exactly one packed SetErrorTypeWithAddress.
+        let structure: SetErrorTypeWithAddress =
+            unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const _) };
+        assert_eq!({ structure.error_type }, ErrorTypes::MEMORY_CORRECTABLE);
+        assert_eq!({ structure.vendor_error_type_extension_offset }, 0x40);
+        let flags = structure.flags;
+        assert!(flags.processor_valid() && flags.memory_valid() && !flags.pcie_valid());
+        assert_eq!({ structure.apic_id }, 3);
+        assert_eq!({ structure.memory_address }, 0x8000_0000);
+        assert_eq!({ structure.memory_address_range }, !0xFFF);
+        assert_eq!(platform.registers[ERROR_TYPE as usize], 0);
+    }
+
+    #[test]
+    fn timeout() {
+        let table = einj_table(&ENTRIES);
+        let mut injector = ErrorInjector::new(einj(&table), platform(0, true));
+        injector.busy_poll_limit = 3;
+        let injection = ErrorInjection::new(ErrorTypes::PCIE_CORRECTABLE);
+        assert_eq!(injector.inject(&injection), Err(EINJError::Timeout));
+        assert_eq!(injector.into_inner().registers[OPERATION as usize], 0);
+    }
+
+    #[test]
+    fn failed_status() {
+        let mut entries = ENTRIES;
+        let table = einj_table(&entries);
+        let mut injector = ErrorInjector::new(einj(&table), platform(2, false));
+        let injection = ErrorInjection::new(ErrorTypes::PCIE_CORRECTABLE);
+        let error = Err(EINJError::Status(InjectionCommandStatus::InvalidAccess));
+        assert_eq!(injector.inject(&injection), error);
+        assert_eq!(injector.into_inner().registers[OPERATION as usize], 0);
+
+        // Even if EndOperation fails too, the status is what comes back.
+        entries[END].instruction = 0x42;
+        let table = einj_table(&entries);
+        let mut injector = ErrorInjector::new(einj(&table), platform(2, false));
+        assert_eq!(injector.inject(&injection), error);
+    }
+
+    #[test]
+    fn trigger() {
+        let mut header = [0u8; 16];
+        header[..4].copy_from_slice(&16u32.to_le_bytes());
+        header[8..12].copy_from_slice(&(16 + 32u32).to_le_bytes());
+        header[12..].copy_from_slice(&1u32.to_le_bytes());
+        let trigger_table = table(&header, &[entry(0xFF, WRITE_REGISTER_VALUE, TRIGGER, 0xE1)]);
+        // SAFETY: the table is laid out like a Trigger Error Action Table, and aligned.
+        let trigger_table = unsafe { &*(trigger_table.as_ptr() as *const TriggerErrorActionTable) };
+
+        let actions = einj_table(&ENTRIES);
+        let mut injector = ErrorInjector::new(einj(&actions), platform(0, false));
+        injector
+            .inject(&ErrorInjection::new(ErrorTypes::MEMORY_CORRECTABLE))
+            .unwrap();
+        injector.trigger(trigger_table).unwrap();
+        let registers = injector.into_inner().registers;
+        assert_eq!(registers[TRIGGER as usize], 0xE1);
+        assert_eq!(registers[OPERATION as usize], 0);
+
+        let broken = table(&header, &[entry(0xFF, 0x42, TRIGGER, 0)]);
+        // SAFETY: as above.
+        let broken = unsafe { &*(broken.as_ptr() as *const TriggerErrorActionTable) };
+        let mut entries = ENTRIES;
+        entries[END].instruction = 0x43;
+        let actions = einj_table(&entries);
+        let mut injector = ErrorInjector::new(einj(&actions), platform(0, false));
+        assert_eq!(
+            injector.trigger(broken),
+            Err(EINJError::Instruction(APEIError::UnknownInstruction(0x42)))
+        );
+    }
+}
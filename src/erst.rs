@@ -0,0 +1,614 @@
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    apei::{APEIError, APEIInstructionEntry, APEIInterpreter, APEIPlatform, instruction_entries},
+};
+
+/// The record ID `GetRecordIdentifier` returns when the store is empty.
+pub const INVALID_RECORD_ID: u64 = u64::MAX;
+/// The number of `CheckBusyStatus` polls an `ErrorRecordStore` makes before giving up, unless told otherwise.
+pub const DEFAULT_BUSY_POLL_LIMIT: u32 = 100_000;
+
+// Size of the CPER record header, and the offset of its Record Length field.
+const RECORD_HEADER_SIZE: usize = 128;
+const RECORD_LENGTH_OFFSET: u64 = 20;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Error Record Serialization Actions
+pub enum SerializationAction {
+    /// Starts a write operation (saving a record to the persistent store).
+    BeginWriteOperation,
+    /// Starts a read operation (reading a record from the persistent store).
+    BeginReadOperation,
+    /// Starts a clear operation (removing a record from the persistent store).
+    BeginClearOperation,
+    /// Ends the current operation.
+    EndOperation,
+    /// Sets the offset, in the error log address range, of the record to write or read into.
+    SetRecordOffset,
+    /// Tells the platform to carry out the current operation.
+    ExecuteOperation,
+    /// Returns 1 while the platform is still busy with the operation.
+    CheckBusyStatus,
+    /// Returns the status of the last operation (see `SerializationCommandStatus`).
+    GetCommandStatus,
+    /// Returns the ID of the first record in the store (or the next one, while enumerating).
+    GetRecordIdentifier,
+    /// Sets the ID of the record to read or clear.
+    SetRecordIdentifier,
+    /// Returns the number of records in the store.
+    GetRecordCount,
+    /// Starts a dummy write operation (to check the store works without writing anything).
+    BeginDummyWriteOperation,
+    /// Returns the physical address of the error log address range.
+    GetErrorLogAddressRange,
+    /// Returns the length of the error log address range.
+    GetErrorLogAddressRangeLength,
+    /// Returns the attributes of the error log address range (see `ErrorLogAddressRangeAttributes`).
+    GetErrorLogAddressRangeAttributes,
+    /// Returns the nominal and maximum execution time of `ExecuteOperation`.
+    GetExecuteOperationTimings,
+    Unknown(u8),
+}
+impl SerializationAction {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0x00 => Self::BeginWriteOperation,
+            0x01 => Self::BeginReadOperation,
+            0x02 => Self::BeginClearOperation,
+            0x03 => Self::EndOperation,
+            0x04 => Self::SetRecordOffset,
+            0x05 => Self::ExecuteOperation,
+            0x06 => Self::CheckBusyStatus,
+            0x07 => Self::GetCommandStatus,
+            0x08 => Self::GetRecordIdentifier,
+            0x09 => Self::SetRecordIdentifier,
+            0x0A => Self::GetRecordCount,
+            0x0B => Self::BeginDummyWriteOperation,
+            0x0D => Self::GetErrorLogAddressRange,
+            0x0E => Self::GetErrorLogAddressRangeLength,
+            0x0F => Self::GetErrorLogAddressRangeAttributes,
+            0x10 => Self::GetExecuteOperationTimings,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::BeginWriteOperation => 0x00,
+            Self::BeginReadOperation => 0x01,
+            Self::BeginClearOperation => 0x02,
+            Self::EndOperation => 0x03,
+            Self::SetRecordOffset => 0x04,
+            Self::ExecuteOperation => 0x05,
+            Self::CheckBusyStatus => 0x06,
+            Self::GetCommandStatus => 0x07,
+            Self::GetRecordIdentifier => 0x08,
+            Self::SetRecordIdentifier => 0x09,
+            Self::GetRecordCount => 0x0A,
+            Self::BeginDummyWriteOperation => 0x0B,
+            Self::GetErrorLogAddressRange => 0x0D,
+            Self::GetErrorLogAddressRangeLength => 0x0E,
+            Self::GetErrorLogAddressRangeAttributes => 0x0F,
+            Self::GetExecuteOperationTimings => 0x10,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// What `GetCommandStatus` returns.
+pub enum SerializationCommandStatus {
+    Success,
+    NotEnoughSpace,
+    HardwareNotAvailable,
+    Failed,
+    RecordStoreEmpty,
+    RecordNotFound,
+    Unknown(u64),
+}
+impl SerializationCommandStatus {
+    pub const fn from_u64(value: u64) -> Self {
+        match value {
+            0x00 => Self::Success,
+            0x01 => Self::NotEnoughSpace,
+            0x02 => Self::HardwareNotAvailable,
+            0x03 => Self::Failed,
+            0x04 => Self::RecordStoreEmpty,
+            0x05 => Self::RecordNotFound,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+/// What `GetErrorLogAddressRangeAttributes` returns.
+pub struct ErrorLogAddressRangeAttributes(u64);
+impl ErrorLogAddressRangeAttributes {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+    /// If set, the error log address range is non-volatile memory, and is the persistent store itself.
+    pub const fn non_volatile(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// If set, the error log address range is slow to access (a flash part behind a slow bus, for example).
+    pub const fn slow(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Error Record Serialization Table (ERST)
+///
+/// Describes how OSPM saves error records to, reads them from, and clears them from a platform persistent store (so they survive a reboot).
+///
+/// Every serialization action is a sequence of instruction entries working on Generic Address Structure registers.
+pub struct ERST {
+    /// - **Signature** - "ERST"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    /// Length, in bytes, of the serialization header (the fields after the SDT header, up to the instruction entries).
+    pub serialization_header_size: u32,
+    reserved: u32,
+    pub instruction_entry_count: u32,
+    /// The serialization instruction entries.
+    pub serialization_instruction_entries: [APEIInstructionEntry; 0],
+}
+impl ERST {
+    pub const fn instruction_entries(&self) -> &[APEIInstructionEntry] {
+        instruction_entries(
+            self,
+            SDT_HEADER_SIZE + 12,
+            self.header.length as usize,
+            self.instruction_entry_count as usize,
+        )
+    }
+    /// The instruction entries of `action`, in the order they need to be run.
+    pub fn action_entries(
+        &self,
+        action: SerializationAction,
+    ) -> impl Iterator<Item = &APEIInstructionEntry> + '_ {
+        self.instruction_entries()
+            .iter()
+            .filter(move |entry| entry.action == action.as_u8())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the ERST record store can run into.
+pub enum ERSTError {
+    /// Running an action's instructions failed.
+    Instruction(APEIError),
+    /// `CheckBusyStatus` never cleared within the poll limit.
+    Timeout,
+    /// The platform finished the operation with a status other than `Success`.
+    Status(SerializationCommandStatus),
+    /// The record (of the given length) doesn't fit in the error log address range.
+    RecordTooLarge(usize),
+    /// The buffer is too small for the record (of the given length).
+    BufferTooSmall(usize),
+    /// The record read back doesn't have a sane CPER record length.
+    InvalidRecord,
+}
+impl From<APEIError> for ERSTError {
+    fn from(error: APEIError) -> Self {
+        Self::Instruction(error)
+    }
+}
+
+/// ## ERST Persistent Error Record Store
+///
+/// Saves, reads and clears CPER error records through the ERST serialization actions.
+/// Records go through the error log address range: they're copied there before a write, and copied out after a read.
+///
+/// If the error log address range is `non_volatile`, it IS the store, and the platform only keeps track of records in it.
+/// The same actions still work; there's just no copy behind them.
+pub struct ErrorRecordStore<'a, P: APEIPlatform> {
+    erst: &'a ERST,
+    interpreter: APEIInterpreter,
+    platform: P,
+    /// Physical address of the error log address range.
+    pub error_log_address_range: u64,
+    /// Length, in bytes, of the error log address range.
+    pub error_log_address_range_length: u64,
+    pub error_log_address_range_attributes: ErrorLogAddressRangeAttributes,
+    /// How many times to poll `CheckBusyStatus` before returning `ERSTError::Timeout`.
+    pub busy_poll_limit: u32,
+}
+impl<'a, P: APEIPlatform> ErrorRecordStore<'a, P> {
+    /// Sets up the store, asking the platform where the error log address range is.
+    pub fn new(erst: &'a ERST, platform: P) -> Result<Self, ERSTError> {
+        let mut store = Self {
+            erst,
+            interpreter: APEIInterpreter::new(),
+            platform,
+            error_log_address_range: 0,
+            error_log_address_range_length: 0,
+            error_log_address_range_attributes: ErrorLogAddressRangeAttributes::new(0),
+            busy_poll_limit: DEFAULT_BUSY_POLL_LIMIT,
+        };
+        store.error_log_address_range =
+            store.run(SerializationAction::GetErrorLogAddressRange, 0)?;
+        store.error_log_address_range_length =
+            store.run(SerializationAction::GetErrorLogAddressRangeLength, 0)?;
+        store.error_log_address_range_attributes = ErrorLogAddressRangeAttributes::new(
+            store.run(SerializationAction::GetErrorLogAddressRangeAttributes, 0)?,
+        );
+        Ok(store)
+    }
+    /// Gives back the platform this store was built with.
+    pub fn into_inner(self) -> P {
+        self.platform
+    }
+    /// The interpreter running the actions (to tune its instruction limit, for example).
+    pub fn interpreter(&mut self) -> &mut APEIInterpreter {
+        &mut self.interpreter
+    }
+
+    /// Runs the instruction entries of `action` with `input`, returning what they read.
+    pub fn run(&mut self, action: SerializationAction, input: u64) -> Result<u64, ERSTError> {
+        let entries = self.erst.instruction_entries();
+        Ok(self
+            .interpreter
+            .run(&mut self.platform, entries, action.as_u8(), input)?)
+    }
+    // ExecuteOperation, wait for the platform, then check how it went.
+    fn execute(&mut self) -> Result<(), ERSTError> {
+        self.run(SerializationAction::ExecuteOperation, 0)?;
+        let mut polls = 0;
+        while self.run(SerializationAction::CheckBusyStatus, 0)? & 1 != 0 {
+            polls += 1;
+            if polls >= self.busy_poll_limit {
+                return Err(ERSTError::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+        match SerializationCommandStatus::from_u64(
+            self.run(SerializationAction::GetCommandStatus, 0)?,
+        ) {
+            SerializationCommandStatus::Success => Ok(()),
+            status => Err(ERSTError::Status(status)),
+        }
+    }
+    // Runs an operation (already begun) to the end, running EndOperation even if it failed.
+    fn finish(&mut self) -> Result<(), ERSTError> {
+        let result = self.execute();
+        let ended = self.run(SerializationAction::EndOperation, 0);
+        // The operation's own error says more than EndOperation's.
+        result.and(ended.map(|_| ()))
+    }
+
+    /// The number of records in the store.
+    pub fn record_count(&mut self) -> Result<u64, ERSTError> {
+        self.run(SerializationAction::GetRecordCount, 0)
+    }
+    /// The ID of a record in the store, or `None` if the store is empty.
+    ///
+    /// The platform moves on to the next record each time, wrapping around at the end; `record_ids` takes care of that.
+    pub fn next_record_id(&mut self) -> Result<Option<u64>, ERSTError> {
+        match self.run(SerializationAction::GetRecordIdentifier, 0)? {
+            INVALID_RECORD_ID => Ok(None),
+            id => Ok(Some(id)),
+        }
+    }
+    /// Iterates over the IDs of the records in the store, each once.
+    pub fn record_ids(&mut self) -> RecordIDIter<'_, 'a, P> {
+        RecordIDIter {
+            store: self,
+            first: None,
+            remaining: None,
+        }
+    }
+
+    /// Saves `record` (a whole CPER record) to the store.
+    pub fn write(&mut self, record: &[u8]) -> Result<(), ERSTError> {
+        if record.len() as u64 > self.error_log_address_range_length {
+            return Err(ERSTError::RecordTooLarge(record.len()));
+        }
+        let range = self.error_log_address_range;
+        self.platform.write_memory(range, record);
+        self.run(SerializationAction::BeginWriteOperation, 0)?;
+        self.run(SerializationAction::SetRecordOffset, 0)?;
+        self.finish()
+    }
+    /// Reads the record `record_id` into `buffer`, returning its length.
+    pub fn read(&mut self, record_id: u64, buffer: &mut [u8]) -> Result<usize, ERSTError> {
+        self.run(SerializationAction::BeginReadOperation, 0)?;
+        self.run(SerializationAction::SetRecordOffset, 0)?;
+        self.run(SerializationAction::SetRecordIdentifier, record_id)?;
+        self.finish()?;
+
+        let range = self.error_log_address_range;
+        let mut length = [0u8; 4];
+        self.platform
+            .read_memory(range + RECORD_LENGTH_OFFSET, &mut length);
+        let length = u32::from_le_bytes(length) as usize;
+        if length < RECORD_HEADER_SIZE || length as u64 > self.error_log_address_range_length {
+            return Err(ERSTError::InvalidRecord);
+        }
+        if buffer.len() < length {
+            return Err(ERSTError::BufferTooSmall(length));
+        }
+        self.platform.read_memory(range, &mut buffer[..length]);
+        Ok(length)
+    }
+    /// Removes the record `record_id` from the store.
+    pub fn clear(&mut self, record_id: u64) -> Result<(), ERSTError> {
+        self.run(SerializationAction::BeginClearOperation, 0)?;
+        self.run(SerializationAction::SetRecordIdentifier, record_id)?;
+        self.finish()
+    }
+}
+
+/// Iterator over the record IDs of an `ErrorRecordStore`.
+///
+/// Stops once the platform wraps around to the first ID, or after `GetRecordCount` IDs, whichever comes first.
+pub struct RecordIDIter<'s, 'a, P: APEIPlatform> {
+    store: &'s mut ErrorRecordStore<'a, P>,
+    first: Option<u64>,
+    remaining: Option<u64>,
+}
+impl<P: APEIPlatform> Iterator for RecordIDIter<'_, '_, P> {
+    type Item = Result<u64, ERSTError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = match self.remaining {
+            Some(remaining) => remaining,
+            None => match self.store.record_count() {
+                Ok(count) => count,
+                Err(error) => {
+                    self.remaining = Some(0);
+                    return Some(Err(error));
+                }
+            },
+        };
+        if remaining == 0 {
+            self.remaining = Some(0);
+            return None;
+        }
+        self.remaining = Some(remaining - 1);
+        match self.store.next_record_id() {
+            Ok(Some(id)) if self.first != Some(id) => {
+                if self.first.is_none() {
+                    self.first = Some(id);
+                }
+                Some(Ok(id))
+            }
+            Ok(_) => {
+                self.remaining = Some(0);
+                None
+            }
+            Err(error) => {
+                self.remaining = Some(0);
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apei::fake::*;
+
+    // Registers of the simulated store.
+    const OPERATION: u64 = 0;
+    const OFFSET: u64 = 1;
+    const EXECUTE: u64 = 2;
+    const BUSY: u64 = 3;
+    const STATUS: u64 = 4;
+    const RECORD_ID: u64 = 5;
+    const COUNT: u64 = 6;
+    const RANGE: u64 = 7;
+    const RANGE_LENGTH: u64 = 8;
+    const RANGE_ATTRIBUTES: u64 = 9;
+
+    const RANGE_SIZE: usize = 256;
+    const RECORD_ID_OFFSET: usize = 96;
+    const SLOTS: usize = 2;
+
+    /// A persistent store of `SLOTS` records, behind the registers above.
+    struct Store {
+        ids: [Option<u64>; SLOTS],
+        records: [[u8; RANGE_SIZE]; SLOTS],
+        cursor: usize,
+        busy: u32,
+    }
+    impl Store {
+        fn new() -> Self {
+            Self {
+                ids: [None; SLOTS],
+                records: [[0; RANGE_SIZE]; SLOTS],
+                cursor: 0,
+                busy: 0,
+            }
+        }
+        fn find(&self, id: u64) -> Option<usize> {
+            self.ids.iter().position(|slot| *slot == Some(id))
+        }
+        fn execute(&mut self, registers: &[u64; REGISTERS], memory: &mut [u8; MEMORY_SIZE]) -> u64 {
+            let range = &mut memory[registers[OFFSET as usize] as usize..][..RANGE_SIZE];
+            match registers[OPERATION as usize] {
+                1 => {
+                    let mut id = [0; 8];
+                    id.copy_from_slice(&range[RECORD_ID_OFFSET..][..8]);
+                    let id = u64::from_le_bytes(id);
+                    let Some(slot) = self
+                        .find(id)
+                        .or_else(|| self.ids.iter().position(Option::is_none))
+                    else {
+                        return 1;
+                    };
+                    self.ids[slot] = Some(id);
+                    self.records[slot].copy_from_slice(range);
+                    0
+                }
+                2 => match self.find(registers[RECORD_ID as usize]) {
+                    Some(slot) => {
+                        range.copy_from_slice(&self.records[slot]);
+                        0
+                    }
+                    None => 5,
+                },
+                3 => match self.find(registers[RECORD_ID as usize]) {
+                    Some(slot) => {
+                        self.ids[slot] = None;
+                        0
+                    }
+                    None => 5,
+                },
+                _ => 3,
+            }
+        }
+    }
+    impl Firmware for Store {
+        fn reading(
+            &mut self,
+            registers: &mut [u64; REGISTERS],
+            _: &mut [u8; MEMORY_SIZE],
+            index: usize,
+        ) {
+            match index as u64 {
+                BUSY => {
+                    self.busy = self.busy.saturating_sub(1);
+                    registers[index] = (self.busy > 0) as u64;
+                }
+                RECORD_ID => {
+                    let stored = (0..SLOTS)
+                        .filter_map(|i| self.ids[(self.cursor + i) % SLOTS].map(|id| (i, id)))
+                        .next();
+                    registers[index] = match stored {
+                        Some((i, id)) => {
+                            self.cursor = (self.cursor + i + 1) % SLOTS;
+                            id
+                        }
+                        None => INVALID_RECORD_ID,
+                    };
+                }
+                COUNT => registers[index] = self.ids.iter().flatten().count() as u64,
+                RANGE => registers[index] = MEMORY_BASE,
+                RANGE_LENGTH => registers[index] = RANGE_SIZE as u64,
+                _ => {}
+            }
+        }
+        fn written(
+            &mut self,
+            registers: &mut [u64; REGISTERS],
+            memory: &mut [u8; MEMORY_SIZE],
+            index: usize,
+        ) {
+            if index as u64 == EXECUTE {
+                registers[STATUS as usize] = self.execute(registers, memory);
+                self.busy = 3;
+            }
+        }
+    }
+
+    const ENTRIES: [APEIInstructionEntry; 16] = [
+        entry(0x00, WRITE_REGISTER_VALUE, OPERATION, 1),
+        entry(0x01, WRITE_REGISTER_VALUE, OPERATION, 2),
+        entry(0x02, WRITE_REGISTER_VALUE, OPERATION, 3),
+        entry(0x03, WRITE_REGISTER_VALUE, OPERATION, 0),
+        entry(0x04, WRITE_REGISTER, OFFSET, 0),
+        entry(0x05, WRITE_REGISTER_VALUE, EXECUTE, 1),
+        masked_entry(0x06, READ_REGISTER_VALUE, BUSY, 1, 1, false),
+        entry(0x07, READ_REGISTER, STATUS, 0),
+        entry(0x08, READ_REGISTER, RECORD_ID, 0),
+        entry(0x09, WRITE_REGISTER, RECORD_ID, 0),
+        entry(0x0A, READ_REGISTER, COUNT, 0),
+        entry(0x0D, READ_REGISTER, RANGE, 0),
+        entry(0x0E, READ_REGISTER, RANGE_LENGTH, 0),
+        entry(0x0F, READ_REGISTER, RANGE_ATTRIBUTES, 0),
+        entry(0x10, WRITE_REGISTER_VALUE, OPERATION, 0),
+        entry(0x0B, WRITE_REGISTER_VALUE, OPERATION, 1),
+    ];
+
+    fn erst_table() -> [u64; 128] {
+        let mut header = [0u8; SDT_HEADER_SIZE + 12];
+        header[..4].copy_from_slice(b"ERST");
+        header[SDT_HEADER_SIZE..][..4].copy_from_slice(&12u32.to_le_bytes());
+        header[SDT_HEADER_SIZE + 8..][..4].copy_from_slice(&(ENTRIES.len() as u32).to_le_bytes());
+        let length = header.len() + core::mem::size_of_val(&ENTRIES);
+        header[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+        table(&header, &ENTRIES)
+    }
+    fn erst(table: &[u64; 128]) -> &ERST {
+        // SAFETY: the table is laid out like an ERST, and aligned.
+        unsafe { &*(table.as_ptr() as *const ERST) }
+    }
+
+    fn record(id: u64, fill: u8) -> [u8; 160] {
+        let mut record = [fill; 160];
+        record[RECORD_LENGTH_OFFSET as usize..][..4].copy_from_slice(&160u32.to_le_bytes());
+        record[RECORD_ID_OFFSET..][..8].copy_from_slice(&id.to_le_bytes());
+        record
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = erst_table();
+        let mut store =
+            ErrorRecordStore::new(erst(&table), FakePlatform::new(Store::new())).unwrap();
+        assert_eq!(store.error_log_address_range, MEMORY_BASE);
+        assert_eq!(store.error_log_address_range_length, RANGE_SIZE as u64);
+        assert_eq!(store.record_ids().next(), None);
+
+        store.write(&record(7, 0xAA)).unwrap();
+        store.write(&record(9, 0xBB)).unwrap();
+        assert_eq!(store.record_count(), Ok(2));
+        let mut ids = [0; 4];
+        let mut found = 0;
+        for id in store.record_ids() {
+            ids[found] = id.unwrap();
+            found += 1;
+        }
+        assert_eq!(ids[..found], [7, 9]);
+
+        let mut buffer = [0; RANGE_SIZE];
+        assert_eq!(store.read(9, &mut buffer), Ok(160));
+        assert_eq!(buffer[..160], record(9, 0xBB));
+        assert_eq!(
+            store.read(7, &mut buffer[..100]),
+            Err(ERSTError::BufferTooSmall(160))
+        );
+
+        store.clear(7).unwrap();
+        assert_eq!(store.record_count(), Ok(1));
+        assert_eq!(
+            store.read(7, &mut buffer),
+            Err(ERSTError::Status(
+                SerializationCommandStatus::RecordNotFound
+            ))
+        );
+        // The failed read still ended its operation.
+        assert_eq!(store.into_inner().registers[OPERATION as usize], 0);
+    }
+
+    #[test]
+    fn full_store() {
+        let table = erst_table();
+        let mut store =
+            ErrorRecordStore::new(erst(&table), FakePlatform::new(Store::new())).unwrap();
+        store.write(&record(1, 0)).unwrap();
+        store.write(&record(2, 0)).unwrap();
+        assert_eq!(
+            store.write(&record(3, 0)),
+            Err(ERSTError::Status(
+                SerializationCommandStatus::NotEnoughSpace
+            ))
+        );
+        assert_eq!(
+            store.write(&[0; RANGE_SIZE + 1]),
+            Err(ERSTError::RecordTooLarge(RANGE_SIZE + 1))
+        );
+    }
+
+    #[test]
+    fn busy_timeout() {
+        let table = erst_table();
+        let mut store =
+            ErrorRecordStore::new(erst(&table), FakePlatform::new(Store::new())).unwrap();
+        store.busy_poll_limit = 2;
+        assert_eq!(store.write(&record(1, 0)), Err(ERSTError::Timeout));
+    }
+}
@@ -0,0 +1,614 @@
+use crate::{
+    AMLEvaluator, GenericAddressAccess, GenericAddressStructure,
+    ec::{EmbeddedController, EmbeddedControllerError, query_method_name},
+    fadt::FixedACPIDescriptionTable,
+};
+
+/// Bus Check. Re-enumerate the device and its children.
+pub const NOTIFY_BUS_CHECK: u8 = 0x00;
+/// Device Check. Re-check the device (it was inserted or removed).
+pub const NOTIFY_DEVICE_CHECK: u8 = 0x01;
+/// Device Wake. The device signaled a wake event.
+pub const NOTIFY_DEVICE_WAKE: u8 = 0x02;
+/// Eject Request. The user asked for the device to be ejected.
+pub const NOTIFY_EJECT_REQUEST: u8 = 0x03;
+/// Device Check Light. Re-check the device, without re-enumerating its children.
+pub const NOTIFY_DEVICE_CHECK_LIGHT: u8 = 0x04;
+/// Frequency Mismatch. The device can't run at the bus frequency.
+pub const NOTIFY_FREQUENCY_MISMATCH: u8 = 0x05;
+/// Bus Mode Mismatch. The device can't run in the bus mode.
+pub const NOTIFY_BUS_MODE_MISMATCH: u8 = 0x06;
+/// Power Fault. The device had a power fault.
+pub const NOTIFY_POWER_FAULT: u8 = 0x07;
+/// Capabilities Check. Re-evaluate the _OSC or _PDC of the processor or device.
+pub const NOTIFY_CAPABILITIES_CHECK: u8 = 0x08;
+/// System Locality Information Update. Re-evaluate _SLI.
+pub const NOTIFY_SYSTEM_LOCALITY_UPDATE: u8 = 0x0B;
+/// Shutdown Request. The platform asks OSPM to shut down.
+pub const NOTIFY_SHUTDOWN_REQUEST: u8 = 0x0C;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// How a GPE is triggered, which decides the name of its method (_Lxx or _Exx) and when its status bit is cleared.
+pub enum GPETrigger {
+    /// The status bit is cleared before the _Exx method runs.
+    Edge,
+    /// The status bit is cleared after the _Lxx method runs, once the source has been dealt with.
+    Level,
+}
+
+/// Builds the name of the method for a GPE, e.g. GPE 0x1A level-triggered gives "_L1A".
+pub const fn gpe_method_name(gpe: u8, trigger: GPETrigger) -> [u8; 4] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    [
+        b'_',
+        match trigger {
+            GPETrigger::Edge => b'E',
+            GPETrigger::Level => b'L',
+        },
+        HEX[(gpe >> 4) as usize],
+        HEX[(gpe & 0xF) as usize],
+    ]
+}
+/// Builds the absolute path of the method for a GPE, e.g. "\\_GPE._L1A".
+pub const fn gpe_method_path(gpe: u8, trigger: GPETrigger) -> [u8; 10] {
+    let name = gpe_method_name(gpe, trigger);
+    [
+        b'\\', b'_', b'G', b'P', b'E', b'.', name[0], name[1], name[2], name[3],
+    ]
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the GPE and event handling can run into.
+pub enum EventError<E> {
+    /// The GPE number isn't in either GPE block.
+    NoSuchGPE(u8),
+    /// The method (_Lxx, _Exx or _Qxx) failed.
+    Method(E),
+    /// Talking to the Embedded Controller failed.
+    EmbeddedController(EmbeddedControllerError),
+    /// The namespace path of the EC is too long to append a _Qxx name to.
+    PathTooLong,
+}
+impl<E> From<EmbeddedControllerError> for EventError<E> {
+    fn from(value: EmbeddedControllerError) -> Self {
+        Self::EmbeddedController(value)
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## General-Purpose Event Register Block
+///
+/// The first half of the block is the status registers, the second half the enable registers, each a byte wide.
+/// Bit `n` of status register `i` is GPE `base + i * 8 + n`.
+pub struct GPEBlock {
+    /// Where the block starts.  Only System I/O and System Memory are valid.
+    pub address: GenericAddressStructure,
+    /// Length of the whole block (status and enable registers), in bytes.
+    pub length: u8,
+    /// The GPE number of bit 0 of the first status register.
+    pub base: u8,
+}
+impl GPEBlock {
+    /// The GPE0 block of the FADT (`x_gpe0_blk` if it's set, `gpe0_blk` otherwise), or `None` if there isn't one.
+    pub fn gpe0(fadt: &FixedACPIDescriptionTable) -> Option<Self> {
+        let x_gpe0_blk = if fadt.header.length as usize
+            >= core::mem::offset_of!(FixedACPIDescriptionTable, x_gpe0_blk)
+                + core::mem::size_of::<GenericAddressStructure>()
+        {
+            Some(fadt.x_gpe0_blk)
+        } else {
+            None
+        };
+        Self::from_fadt(x_gpe0_blk, fadt.gpe0_blk, fadt.gpe0_blk_len, 0)
+    }
+    /// The GPE1 block of the FADT (`x_gpe1_blk` if it's set, `gpe1_blk` otherwise), or `None` if there isn't one.
+    pub fn gpe1(fadt: &FixedACPIDescriptionTable) -> Option<Self> {
+        let x_gpe1_blk = if fadt.header.length as usize
+            >= core::mem::offset_of!(FixedACPIDescriptionTable, x_gpe1_blk)
+                + core::mem::size_of::<GenericAddressStructure>()
+        {
+            Some(fadt.x_gpe1_blk)
+        } else {
+            None
+        };
+        Self::from_fadt(x_gpe1_blk, fadt.gpe1_blk, fadt.gpe1_blk_len, fadt.gpe1_base)
+    }
+    fn from_fadt(
+        x_blk: Option<GenericAddressStructure>,
+        blk: u32,
+        length: u8,
+        base: u8,
+    ) -> Option<Self> {
+        let address = match x_blk {
+            Some(x_blk) if { x_blk.address } != 0 => x_blk,
+            _ if blk != 0 => GenericAddressStructure {
+                address_space_id: 0x01,
+                reg_bit_width: 0,
+                reg_bit_offset: 0,
+                access_size: 0,
+                address: blk as u64,
+            },
+            _ => return None,
+        };
+        if length < 2 {
+            return None;
+        }
+        Some(Self {
+            address,
+            length,
+            base,
+        })
+    }
+    /// The number of status (or enable) registers.
+    pub const fn registers(&self) -> u8 {
+        self.length / 2
+    }
+    /// The number of GPEs in the block.
+    pub const fn count(&self) -> u16 {
+        self.registers() as u16 * 8
+    }
+    /// Returns true if the GPE `gpe` is in this block.
+    pub const fn contains(&self, gpe: u8) -> bool {
+        gpe >= self.base && ((gpe - self.base) as u16) < self.count()
+    }
+    fn register(&self, offset: u8) -> GenericAddressStructure {
+        GenericAddressStructure {
+            address_space_id: self.address.address_space_id,
+            reg_bit_width: 8,
+            reg_bit_offset: 0,
+            access_size: 1,
+            address: self.address.address + offset as u64,
+        }
+    }
+    /// The status register `index` (GPEs `base + index * 8` to `base + index * 8 + 7`).
+    pub fn status_register(&self, index: u8) -> GenericAddressStructure {
+        self.register(index)
+    }
+    /// The enable register `index` (GPEs `base + index * 8` to `base + index * 8 + 7`).
+    pub fn enable_register(&self, index: u8) -> GenericAddressStructure {
+        self.register(self.registers() + index)
+    }
+}
+
+/// ## General-Purpose Event Controller
+///
+/// Handles the status and enable bits of the FADT GPE blocks, and runs the `\_GPE._Lxx`/`_Exx` method of a GPE through an `AMLEvaluator`.
+///
+/// The trigger type of a GPE comes from the name of its method (_Lxx or _Exx) in the namespace, so whoever loads the namespace tells this controller with `set_trigger`.
+/// Everything defaults to edge-triggered.
+pub struct GPEController<A: GenericAddressAccess> {
+    pub gpe0: Option<GPEBlock>,
+    pub gpe1: Option<GPEBlock>,
+    level: [u64; 4],
+    access: A,
+}
+impl<A: GenericAddressAccess> GPEController<A> {
+    pub const fn new(gpe0: Option<GPEBlock>, gpe1: Option<GPEBlock>, access: A) -> Self {
+        Self {
+            gpe0,
+            gpe1,
+            level: [0; 4],
+            access,
+        }
+    }
+    /// Sets up the controller with the GPE blocks of the FADT.
+    pub fn from_fadt(fadt: &FixedACPIDescriptionTable, access: A) -> Self {
+        Self::new(GPEBlock::gpe0(fadt), GPEBlock::gpe1(fadt), access)
+    }
+    /// Gives back the register access this controller was built with.
+    pub fn into_inner(self) -> A {
+        self.access
+    }
+
+    /// Finds the block of `gpe`, along with its register index and bit mask.
+    fn locate<E>(&self, gpe: u8) -> Result<(GPEBlock, u8, u8), EventError<E>> {
+        let block = [self.gpe0, self.gpe1]
+            .iter()
+            .flatten()
+            .find(|block| block.contains(gpe))
+            .copied()
+            .ok_or(EventError::NoSuchGPE(gpe))?;
+        let bit = gpe - block.base;
+        Ok((block, bit / 8, 1 << (bit % 8)))
+    }
+    /// Returns true if the status bit of `gpe` is set.
+    pub fn status<E>(&mut self, gpe: u8) -> Result<bool, EventError<E>> {
+        let (block, index, mask) = self.locate(gpe)?;
+        Ok(self.access.read(&block.status_register(index)) as u8 & mask != 0)
+    }
+    /// Clears the status bit of `gpe`.  Status bits are write-1-to-clear, so no other bits are touched.
+    pub fn clear_status<E>(&mut self, gpe: u8) -> Result<(), EventError<E>> {
+        let (block, index, mask) = self.locate(gpe)?;
+        self.access
+            .write(&block.status_register(index), mask as u64);
+        Ok(())
+    }
+    /// Returns true if the enable bit of `gpe` is set.
+    pub fn is_enabled<E>(&mut self, gpe: u8) -> Result<bool, EventError<E>> {
+        let (block, index, mask) = self.locate(gpe)?;
+        Ok(self.access.read(&block.enable_register(index)) as u8 & mask != 0)
+    }
+    fn set_enable<E>(&mut self, gpe: u8, enable: bool) -> Result<(), EventError<E>> {
+        let (block, index, mask) = self.locate(gpe)?;
+        let register = block.enable_register(index);
+        let value = self.access.read(&register) as u8;
+        let value = if enable { value | mask } else { value & !mask };
+        self.access.write(&register, value as u64);
+        Ok(())
+    }
+    /// Sets the enable bit of `gpe`, so it raises an SCI.
+    pub fn enable<E>(&mut self, gpe: u8) -> Result<(), EventError<E>> {
+        self.set_enable(gpe, true)
+    }
+    /// Clears the enable bit of `gpe`.
+    pub fn disable<E>(&mut self, gpe: u8) -> Result<(), EventError<E>> {
+        self.set_enable(gpe, false)
+    }
+    /// Clears every enable and status bit of both blocks, which is what OSPM does before setting up its GPEs.
+    pub fn disable_all(&mut self) {
+        for block in [self.gpe0, self.gpe1].iter().flatten() {
+            for index in 0..block.registers() {
+                self.access.write(&block.enable_register(index), 0);
+                self.access.write(&block.status_register(index), 0xFF);
+            }
+        }
+    }
+
+    /// Records the trigger type of `gpe` (from its _Lxx or _Exx method).
+    pub fn set_trigger(&mut self, gpe: u8, trigger: GPETrigger) {
+        let (word, bit) = ((gpe / 64) as usize, gpe % 64);
+        match trigger {
+            GPETrigger::Edge => self.level[word] &= !(1 << bit),
+            GPETrigger::Level => self.level[word] |= 1 << bit,
+        }
+    }
+    pub const fn trigger(&self, gpe: u8) -> GPETrigger {
+        if self.level[(gpe / 64) as usize] & 1 << (gpe % 64) != 0 {
+            GPETrigger::Level
+        } else {
+            GPETrigger::Edge
+        }
+    }
+
+    /// Runs the method of `gpe`, clearing its status bit before (edge) or after (level) it, and re-enables it.
+    ///
+    /// The GPE is disabled while its method runs.  If there's no method for it, it stays disabled and this returns `Ok(false)`.
+    pub fn dispatch<E: AMLEvaluator>(
+        &mut self,
+        gpe: u8,
+        evaluator: &mut E,
+    ) -> Result<bool, EventError<E::Error>> {
+        let trigger = self.trigger(gpe);
+        self.disable(gpe)?;
+        if trigger == GPETrigger::Edge {
+            self.clear_status(gpe)?;
+        }
+        let result = evaluator.evaluate(&gpe_method_path(gpe, trigger), &[]);
+        if trigger == GPETrigger::Level {
+            self.clear_status(gpe)?;
+        }
+        match result {
+            Ok(None) => Ok(false),
+            Ok(Some(_)) => {
+                self.enable(gpe)?;
+                Ok(true)
+            }
+            Err(e) => {
+                self.enable(gpe)?;
+                Err(EventError::Method(e))
+            }
+        }
+    }
+    /// Runs the method of every GPE that is both enabled and has its status bit set, returning how many ran.
+    ///
+    /// This is what the SCI handler calls.  Each GPE gets one go, so a level-triggered one the method didn't deal with waits for the next SCI.
+    pub fn dispatch_pending<E: AMLEvaluator>(
+        &mut self,
+        evaluator: &mut E,
+    ) -> Result<usize, EventError<E::Error>> {
+        let mut count = 0;
+        for block in [self.gpe0, self.gpe1].iter().flatten() {
+            for index in 0..block.registers() {
+                let pending = self.access.read(&block.status_register(index)) as u8
+                    & self.access.read(&block.enable_register(index)) as u8;
+                for bit in 0..8 {
+                    // GPEs past 255 have no number (nothing can enable them but the firmware, and `disable_all` turns those off).
+                    let gpe = block.base as u16 + index as u16 * 8 + bit;
+                    if gpe > u8::MAX as u16 {
+                        break;
+                    }
+                    let gpe = gpe as u8;
+                    if pending & 1 << bit != 0 && self.dispatch(gpe, evaluator)? {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Handles one SCI event of the Embedded Controller: sends QR_EC and runs the matching _Qxx method under `ec_path` (the EC's namespace path, like the ECDT's `ec_id`).
+///
+/// Returns the query value, or `None` if no event was pending.
+pub fn dispatch_ec_query<A: GenericAddressAccess, E: AMLEvaluator>(
+    ec: &mut EmbeddedController<A>,
+    ec_path: &[u8],
+    evaluator: &mut E,
+) -> Result<Option<u8>, EventError<E::Error>> {
+    if !ec.event_pending() {
+        return Ok(None);
+    }
+    let Some(query) = ec.query()? else {
+        return Ok(None);
+    };
+    let mut path = [0u8; 256];
+    let length = ec_path.len() + 5;
+    if length > path.len() {
+        return Err(EventError::PathTooLong);
+    }
+    path[..ec_path.len()].copy_from_slice(ec_path);
+    path[ec_path.len()] = b'.';
+    path[ec_path.len() + 1..length].copy_from_slice(&query_method_name(query));
+    evaluator
+        .evaluate(&path[..length], &[])
+        .map_err(EventError::Method)?;
+    Ok(Some(query))
+}
+
+/// A Rust handler for the Notify operations on one device: gets the device and the notification value.
+pub type NotifyHandler<D> = fn(device: D, value: u8);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the Notify queue can run into.
+pub enum NotifyError {
+    /// There are already `N` notifications waiting.
+    QueueFull,
+    /// Every one of the `N` handler slots is taken.
+    NoHandlerSlot,
+}
+
+/// ## Notify Queue
+///
+/// The interpreter calls `notify` when AML runs `Notify(device, value)`, and the OS calls `dispatch` once the method is done,
+/// which hands each notification to the Rust handler registered for its device.
+///
+/// `D` is however the interpreter names a device (a node handle, a path...).  `N` is both the number of handlers and of waiting notifications.
+pub struct NotifyQueue<D: Copy + PartialEq, const N: usize> {
+    handlers: [Option<(D, NotifyHandler<D>)>; N],
+    queue: [Option<(D, u8)>; N],
+    head: usize,
+    len: usize,
+}
+impl<D: Copy + PartialEq, const N: usize> Default for NotifyQueue<D, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<D: Copy + PartialEq, const N: usize> NotifyQueue<D, N> {
+    pub const fn new() -> Self {
+        Self {
+            handlers: [None; N],
+            queue: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+    /// Registers `handler` for the notifications on `device`, replacing the one it had.
+    pub fn register(&mut self, device: D, handler: NotifyHandler<D>) -> Result<(), NotifyError> {
+        let slot = match self
+            .handlers
+            .iter()
+            .position(|slot| slot.is_some_and(|(d, _)| d == device))
+        {
+            Some(slot) => slot,
+            None => self
+                .handlers
+                .iter()
+                .position(Option::is_none)
+                .ok_or(NotifyError::NoHandlerSlot)?,
+        };
+        self.handlers[slot] = Some((device, handler));
+        Ok(())
+    }
+    /// Removes the handler of `device`, returning true if it had one.
+    pub fn unregister(&mut self, device: D) -> bool {
+        match self
+            .handlers
+            .iter_mut()
+            .find(|slot| slot.is_some_and(|(d, _)| d == device))
+        {
+            Some(slot) => {
+                *slot = None;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Queues a `Notify(device, value)`.
+    pub fn notify(&mut self, device: D, value: u8) -> Result<(), NotifyError> {
+        if self.len == N {
+            return Err(NotifyError::QueueFull);
+        }
+        self.queue[(self.head + self.len) % N] = Some((device, value));
+        self.len += 1;
+        Ok(())
+    }
+    /// Takes the oldest waiting notification off the queue.
+    pub fn pop(&mut self) -> Option<(D, u8)> {
+        if self.len == 0 {
+            return None;
+        }
+        let entry = self.queue[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        entry
+    }
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Hands every waiting notification to the handler of its device, oldest first, returning how many had one.
+    ///
+    /// Notifications on a device without a handler are dropped.
+    pub fn dispatch(&mut self) -> usize {
+        let mut delivered = 0;
+        while let Some((device, value)) = self.pop() {
+            let handler = self
+                .handlers
+                .iter()
+                .flatten()
+                .find(|(d, _)| *d == device)
+                .map(|&(_, handler)| handler);
+            if let Some(handler) = handler {
+                handler(device, value);
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A GPE0 block of N bytes at I/O port 0: the status registers, then the enable registers.
+    struct Registers<const N: usize>([u8; N]);
+    impl<const N: usize> GenericAddressAccess for Registers<N> {
+        fn read(&mut self, gas: &GenericAddressStructure) -> u64 {
+            self.0[gas.address as usize] as u64
+        }
+        fn write(&mut self, gas: &GenericAddressStructure, value: u64) {
+            let address = gas.address as usize;
+            if address < N / 2 {
+                self.0[address] &= !(value as u8);
+            } else {
+                self.0[address] = value as u8;
+            }
+        }
+    }
+
+    /// Answers for every method but `missing`, remembering the last path it was asked for.
+    struct Evaluator {
+        missing: Option<[u8; 10]>,
+        last: [u8; 10],
+    }
+    impl AMLEvaluator for Evaluator {
+        type Error = ();
+        fn evaluate(&mut self, path: &[u8], _: &[u64]) -> Result<Option<u64>, ()> {
+            self.last.copy_from_slice(path);
+            Ok(if self.missing.is_some_and(|m| m == path) {
+                None
+            } else {
+                Some(0)
+            })
+        }
+    }
+
+    fn controller<const N: usize>(registers: [u8; N], base: u8) -> GPEController<Registers<N>> {
+        let block = GPEBlock {
+            address: GenericAddressStructure {
+                address_space_id: 0x01,
+                reg_bit_width: 0,
+                reg_bit_offset: 0,
+                access_size: 0,
+                address: 0,
+            },
+            length: N as u8,
+            base,
+        };
+        GPEController::new(Some(block), None, Registers(registers))
+    }
+
+    #[test]
+    fn method_names() {
+        assert_eq!(&gpe_method_name(0x1A, GPETrigger::Level), b"_L1A");
+        assert_eq!(&gpe_method_path(0x03, GPETrigger::Edge), b"\\_GPE._E03");
+    }
+
+    #[test]
+    fn dispatch_pending_runs_and_rearms() {
+        // GPE 0x03 (edge) and GPE 0x09 (level) are pending and enabled; GPE 0x01 is pending but disabled.
+        let mut gpe = controller([0b1010, 0b10, 0b1000, 0b10], 0);
+        gpe.set_trigger(0x09, GPETrigger::Level);
+        let mut evaluator = Evaluator {
+            missing: None,
+            last: [0; 10],
+        };
+        assert_eq!(gpe.dispatch_pending(&mut evaluator), Ok(2));
+        assert_eq!(&evaluator.last, b"\\_GPE._L09");
+        assert_eq!(gpe.status::<()>(0x03), Ok(false));
+        assert_eq!(gpe.status::<()>(0x09), Ok(false));
+        assert_eq!(gpe.status::<()>(0x01), Ok(true));
+        assert_eq!(gpe.is_enabled::<()>(0x03), Ok(true));
+        assert_eq!(gpe.is_enabled::<()>(0x09), Ok(true));
+        assert_eq!(gpe.dispatch_pending(&mut evaluator), Ok(0));
+    }
+
+    #[test]
+    fn gpe_without_method_stays_disabled() {
+        let mut gpe = controller([0b1, 0, 0b1, 0], 0);
+        let mut evaluator = Evaluator {
+            missing: Some(gpe_method_path(0x00, GPETrigger::Edge)),
+            last: [0; 10],
+        };
+        assert_eq!(gpe.dispatch(0x00, &mut evaluator), Ok(false));
+        assert_eq!(gpe.is_enabled::<()>(0x00), Ok(false));
+        assert_eq!(
+            gpe.dispatch(0x10, &mut evaluator),
+            Err(EventError::NoSuchGPE(0x10))
+        );
+    }
+
+    #[test]
+    fn gpes_past_255_are_skipped() {
+        // 40 status registers from GPE 0x10: register 29 ends at GPE 0xFF, and register 30 starts past it.
+        let mut registers = [0; 80];
+        registers[29] = 0x80;
+        registers[30] = 0x01;
+        registers[39] = 0xFF;
+        registers[40 + 29] = 0x80;
+        registers[40 + 30] = 0x01;
+        registers[40 + 39] = 0xFF;
+        let mut gpe = controller(registers, 0x10);
+        let mut evaluator = Evaluator {
+            missing: None,
+            last: [0; 10],
+        };
+        assert_eq!(gpe.dispatch_pending(&mut evaluator), Ok(1));
+        assert_eq!(&evaluator.last, b"\\_GPE._EFF");
+    }
+
+    #[test]
+    fn notify_queue() {
+        static mut SEEN: [(u32, u8); 2] = [(0, 0); 2];
+        fn handler(device: u32, value: u8) {
+            // SAFETY: the test is the only thing touching SEEN.
+            unsafe { SEEN[(device - 1) as usize] = (device, value) };
+        }
+        let mut queue = NotifyQueue::<u32, 2>::new();
+        queue.register(1, handler).unwrap();
+        queue.register(2, handler).unwrap();
+        assert_eq!(queue.register(3, handler), Err(NotifyError::NoHandlerSlot));
+        queue.notify(2, NOTIFY_DEVICE_CHECK).unwrap();
+        queue.notify(3, NOTIFY_BUS_CHECK).unwrap();
+        assert_eq!(
+            queue.notify(1, NOTIFY_EJECT_REQUEST),
+            Err(NotifyError::QueueFull)
+        );
+        assert_eq!(queue.dispatch(), 1);
+        assert!(queue.is_empty());
+        assert!(queue.unregister(2));
+        queue.notify(2, NOTIFY_DEVICE_WAKE).unwrap();
+        queue.notify(1, NOTIFY_EJECT_REQUEST).unwrap();
+        assert_eq!(queue.dispatch(), 1);
+        // SAFETY: the handlers are done running.
+        assert_eq!(
+            unsafe { SEEN },
+            [(1, NOTIFY_EJECT_REQUEST), (2, NOTIFY_DEVICE_CHECK)]
+        );
+    }
+}
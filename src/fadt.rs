@@ -164,7 +164,7 @@ impl FADTFixedFeatureFlags {
     /// - 00b - Not reported by the platform. Software should reference the NFIT Platform Capabilities
     /// - 01b - Cpu caches and any other caches that are coherent with them, are not persistent. Software is responsible for flushing data from cpu caches to make stores persistent. Supersedes NFIT Platform Capabilities.
     /// - 10b - Cpu caches and any other caches that are coherent with them, are persistent. Supersedes NFIT Platform Capabilities.
-    /// When reporting this state, the platform shall provide enough stored energy for ALL of the following:
+    ///   When reporting this state, the platform shall provide enough stored energy for ALL of the following:
     ///   - Time to flush cpu caches and any other caches that are coherent with them
     ///   - Time of all targets of those flushes to complete flushing stored data
     ///   - If supporting hot plug, the worst case CXL device topology that can be hot plugged
@@ -452,7 +452,7 @@ pub struct FixedACPIDescriptionTable {
     /// Minor Version of this FADT structure, in "Major.Minor" form, where 'Major' is the value in the Major Version Field (Byte offset 8 in this table).
     /// - Bits 0-3 - The low order bits correspond to the minor version of the specification version. For instance, ACPI 6.3 has a major version of 6, and a minor version of 3.
     /// - Bits 4-7 - The high order bits correspond to the version of the ACPI Specification errata this table complies with. A value of 0 means that it complies with the base version of the current specification.
-    /// A value of 1 means this is compatible with Errata A, 2 would be compatible with Errata B, and so on.
+    ///   A value of 1 means this is compatible with Errata A, 2 would be compatible with Errata B, and so on.
     pub minor_version: u8,
 
     /// Extended physical address of the FACS.
@@ -0,0 +1,364 @@
+use crate::{PhysicalMapper, SDT_HEADER_SIZE, SDTHeader};
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Performance Record Header
+///
+/// Starts every record of the FPDT, FBPT and S3PT.
+pub struct PerformanceRecordHeader {
+    /// What the record is; the meaning depends on the table it's in.
+    pub record_type: u16,
+    /// Length, in bytes, of the whole record.
+    pub length: u8,
+    pub revision: u8,
+}
+
+// Iterates over the performance records in `length` bytes at `start`.
+struct PerformanceRecords<'a> {
+    next: *const u8,
+    remaining: usize,
+    _table: core::marker::PhantomData<&'a PerformanceRecordHeader>,
+}
+impl<'a> PerformanceRecords<'a> {
+    const fn new(start: *const u8, length: usize) -> Self {
+        Self {
+            next: start,
+            remaining: length,
+            _table: core::marker::PhantomData,
+        }
+    }
+}
+impl<'a> Iterator for PerformanceRecords<'a> {
+    type Item = &'a PerformanceRecordHeader;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const PerformanceRecordHeader) };
+        let length = header.length as usize;
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 4 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        Some(header)
+    }
+}
+
+// Maps a table that starts with a signature and a length, and checks the signature.
+fn map_table<'m, T>(
+    mapper: &'m impl PhysicalMapper,
+    address: u64,
+    signature: &[u8; 4],
+) -> Option<&'m T> {
+    let header = mapper.map(address, 8)?;
+    // SAFETY: the mapper hands out `length` readable bytes (PhysicalMapper's contract).
+    let (found, length) = unsafe {
+        (
+            *(header as *const [u8; 4]),
+            (header.add(4) as *const u32).read_unaligned() as usize,
+        )
+    };
+    if &found != signature || length < core::mem::size_of::<T>() {
+        return None;
+    }
+    let table = mapper.map(address, length)?;
+    // SAFETY: `length` covers a T, T is packed (so any address will do), and the mapping lasts for 'm (PhysicalMapper's contract).
+    Some(unsafe { &*(table as *const T) })
+}
+
+// b - a, if both timestamps were recorded.
+const fn duration(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return None;
+    }
+    b.checked_sub(a)
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Performance Pointer Record
+///
+/// Points at the Firmware Basic Boot Performance Table or the S3 Performance Table.
+pub struct PerformancePointerRecord {
+    /// - **Record Type** - 0x0000 (FBPT) or 0x0001 (S3PT)
+    /// - **Length** - 16
+    /// - **Revision** - 1
+    pub header: PerformanceRecordHeader,
+    reserved: u32,
+    /// Physical address of the table.
+    pub address: u64,
+}
+
+#[derive(Copy, Clone)]
+/// A record of the FPDT.
+pub enum PerformanceRecord<'a> {
+    BasicBootPerformanceTablePointer(&'a PerformancePointerRecord),
+    S3PerformanceTablePointer(&'a PerformancePointerRecord),
+    /// A reserved or vendor-specific record (0x1000 to 0x3FFF).
+    Unknown(&'a PerformanceRecordHeader),
+}
+impl<'a> PerformanceRecord<'a> {
+    fn from_header(header: &'a PerformanceRecordHeader) -> Self {
+        let pointer = header.length as usize >= core::mem::size_of::<PerformancePointerRecord>();
+        // SAFETY: the length was checked against the record before the cast.
+        match header.record_type {
+            0x0000 if pointer => Self::BasicBootPerformanceTablePointer(unsafe {
+                &*(header as *const _ as *const PerformancePointerRecord)
+            }),
+            0x0001 if pointer => Self::S3PerformanceTablePointer(unsafe {
+                &*(header as *const _ as *const PerformancePointerRecord)
+            }),
+            _ => Self::Unknown(header),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Firmware Performance Data Table (FPDT)
+///
+/// Points at tables of firmware boot and S3 suspend/resume timings, which the OS can report (or log) to track firmware performance.
+///
+/// All timestamps are in nanoseconds since the timer started at reset; 0 means the firmware didn't record it.
+pub struct FPDT {
+    /// - **Signature** - "FPDT"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    pub performance_records: [PerformanceRecordHeader; 0],
+}
+impl FPDT {
+    pub fn records(&self) -> impl Iterator<Item = PerformanceRecord<'_>> {
+        PerformanceRecords::new(
+            (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE),
+            (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE),
+        )
+        .map(PerformanceRecord::from_header)
+    }
+    /// Physical address of the Firmware Basic Boot Performance Table.
+    pub fn basic_boot_performance_table_address(&self) -> Option<u64> {
+        self.records().find_map(|record| match record {
+            PerformanceRecord::BasicBootPerformanceTablePointer(pointer) => Some(pointer.address),
+            _ => None,
+        })
+    }
+    /// Physical address of the S3 Performance Table.
+    pub fn s3_performance_table_address(&self) -> Option<u64> {
+        self.records().find_map(|record| match record {
+            PerformanceRecord::S3PerformanceTablePointer(pointer) => Some(pointer.address),
+            _ => None,
+        })
+    }
+    /// Maps the Firmware Basic Boot Performance Table, if there is one (and its signature checks out).
+    pub fn basic_boot_performance_table<'m>(
+        &self,
+        mapper: &'m impl PhysicalMapper,
+    ) -> Option<&'m FBPT> {
+        map_table(
+            mapper,
+            self.basic_boot_performance_table_address()?,
+            b"FBPT",
+        )
+    }
+    /// Maps the S3 Performance Table, if there is one (and its signature checks out).
+    ///
+    /// Firmware rewrites this table on every suspend and resume, so map it again (or at least reread it) after each.
+    pub fn s3_performance_table<'m>(&self, mapper: &'m impl PhysicalMapper) -> Option<&'m S3PT> {
+        map_table(mapper, self.s3_performance_table_address()?, b"S3PT")
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Firmware Basic Boot Performance Data Record
+///
+/// The timestamps of the main steps of the last boot, in nanoseconds.
+pub struct FirmwareBasicBootPerformanceRecord {
+    /// - **Record Type** - 0x0002
+    /// - **Length** - 48
+    /// - **Revision** - 2
+    pub header: PerformanceRecordHeader,
+    reserved: u32,
+    /// When the reset sequence ended (so also how long it took, since the timer starts at reset).
+    pub reset_end: u64,
+    /// When the firmware started loading the OS loader image.
+    pub os_loader_load_image_start: u64,
+    /// When the firmware started running the OS loader image.
+    pub os_loader_start_image_start: u64,
+    /// When the OS loader called ExitBootServices.
+    pub exit_boot_services_entry: u64,
+    /// When ExitBootServices returned to the OS loader.
+    pub exit_boot_services_exit: u64,
+}
+impl FirmwareBasicBootPerformanceRecord {
+    /// From the end of reset to when the OS loader started loading: the firmware's own initialization.
+    pub const fn firmware_duration(&self) -> Option<u64> {
+        duration(self.reset_end, self.os_loader_load_image_start)
+    }
+    /// Loading the OS loader image.
+    pub const fn os_loader_load_duration(&self) -> Option<u64> {
+        duration(
+            self.os_loader_load_image_start,
+            self.os_loader_start_image_start,
+        )
+    }
+    /// From when the OS loader started running to when it called ExitBootServices.
+    pub const fn os_loader_duration(&self) -> Option<u64> {
+        duration(
+            self.os_loader_start_image_start,
+            self.exit_boot_services_entry,
+        )
+    }
+    /// ExitBootServices itself.
+    pub const fn exit_boot_services_duration(&self) -> Option<u64> {
+        duration(self.exit_boot_services_entry, self.exit_boot_services_exit)
+    }
+    /// From reset to when ExitBootServices returned: everything firmware (and the OS loader) took.
+    pub const fn total_duration(&self) -> Option<u64> {
+        match self.exit_boot_services_exit {
+            0 => None,
+            exit => Some(exit),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+/// A record of the FBPT.
+pub enum FBPTRecord<'a> {
+    BasicBootPerformance(&'a FirmwareBasicBootPerformanceRecord),
+    /// A reserved or vendor-specific record.
+    Unknown(&'a PerformanceRecordHeader),
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Firmware Basic Boot Performance Table (FBPT)
+pub struct FBPT {
+    /// "FBPT"
+    pub signature: [u8; 4],
+    /// Length, in bytes, of the whole table.
+    pub length: u32,
+    pub performance_records: [PerformanceRecordHeader; 0],
+}
+impl FBPT {
+    pub fn records(&self) -> impl Iterator<Item = FBPTRecord<'_>> {
+        PerformanceRecords::new(
+            (self as *const _ as *const u8).wrapping_add(8),
+            (self.length as usize).saturating_sub(8),
+        )
+        .map(|header| match header.record_type {
+            0x0002
+                if header.length as usize
+                    >= core::mem::size_of::<FirmwareBasicBootPerformanceRecord>() =>
+            {
+                FBPTRecord::BasicBootPerformance(unsafe {
+                    &*(header as *const _ as *const FirmwareBasicBootPerformanceRecord)
+                })
+            }
+            _ => FBPTRecord::Unknown(header),
+        })
+    }
+    pub fn basic_boot_performance(&self) -> Option<&FirmwareBasicBootPerformanceRecord> {
+        self.records().find_map(|record| match record {
+            FBPTRecord::BasicBootPerformance(record) => Some(record),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Basic S3 Resume Performance Record
+pub struct S3ResumePerformanceRecord {
+    /// - **Record Type** - 0x0000
+    /// - **Length** - 24
+    /// - **Revision** - 1
+    pub header: PerformanceRecordHeader,
+    /// The number of resumes since the last full boot.
+    pub resume_count: u32,
+    /// How long the last resume took, in nanoseconds, from the firmware starting to resume until handing off to the OS waking vector.
+    pub full_resume: u64,
+    /// The average of `full_resume` over all `resume_count` resumes, in nanoseconds.
+    pub average_resume: u64,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Basic S3 Suspend Performance Record
+pub struct S3SuspendPerformanceRecord {
+    /// - **Record Type** - 0x0001
+    /// - **Length** - 20
+    /// - **Revision** - 1
+    pub header: PerformanceRecordHeader,
+    /// When OSPM wrote SLP_TYP/SLP_EN to start the last suspend.
+    pub suspend_start: u64,
+    /// When the firmware finished the last suspend, right before the platform went into S3.
+    pub suspend_end: u64,
+}
+impl S3SuspendPerformanceRecord {
+    /// How long the last suspend took, in nanoseconds.
+    pub const fn suspend_duration(&self) -> Option<u64> {
+        duration(self.suspend_start, self.suspend_end)
+    }
+}
+
+#[derive(Copy, Clone)]
+/// A record of the S3PT.
+pub enum S3PTRecord<'a> {
+    Resume(&'a S3ResumePerformanceRecord),
+    Suspend(&'a S3SuspendPerformanceRecord),
+    /// A reserved or vendor-specific record.
+    Unknown(&'a PerformanceRecordHeader),
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## S3 Performance Table (S3PT)
+pub struct S3PT {
+    /// "S3PT"
+    pub signature: [u8; 4],
+    /// Length, in bytes, of the whole table.
+    pub length: u32,
+    pub performance_records: [PerformanceRecordHeader; 0],
+}
+impl S3PT {
+    pub fn records(&self) -> impl Iterator<Item = S3PTRecord<'_>> {
+        PerformanceRecords::new(
+            (self as *const _ as *const u8).wrapping_add(8),
+            (self.length as usize).saturating_sub(8),
+        )
+        .map(|header| {
+            let length = header.length as usize;
+            match header.record_type {
+                0x0000 if length >= core::mem::size_of::<S3ResumePerformanceRecord>() => {
+                    S3PTRecord::Resume(unsafe {
+                        &*(header as *const _ as *const S3ResumePerformanceRecord)
+                    })
+                }
+                0x0001 if length >= core::mem::size_of::<S3SuspendPerformanceRecord>() => {
+                    S3PTRecord::Suspend(unsafe {
+                        &*(header as *const _ as *const S3SuspendPerformanceRecord)
+                    })
+                }
+                _ => S3PTRecord::Unknown(header),
+            }
+        })
+    }
+    /// The record of the last resume (absent until the first resume).
+    pub fn resume(&self) -> Option<&S3ResumePerformanceRecord> {
+        self.records().find_map(|record| match record {
+            S3PTRecord::Resume(record) => Some(record),
+            _ => None,
+        })
+    }
+    /// The record of the last suspend (absent until the first suspend).
+    pub fn suspend(&self) -> Option<&S3SuspendPerformanceRecord> {
+        self.records().find_map(|record| match record {
+            S3PTRecord::Suspend(record) => Some(record),
+            _ => None,
+        })
+    }
+}
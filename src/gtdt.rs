@@ -0,0 +1,271 @@
+use crate::{
+    SDTHeader,
+    madt::interrupt_source_override::{
+        InterruptSourceOverridePolarity, InterruptSourceOverrideTriggerMode,
+    },
+};
+
+// The GTDT flags only have one bit each for trigger mode and polarity, so these never hand back `Conform`.
+const fn trigger_mode(bit: bool) -> InterruptSourceOverrideTriggerMode {
+    if bit {
+        InterruptSourceOverrideTriggerMode::EdgeTriggered
+    } else {
+        InterruptSourceOverrideTriggerMode::LevelTriggered
+    }
+}
+const fn polarity(bit: bool) -> InterruptSourceOverridePolarity {
+    if bit {
+        InterruptSourceOverridePolarity::ActiveLow
+    } else {
+        InterruptSourceOverridePolarity::ActiveHigh
+    }
+}
+
+#[derive(Copy, Clone)]
+/// Flags of the per-processor timer interrupts and of the GT Block timers.
+pub struct GTDTTimerFlags(u32);
+impl GTDTTimerFlags {
+    pub const fn trigger_mode(&self) -> InterruptSourceOverrideTriggerMode {
+        trigger_mode(self.0 & 0b001 != 0)
+    }
+    pub const fn polarity(&self) -> InterruptSourceOverridePolarity {
+        polarity(self.0 & 0b010 != 0)
+    }
+    /// If set, the timer keeps running (and can wake the processor) in every power state.
+    ///
+    /// Only used by the per-processor timers; reserved in the GT Block timer flags.
+    pub const fn always_on_capability(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+pub struct GTBlockTimerCommonFlags(u32);
+impl GTBlockTimerCommonFlags {
+    /// If set, the timer is secure; otherwise it's non-secure.
+    pub const fn secure_timer(&self) -> bool {
+        self.0 & 0b01 != 0
+    }
+    /// If set, the timer keeps running in every power state.
+    pub const fn always_on_capability(&self) -> bool {
+        self.0 & 0b10 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## GT Block Timer Structure
+pub struct GTBlockTimer {
+    /// The frame number (0 to 7) of this timer in the GT Block.
+    pub gt_frame_number: u8,
+    reserved: [u8; 3],
+    /// Physical address of the CNTBaseX frame, or 0xFFFFFFFFFFFFFFFF if it isn't provided.
+    pub cnt_base_x: u64,
+    /// Physical address of the CNTEL0BaseX frame, or 0xFFFFFFFFFFFFFFFF if it isn't provided.
+    pub cnt_el0_base_x: u64,
+    /// GSIV of the physical timer of this frame.
+    pub gtx_physical_timer_gsiv: u32,
+    pub gtx_physical_timer_flags: GTDTTimerFlags,
+    /// GSIV of the virtual timer of this frame, or 0 if it isn't implemented.
+    pub gtx_virtual_timer_gsiv: u32,
+    pub gtx_virtual_timer_flags: GTDTTimerFlags,
+    pub gtx_common_flags: GTBlockTimerCommonFlags,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## GT Block Structure
+///
+/// A memory-mapped Generic Timer block (CNTCTLBase), with up to 8 timer frames.
+pub struct GTBlock {
+    /// 0 - GT Block
+    pub r#type: u8,
+    /// 20 + 40 * `gt_block_timer_count`
+    pub length: u16,
+    reserved: u8,
+    /// Physical address of the CNTCTLBase frame.
+    pub gt_block_physical_address: u64,
+    /// The number of timer frames in this block (up to 8).
+    pub gt_block_timer_count: u32,
+    /// Offset, from the start of this structure, of the GT Block timer structures.
+    pub gt_block_timer_offset: u32,
+}
+impl GTBlock {
+    /// The timer frames (the ones that fit in the structure's length).
+    pub const fn timers(&self) -> &[GTBlockTimer] {
+        let offset = self.gt_block_timer_offset as usize;
+        let fits =
+            (self.length as usize).saturating_sub(offset) / core::mem::size_of::<GTBlockTimer>();
+        let count = if (self.gt_block_timer_count as usize) < fits {
+            self.gt_block_timer_count as usize
+        } else {
+            fits
+        };
+        if count == 0 {
+            return &[];
+        }
+        // SAFETY: the timers lie within the structure's length, which bounds the count, and `PlatformTimerIter` checked that length against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(offset) as *const GTBlockTimer,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SBSAGenericWatchdogFlags(u32);
+impl SBSAGenericWatchdogFlags {
+    pub const fn trigger_mode(&self) -> InterruptSourceOverrideTriggerMode {
+        trigger_mode(self.0 & 0b001 != 0)
+    }
+    pub const fn polarity(&self) -> InterruptSourceOverridePolarity {
+        polarity(self.0 & 0b010 != 0)
+    }
+    /// If set, the watchdog is secure; otherwise it's non-secure.
+    pub const fn secure_timer(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Arm Generic Watchdog Structure
+///
+/// Describes an SBSA Generic Watchdog.
+pub struct SBSAGenericWatchdog {
+    /// 1 - Arm Generic Watchdog
+    pub r#type: u8,
+    /// 28
+    pub length: u16,
+    reserved: u8,
+    /// Physical address of the WatchdogRefresh frame.
+    pub refresh_frame_physical_address: u64,
+    /// Physical address of the WatchdogControl frame.
+    pub watchdog_control_frame_physical_address: u64,
+    /// GSIV of the watchdog signal (WS0).
+    pub watchdog_timer_gsiv: u32,
+    pub watchdog_timer_flags: SBSAGenericWatchdogFlags,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// The common beginning of every platform timer structure.
+pub struct PlatformTimerHeader {
+    /// - **0** - GT Block
+    /// - **1** - Arm Generic Watchdog
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u8,
+    pub length: u16,
+}
+
+#[derive(Copy, Clone)]
+pub enum PlatformTimer<'a> {
+    GTBlock(&'a GTBlock),
+    SBSAGenericWatchdog(&'a SBSAGenericWatchdog),
+    /// A platform timer type this library doesn't know about (or a reserved one).
+    Unknown(&'a PlatformTimerHeader),
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Generic Timer Description Table (GTDT)
+///
+/// Describes the Arm Generic Timer: the interrupts of the per-processor timers, the memory-mapped counter frames,
+/// and the platform timers (memory-mapped GT Blocks and SBSA Generic Watchdogs).
+///
+/// A GSIV of 0 means that timer isn't provided.
+pub struct GTDT {
+    /// - **Signature** - "GTDT"
+    /// - **Revision** - 3
+    pub header: SDTHeader,
+    /// Physical address of the CntControlBase frame, or 0xFFFFFFFFFFFFFFFF if it isn't provided.
+    pub cnt_control_base_physical_address: u64,
+    reserved: u32,
+    /// GSIV of the secure EL1 timer.
+    pub secure_el1_timer_gsiv: u32,
+    pub secure_el1_timer_flags: GTDTTimerFlags,
+    /// GSIV of the non-secure EL1 timer.
+    pub non_secure_el1_timer_gsiv: u32,
+    pub non_secure_el1_timer_flags: GTDTTimerFlags,
+    /// GSIV of the virtual EL1 timer.
+    pub virtual_el1_timer_gsiv: u32,
+    pub virtual_el1_timer_flags: GTDTTimerFlags,
+    /// GSIV of the EL2 timer.
+    pub el2_timer_gsiv: u32,
+    pub el2_timer_flags: GTDTTimerFlags,
+    /// Physical address of the CntReadBase frame, or 0xFFFFFFFFFFFFFFFF if it isn't provided.
+    pub cnt_read_base_physical_address: u64,
+    pub platform_timer_count: u32,
+    /// Offset, from the start of this table, of the platform timer structures.
+    pub platform_timer_offset: u32,
+    /// GSIV of the virtual EL2 timer (revision 3 and up).
+    ///
+    /// This field is only there if the table is at least 104 bytes long; use `virtual_el2_timer()`.
+    pub virtual_el2_timer_gsiv: u32,
+    pub virtual_el2_timer_flags: GTDTTimerFlags,
+}
+impl GTDT {
+    /// The virtual EL2 timer GSIV and flags, if the table has them and the timer is provided.
+    pub const fn virtual_el2_timer(&self) -> Option<(u32, GTDTTimerFlags)> {
+        if self.header.length < 104 || self.virtual_el2_timer_gsiv == 0 {
+            return None;
+        }
+        Some((self.virtual_el2_timer_gsiv, self.virtual_el2_timer_flags))
+    }
+    pub const fn platform_timers(&self) -> PlatformTimerIter<'_> {
+        let offset = self.platform_timer_offset as usize;
+        PlatformTimerIter {
+            next: (self as *const _ as *const u8).wrapping_add(offset),
+            remaining: self.platform_timer_count,
+            bytes_left: (self.header.length as usize).saturating_sub(offset),
+            _table: core::marker::PhantomData,
+        }
+    }
+}
+
+/// Iterator over the platform timer structures of a GTDT.
+pub struct PlatformTimerIter<'a> {
+    next: *const u8,
+    remaining: u32,
+    bytes_left: usize,
+    _table: core::marker::PhantomData<&'a GTDT>,
+}
+impl<'a> Iterator for PlatformTimerIter<'a> {
+    type Item = PlatformTimer<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let header_size = core::mem::size_of::<PlatformTimerHeader>();
+        if self.remaining == 0 || self.bytes_left < header_size {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const PlatformTimerHeader) };
+        let length = header.length as usize;
+        if length < header_size || length > self.bytes_left {
+            // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+            self.remaining = 0;
+            return None;
+        }
+        // SAFETY: the structure lies within the table (checked above), and is only cast to a type it's long enough for.
+        let timer = unsafe {
+            match header.r#type {
+                0 if length >= core::mem::size_of::<GTBlock>() => {
+                    PlatformTimer::GTBlock(&*(self.next as *const _))
+                }
+                1 if length >= core::mem::size_of::<SBSAGenericWatchdog>() => {
+                    PlatformTimer::SBSAGenericWatchdog(&*(self.next as *const _))
+                }
+                _ => PlatformTimer::Unknown(header),
+            }
+        };
+        self.next = self.next.wrapping_add(length);
+        self.bytes_left -= length;
+        self.remaining -= 1;
+        Some(timer)
+    }
+}
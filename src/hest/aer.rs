@@ -0,0 +1,74 @@
+use crate::hest::ErrorSourceFlags;
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express AER Common Fields
+///
+/// The fields every PCIe Advanced Error Reporting error source starts with.
+///
+/// The mask, severity and control fields are the values OSPM should program into the device's AER capability.
+pub struct PCIeAERCommon {
+    /// - **6** - PCI Express Root Port AER
+    /// - **7** - PCI Express Device AER
+    /// - **8** - PCI Express/PCI-X Bridge AER
+    pub r#type: u16,
+    pub source_id: u16,
+    reserved0: u16,
+    /// If `flags.global()` is set, these settings apply to every device of this type, and the bus/device/function fields are ignored.
+    pub flags: ErrorSourceFlags,
+    /// If 1, the error source is enabled.
+    pub enabled: u8,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    /// - **Bits [[7:0]]** - Bus
+    /// - **Bits [[15:8]]** - PCI Segment
+    pub bus: u32,
+    pub device: u16,
+    pub function: u16,
+    /// The value for the Device Control register.
+    pub device_control: u16,
+    reserved1: u16,
+    pub uncorrectable_error_mask: u32,
+    pub uncorrectable_error_severity: u32,
+    pub correctable_error_mask: u32,
+    pub advanced_error_capabilities_and_control: u32,
+}
+impl PCIeAERCommon {
+    pub const fn segment(&self) -> u8 {
+        (self.bus >> 8) as u8
+    }
+    pub const fn bus_number(&self) -> u8 {
+        self.bus as u8
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express Root Port AER Structure
+pub struct PCIeRootPortAER {
+    /// - **Type** - 6
+    pub common: PCIeAERCommon,
+    /// The value for the Root Error Command register.
+    pub root_error_command: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express Device AER Structure
+pub struct PCIeDeviceAER {
+    /// - **Type** - 7
+    pub common: PCIeAERCommon,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express/PCI-X Bridge AER Structure
+pub struct PCIeBridgeAER {
+    /// - **Type** - 8
+    pub common: PCIeAERCommon,
+    pub secondary_uncorrectable_error_mask: u32,
+    pub secondary_uncorrectable_error_severity: u32,
+    pub secondary_advanced_capabilities_and_control: u32,
+}
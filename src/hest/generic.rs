@@ -0,0 +1,68 @@
+use crate::{GenericAddressAccess, GenericAddressStructure, hest::HardwareErrorNotification};
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Generic Hardware Error Source Structure
+///
+/// An error source that reports its errors through a Generic Error Status Block, written by the platform.
+/// `error_status_address` is a register holding the physical address of that block.
+pub struct GenericHardwareErrorSource {
+    /// 9 - Generic Hardware Error Source
+    pub r#type: u16,
+    pub source_id: u16,
+    /// The source ID of the error source this one assists (see `ErrorSourceFlags::ghes_assist`), or 0xFFFF if it doesn't assist any.
+    pub related_source_id: u16,
+    /// Reserved.
+    pub flags: u8,
+    /// If 1, the error source is enabled.
+    pub enabled: u8,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    /// The maximum size, in bytes, of the raw error data of this source.
+    pub max_raw_data_length: u32,
+    /// The register holding the physical address of the Generic Error Status Block.
+    pub error_status_address: GenericAddressStructure,
+    pub notification_structure: HardwareErrorNotification,
+    /// The size, in bytes, of the Generic Error Status Block.
+    pub error_status_block_length: u32,
+}
+impl GenericHardwareErrorSource {
+    /// Reads the physical address of the Generic Error Status Block out of `error_status_address`.
+    pub fn error_status_block_address(&self, access: &mut impl GenericAddressAccess) -> u64 {
+        let register = self.error_status_address;
+        access.read(&register)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Generic Hardware Error Source Version 2 (GHESv2) Structure
+///
+/// A Generic Hardware Error Source with a Read Ack register: once OSPM is done with the error status block,
+/// it acknowledges it so the platform can reuse the block.
+pub struct GenericHardwareErrorSourceV2 {
+    /// - **Type** - 10
+    pub ghes: GenericHardwareErrorSource,
+    /// The register OSPM writes to after reading the error status block.
+    pub read_ack_register: GenericAddressStructure,
+    /// The bits of the Read Ack register to keep when acknowledging.
+    pub read_ack_preserve: u64,
+    /// The bits to set in the Read Ack register when acknowledging.
+    pub read_ack_write: u64,
+}
+impl GenericHardwareErrorSourceV2 {
+    /// Reads the physical address of the Generic Error Status Block out of `error_status_address`.
+    pub fn error_status_block_address(&self, access: &mut impl GenericAddressAccess) -> u64 {
+        let ghes = self.ghes;
+        ghes.error_status_block_address(access)
+    }
+    /// Tells the platform OSPM is done with the error status block:
+    /// `((Read Ack register) & read_ack_preserve) | read_ack_write` is written back to the Read Ack register.
+    pub fn acknowledge(&self, access: &mut impl GenericAddressAccess) {
+        let register = self.read_ack_register;
+        let value = (access.read(&register) & self.read_ack_preserve) | self.read_ack_write;
+        access.write(&register, value)
+    }
+}
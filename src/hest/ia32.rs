@@ -0,0 +1,157 @@
+use crate::hest::{ErrorSourceFlags, HardwareErrorNotification};
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IA-32 Architecture Machine Check Bank Structure
+///
+/// The MSRs of one machine check bank, and how OSPM should set them up.
+pub struct MachineCheckBank {
+    /// The bank number (the N in IA32_MCi_CTL).
+    pub bank_number: u8,
+    /// If 1, OSPM should clear the bank's status register when it initializes.
+    pub clear_status_on_initialization: u8,
+    /// The format of the data in the status register:
+    ///
+    /// - **0** - IA-32 MCA
+    /// - **1** - Intel 64 MCA
+    /// - **2** - AMD64 MCA
+    pub status_data_format: u8,
+    reserved: u8,
+    /// The address of the bank's control MSR, or 0 if it has none.
+    pub control_register_msr_address: u32,
+    /// The value OSPM should write to the control MSR.
+    pub control_init_data: u64,
+    /// The address of the bank's status MSR, or 0 if it has none.
+    pub status_register_msr_address: u32,
+    /// The address of the bank's address MSR, or 0 if it has none.
+    pub address_register_msr_address: u32,
+    /// The address of the bank's misc MSR, or 0 if it has none.
+    pub misc_register_msr_address: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IA-32 Architecture Machine Check Exception Structure
+pub struct IA32MachineCheckException {
+    /// 0 - IA-32 Architecture Machine Check Exception
+    pub r#type: u16,
+    pub source_id: u16,
+    reserved0: u16,
+    pub flags: ErrorSourceFlags,
+    /// If 1, the error source is enabled.
+    pub enabled: u8,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    /// The value OSPM should write to the IA32_MCG_CAP MSR.
+    pub global_capability_init_data: u64,
+    /// The value OSPM should write to the IA32_MCG_CTL MSR.
+    pub global_control_init_data: u64,
+    pub number_of_hardware_banks: u8,
+    reserved1: [u8; 7],
+    pub machine_check_bank_structure: [MachineCheckBank; 0],
+}
+impl IA32MachineCheckException {
+    pub const fn banks(&self) -> &[MachineCheckBank] {
+        // SAFETY: `ErrorSourceIter` only hands out sources whose banks (`length()`) lie within the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(40) as *const MachineCheckBank,
+                self.number_of_hardware_banks as usize,
+            )
+        }
+    }
+    /// The size of this structure, banks included.
+    pub const fn length(&self) -> usize {
+        40 + self.number_of_hardware_banks as usize * core::mem::size_of::<MachineCheckBank>()
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IA-32 Architecture Corrected Machine Check Structure
+pub struct IA32CorrectedMachineCheck {
+    /// 1 - IA-32 Architecture Corrected Machine Check
+    pub r#type: u16,
+    pub source_id: u16,
+    reserved0: u16,
+    pub flags: ErrorSourceFlags,
+    /// If 1, the error source is enabled.
+    pub enabled: u8,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    pub notification_structure: HardwareErrorNotification,
+    pub number_of_hardware_banks: u8,
+    reserved1: [u8; 3],
+    pub machine_check_bank_structure: [MachineCheckBank; 0],
+}
+impl IA32CorrectedMachineCheck {
+    pub const fn banks(&self) -> &[MachineCheckBank] {
+        // SAFETY: `ErrorSourceIter` only hands out sources whose banks (`length()`) lie within the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(48) as *const MachineCheckBank,
+                self.number_of_hardware_banks as usize,
+            )
+        }
+    }
+    /// The size of this structure, banks included.
+    pub const fn length(&self) -> usize {
+        48 + self.number_of_hardware_banks as usize * core::mem::size_of::<MachineCheckBank>()
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IA-32 Architecture NMI Error Structure
+pub struct IA32NonMaskableInterrupt {
+    /// 2 - IA-32 Architecture NMI Error
+    pub r#type: u16,
+    pub source_id: u16,
+    reserved: u32,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    /// The maximum size, in bytes, of the raw error data of this source.
+    pub max_raw_data_length: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IA-32 Architecture Deferred Machine Check Structure
+pub struct IA32DeferredMachineCheck {
+    /// 11 - IA-32 Architecture Deferred Machine Check
+    pub r#type: u16,
+    pub source_id: u16,
+    reserved0: u16,
+    pub flags: ErrorSourceFlags,
+    /// If 1, the error source is enabled.
+    pub enabled: u8,
+    /// The number of error records OSPM should pre-allocate for this source.
+    pub number_of_records_to_pre_allocate: u32,
+    /// The maximum number of sections in a record from this source.
+    pub max_sections_per_record: u32,
+    pub notification_structure: HardwareErrorNotification,
+    pub number_of_hardware_banks: u8,
+    reserved1: [u8; 3],
+    pub machine_check_bank_structure: [MachineCheckBank; 0],
+}
+impl IA32DeferredMachineCheck {
+    pub const fn banks(&self) -> &[MachineCheckBank] {
+        // SAFETY: `ErrorSourceIter` only hands out sources whose banks (`length()`) lie within the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(48) as *const MachineCheckBank,
+                self.number_of_hardware_banks as usize,
+            )
+        }
+    }
+    /// The size of this structure, banks included.
+    pub const fn length(&self) -> usize {
+        48 + self.number_of_hardware_banks as usize * core::mem::size_of::<MachineCheckBank>()
+    }
+}
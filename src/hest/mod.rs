@@ -0,0 +1,282 @@
+pub mod aer;
+pub mod generic;
+pub mod ia32;
+
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    hest::{
+        aer::{PCIeBridgeAER, PCIeDeviceAER, PCIeRootPortAER},
+        generic::{GenericHardwareErrorSource, GenericHardwareErrorSourceV2},
+        ia32::{
+            IA32CorrectedMachineCheck, IA32DeferredMachineCheck, IA32MachineCheckException,
+            IA32NonMaskableInterrupt,
+        },
+    },
+};
+
+#[derive(Copy, Clone)]
+pub struct ErrorSourceFlags(u8);
+impl ErrorSourceFlags {
+    /// If set, the platform handles the errors of this source first (firmware first), and OSPM must not touch the error source's hardware directly.
+    pub const fn firmware_first(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// For PCIe AER sources only: if set, the settings of the structure apply to every device of its type, and the bus/device/function fields are ignored.
+    pub const fn global(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// For IA-32 machine check sources only: if set, a GHES with a `related_source_id` pointing at this source assists with its errors.
+    pub const fn ghes_assist(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// How an error source tells OSPM about errors.
+pub enum HardwareErrorNotificationType {
+    Polled,
+    ExternalInterrupt,
+    LocalInterrupt,
+    SCI,
+    NMI,
+    CMCI,
+    MCE,
+    GPIOSignal,
+    ARMv8SEA,
+    ARMv8SEI,
+    /// External interrupt with a GSIV.
+    GSIV,
+    SoftwareDelegatedException,
+    Unknown(u8),
+}
+
+#[derive(Copy, Clone)]
+pub struct ConfigurationWriteEnable(u16);
+impl ConfigurationWriteEnable {
+    /// If set, OSPM may change the notification type.
+    pub const fn r#type(&self) -> bool {
+        self.0 & 0b0000001 != 0
+    }
+    /// If set, OSPM may change the poll interval.
+    pub const fn poll_interval(&self) -> bool {
+        self.0 & 0b0000010 != 0
+    }
+    /// If set, OSPM may change the switch to polling threshold value.
+    pub const fn switch_to_polling_threshold_value(&self) -> bool {
+        self.0 & 0b0000100 != 0
+    }
+    /// If set, OSPM may change the switch to polling threshold window.
+    pub const fn switch_to_polling_threshold_window(&self) -> bool {
+        self.0 & 0b0001000 != 0
+    }
+    /// If set, OSPM may change the error threshold value.
+    pub const fn error_threshold_value(&self) -> bool {
+        self.0 & 0b0010000 != 0
+    }
+    /// If set, OSPM may change the error threshold window.
+    pub const fn error_threshold_window(&self) -> bool {
+        self.0 & 0b0100000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Hardware Error Notification Structure
+///
+/// Describes how an error source notifies OSPM of errors, and when OSPM should switch between interrupts and polling.
+pub struct HardwareErrorNotification {
+    /// - **0** - Polled
+    /// - **1** - External Interrupt
+    /// - **2** - Local Interrupt
+    /// - **3** - SCI
+    /// - **4** - NMI
+    /// - **5** - CMCI
+    /// - **6** - MCE
+    /// - **7** - GPIO-Signal
+    /// - **8** - ARMv8 SEA
+    /// - **9** - ARMv8 SEI
+    /// - **10** - External Interrupt - GSIV
+    /// - **11** - Software Delegated Exception
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u8,
+    /// 28
+    pub length: u8,
+    /// Which of the fields OSPM is allowed to change.
+    pub configuration_write_enable: ConfigurationWriteEnable,
+    /// Polling interval in milliseconds, for polled notifications.
+    pub poll_interval: u32,
+    /// The interrupt vector (or GSIV, or SDEI event number) of the notification.
+    pub vector: u32,
+    /// The number of errors within `switch_to_polling_threshold_window` after which OSPM should switch to polling.
+    pub switch_to_polling_threshold_value: u32,
+    /// In milliseconds.
+    pub switch_to_polling_threshold_window: u32,
+    /// The number of errors within `error_threshold_window` after which OSPM should treat the source as failing.
+    pub error_threshold_value: u32,
+    /// In milliseconds.
+    pub error_threshold_window: u32,
+}
+impl HardwareErrorNotification {
+    pub const fn notification_type(&self) -> HardwareErrorNotificationType {
+        match self.r#type {
+            0 => HardwareErrorNotificationType::Polled,
+            1 => HardwareErrorNotificationType::ExternalInterrupt,
+            2 => HardwareErrorNotificationType::LocalInterrupt,
+            3 => HardwareErrorNotificationType::SCI,
+            4 => HardwareErrorNotificationType::NMI,
+            5 => HardwareErrorNotificationType::CMCI,
+            6 => HardwareErrorNotificationType::MCE,
+            7 => HardwareErrorNotificationType::GPIOSignal,
+            8 => HardwareErrorNotificationType::ARMv8SEA,
+            9 => HardwareErrorNotificationType::ARMv8SEI,
+            10 => HardwareErrorNotificationType::GSIV,
+            11 => HardwareErrorNotificationType::SoftwareDelegatedException,
+            other => HardwareErrorNotificationType::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum ErrorSource<'a> {
+    IA32MachineCheckException(&'a IA32MachineCheckException),
+    IA32CorrectedMachineCheck(&'a IA32CorrectedMachineCheck),
+    IA32NonMaskableInterrupt(&'a IA32NonMaskableInterrupt),
+    PCIeRootPortAER(&'a PCIeRootPortAER),
+    PCIeDeviceAER(&'a PCIeDeviceAER),
+    PCIeBridgeAER(&'a PCIeBridgeAER),
+    GenericHardwareErrorSource(&'a GenericHardwareErrorSource),
+    GenericHardwareErrorSourceV2(&'a GenericHardwareErrorSourceV2),
+    IA32DeferredMachineCheck(&'a IA32DeferredMachineCheck),
+}
+impl ErrorSource<'_> {
+    /// The ID of the error source, unique within the HEST.
+    pub const fn source_id(&self) -> u16 {
+        match *self {
+            Self::IA32MachineCheckException(source) => source.source_id,
+            Self::IA32CorrectedMachineCheck(source) => source.source_id,
+            Self::IA32NonMaskableInterrupt(source) => source.source_id,
+            Self::PCIeRootPortAER(source) => source.common.source_id,
+            Self::PCIeDeviceAER(source) => source.common.source_id,
+            Self::PCIeBridgeAER(source) => source.common.source_id,
+            Self::GenericHardwareErrorSource(source) => source.source_id,
+            Self::GenericHardwareErrorSourceV2(source) => source.ghes.source_id,
+            Self::IA32DeferredMachineCheck(source) => source.source_id,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Hardware Error Source Table (HEST)
+///
+/// Describes the platform's hardware error sources: what kind of errors they report, how they notify OSPM, and whether the platform handles them first (firmware first).
+pub struct HEST {
+    /// - **Signature** - "HEST"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    pub error_source_count: u32,
+    /// A list of error source structures.
+    pub error_source_structure: [u8; 0],
+}
+impl HEST {
+    pub const fn error_sources(&self) -> ErrorSourceIter<'_> {
+        ErrorSourceIter {
+            hest: self,
+            offset: SDT_HEADER_SIZE + 4,
+            remaining: self.error_source_count,
+        }
+    }
+    /// Finds the error source with `source_id`.
+    pub fn error_source(&self, source_id: u16) -> Option<ErrorSource<'_>> {
+        self.error_sources()
+            .find(|source| source.source_id() == source_id)
+    }
+}
+
+/// Iterator over the error sources of a HEST.
+///
+/// Error source structures have no length field, so the size of each one comes from its type (and bank count).
+/// Iteration stops at the first type this library doesn't know, since there's no telling where the next structure starts, and at the first one that doesn't fit in the table.
+pub struct ErrorSourceIter<'a> {
+    hest: &'a HEST,
+    offset: usize,
+    remaining: u32,
+}
+impl<'a> Iterator for ErrorSourceIter<'a> {
+    type Item = ErrorSource<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let left = (self.hest.header.length as usize).saturating_sub(self.offset);
+        if self.remaining == 0 || left < 2 {
+            return None;
+        }
+        let ptr = (self.hest as *const _ as *const u8).wrapping_add(self.offset);
+        // SAFETY: the type lies within the table, checked above.
+        let r#type = unsafe { core::ptr::read_unaligned(ptr as *const u16) };
+        let fits = |size: usize| size <= left;
+        // SAFETY: each type is only cast to if its fixed part lies within the table; the banks are checked below.
+        let (source, length) = unsafe {
+            match r#type {
+                0 if fits(core::mem::size_of::<IA32MachineCheckException>()) => {
+                    let source = &*(ptr as *const IA32MachineCheckException);
+                    (
+                        ErrorSource::IA32MachineCheckException(source),
+                        source.length(),
+                    )
+                }
+                1 if fits(core::mem::size_of::<IA32CorrectedMachineCheck>()) => {
+                    let source = &*(ptr as *const IA32CorrectedMachineCheck);
+                    (
+                        ErrorSource::IA32CorrectedMachineCheck(source),
+                        source.length(),
+                    )
+                }
+                2 if fits(core::mem::size_of::<IA32NonMaskableInterrupt>()) => (
+                    ErrorSource::IA32NonMaskableInterrupt(&*(ptr as *const _)),
+                    core::mem::size_of::<IA32NonMaskableInterrupt>(),
+                ),
+                6 if fits(core::mem::size_of::<PCIeRootPortAER>()) => (
+                    ErrorSource::PCIeRootPortAER(&*(ptr as *const _)),
+                    core::mem::size_of::<PCIeRootPortAER>(),
+                ),
+                7 if fits(core::mem::size_of::<PCIeDeviceAER>()) => (
+                    ErrorSource::PCIeDeviceAER(&*(ptr as *const _)),
+                    core::mem::size_of::<PCIeDeviceAER>(),
+                ),
+                8 if fits(core::mem::size_of::<PCIeBridgeAER>()) => (
+                    ErrorSource::PCIeBridgeAER(&*(ptr as *const _)),
+                    core::mem::size_of::<PCIeBridgeAER>(),
+                ),
+                9 if fits(core::mem::size_of::<GenericHardwareErrorSource>()) => (
+                    ErrorSource::GenericHardwareErrorSource(&*(ptr as *const _)),
+                    core::mem::size_of::<GenericHardwareErrorSource>(),
+                ),
+                10 if fits(core::mem::size_of::<GenericHardwareErrorSourceV2>()) => (
+                    ErrorSource::GenericHardwareErrorSourceV2(&*(ptr as *const _)),
+                    core::mem::size_of::<GenericHardwareErrorSourceV2>(),
+                ),
+                11 if fits(core::mem::size_of::<IA32DeferredMachineCheck>()) => {
+                    let source = &*(ptr as *const IA32DeferredMachineCheck);
+                    (
+                        ErrorSource::IA32DeferredMachineCheck(source),
+                        source.length(),
+                    )
+                }
+                _ => {
+                    self.remaining = 0;
+                    return None;
+                }
+            }
+        };
+        if !fits(length) {
+            // The banks run off the table.
+            self.remaining = 0;
+            return None;
+        }
+        self.offset += length;
+        self.remaining -= 1;
+        Some(source)
+    }
+}
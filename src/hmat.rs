@@ -0,0 +1,515 @@
+use crate::{SDT_HEADER_SIZE, SDTHeader};
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// The common beginning of every HMAT structure.
+pub struct HMATStructureHeader {
+    /// - **0** - Memory Proximity Domain Attributes Structure
+    /// - **1** - System Locality Latency and Bandwidth Information Structure
+    /// - **2** - Memory Side Cache Information Structure
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u16,
+    reserved: u16,
+    /// Length in bytes for the entire structure.
+    pub length: u32,
+}
+
+#[derive(Copy, Clone)]
+pub struct MemoryProximityDomainAttributesFlags(u16);
+impl MemoryProximityDomainAttributesFlags {
+    /// If set, the `initiator_proximity_domain` field is valid.
+    pub const fn initiator_proximity_domain_valid(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Proximity Domain Attributes Structure
+///
+/// Describes the attributes of a memory proximity domain, including the initiator proximity domain it's directly attached to (if any).
+pub struct MemoryProximityDomainAttributes {
+    /// 0 - Memory Proximity Domain Attributes Structure
+    pub r#type: u16,
+    reserved0: u16,
+    /// 40
+    pub length: u32,
+    pub flags: MemoryProximityDomainAttributesFlags,
+    reserved1: u16,
+    /// The proximity domain of the initiator (processor or generic initiator) the memory controller of this memory proximity domain is attached to.
+    ///
+    /// Only valid if `flags.initiator_proximity_domain_valid()` is set.
+    pub initiator_proximity_domain: u32,
+    /// The proximity domain of the memory this structure describes, as found in the SRAT.
+    pub memory_proximity_domain: u32,
+    reserved2: u32,
+    reserved3: u64,
+    reserved4: u64,
+}
+impl MemoryProximityDomainAttributes {
+    pub const fn initiator(&self) -> Option<u32> {
+        let flags = self.flags;
+        if flags.initiator_proximity_domain_valid() {
+            Some(self.initiator_proximity_domain)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Which level of the memory hierarchy a System Locality Latency and Bandwidth Information Structure describes.
+pub enum MemoryHierarchy {
+    /// The memory itself.
+    Memory,
+    FirstLevelMemorySideCache,
+    SecondLevelMemorySideCache,
+    ThirdLevelMemorySideCache,
+    Unknown(u8),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// What the entries of a System Locality Latency and Bandwidth Information Structure measure.
+pub enum HMATDataType {
+    AccessLatency,
+    ReadLatency,
+    WriteLatency,
+    AccessBandwidth,
+    ReadBandwidth,
+    WriteBandwidth,
+    Unknown(u8),
+}
+impl HMATDataType {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::AccessLatency,
+            1 => Self::ReadLatency,
+            2 => Self::WriteLatency,
+            3 => Self::AccessBandwidth,
+            4 => Self::ReadBandwidth,
+            5 => Self::WriteBandwidth,
+            other => Self::Unknown(other),
+        }
+    }
+    /// Entries are in picoseconds (times the entry base unit).
+    pub const fn is_latency(&self) -> bool {
+        matches!(
+            self,
+            Self::AccessLatency | Self::ReadLatency | Self::WriteLatency
+        )
+    }
+    /// Entries are in MB/s (times the entry base unit).
+    pub const fn is_bandwidth(&self) -> bool {
+        matches!(
+            self,
+            Self::AccessBandwidth | Self::ReadBandwidth | Self::WriteBandwidth
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SLLBIFlags(u8);
+impl SLLBIFlags {
+    pub const fn memory_hierarchy(&self) -> MemoryHierarchy {
+        match self.0 & 0xF {
+            0 => MemoryHierarchy::Memory,
+            1 => MemoryHierarchy::FirstLevelMemorySideCache,
+            2 => MemoryHierarchy::SecondLevelMemorySideCache,
+            3 => MemoryHierarchy::ThirdLevelMemorySideCache,
+            other => MemoryHierarchy::Unknown(other),
+        }
+    }
+    /// Access attributes (revision 2 of the HMAT and up):
+    ///
+    /// - **0** - The values are for all initiators
+    /// - **1** - The values are for transfers of `min_transfer_size`
+    /// - **2** - The values are for non-sequential transfers
+    ///
+    /// The rest of the values are reserved.
+    pub const fn access_attributes(&self) -> u8 {
+        (self.0 >> 4) & 0b11
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## System Locality Latency and Bandwidth Information Structure
+///
+/// Gives one kind of latency or bandwidth (`data_type`) between every listed initiator proximity domain and every listed target proximity domain.
+///
+/// The structure is followed by the initiator proximity domain list, the target proximity domain list, and then the entries,
+/// a matrix with one row per initiator and one column per target.
+/// Each entry multiplied by `entry_base_unit` is the latency in picoseconds or the bandwidth in MB/s.
+/// An entry of 0 means the value isn't provided, and 0xFFFF is reserved.
+pub struct SystemLocalityLatencyBandwidthInformation {
+    /// 1 - System Locality Latency and Bandwidth Information Structure
+    pub r#type: u16,
+    reserved0: u16,
+    pub length: u32,
+    pub flags: SLLBIFlags,
+    /// - **0** - Access Latency
+    /// - **1** - Read Latency
+    /// - **2** - Write Latency
+    /// - **3** - Access Bandwidth
+    /// - **4** - Read Bandwidth
+    /// - **5** - Write Bandwidth
+    ///
+    /// The rest of the values are reserved.
+    pub data_type: u8,
+    /// The minimum transfer size, in bytes, the values were measured with (`flags.access_attributes()` of 1).
+    pub min_transfer_size: u8,
+    reserved1: u8,
+    pub number_of_initiator_proximity_domains: u32,
+    pub number_of_target_proximity_domains: u32,
+    reserved2: u32,
+    /// The unit every entry is multiplied by.
+    pub entry_base_unit: u64,
+    pub initiator_proximity_domain_list: [u32; 0],
+}
+impl SystemLocalityLatencyBandwidthInformation {
+    pub const fn data_type(&self) -> HMATDataType {
+        HMATDataType::from_u8(self.data_type)
+    }
+    // Reads the `T` at `offset` (from the start of this structure), if it lies within the structure's length.
+    fn read<T: Copy>(&self, offset: usize) -> Option<T> {
+        if offset + core::mem::size_of::<T>() > self.length as usize {
+            return None;
+        }
+        // SAFETY: the value lies within the structure's length, checked above.
+        unsafe {
+            Some(core::ptr::read_unaligned(
+                (self as *const _ as *const u8).add(offset) as *const T,
+            ))
+        }
+    }
+    /// The `index`th initiator proximity domain.
+    ///
+    /// A structure that follows an odd-sized entry matrix is only 2-byte aligned, so the lists can't be handed out as slices; each entry is read on its own instead.
+    pub fn initiator_proximity_domain(&self, index: usize) -> Option<u32> {
+        if index >= self.number_of_initiator_proximity_domains as usize {
+            return None;
+        }
+        self.read(32 + index * 4)
+    }
+    /// The `index`th target proximity domain.
+    pub fn target_proximity_domain(&self, index: usize) -> Option<u32> {
+        if index >= self.number_of_target_proximity_domains as usize {
+            return None;
+        }
+        self.read(32 + (self.number_of_initiator_proximity_domains as usize + index) * 4)
+    }
+    /// The initiator proximity domains (the ones that fit in the structure's length).
+    pub fn initiator_proximity_domains(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.number_of_initiator_proximity_domains as usize)
+            .map_while(move |i| self.initiator_proximity_domain(i))
+    }
+    /// The target proximity domains (the ones that fit in the structure's length).
+    pub fn target_proximity_domains(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.number_of_target_proximity_domains as usize)
+            .map_while(move |i| self.target_proximity_domain(i))
+    }
+    /// The raw entry of initiator index `i` and target index `j` (indices into the lists, not proximity domains).
+    pub fn entry_at(&self, i: usize, j: usize) -> Option<u16> {
+        let initiators = self.number_of_initiator_proximity_domains as usize;
+        let targets = self.number_of_target_proximity_domains as usize;
+        if i >= initiators || j >= targets {
+            return None;
+        }
+        self.read(32 + 4 * (initiators + targets) + 2 * (i * targets + j))
+    }
+    /// The raw entry from proximity domain `initiator` to proximity domain `target`.
+    ///
+    /// Returns `None` if either domain isn't listed, or the entry isn't provided (0) or is reserved (0xFFFF).
+    pub fn entry(&self, initiator: u32, target: u32) -> Option<u16> {
+        let i = self
+            .initiator_proximity_domains()
+            .position(|d| d == initiator)?;
+        let j = self.target_proximity_domains().position(|d| d == target)?;
+        match self.entry_at(i, j)? {
+            0 | 0xFFFF => None,
+            entry => Some(entry),
+        }
+    }
+    /// The latency from `initiator` to `target` in picoseconds, if this is a latency structure.
+    pub fn latency_ps(&self, initiator: u32, target: u32) -> Option<u64> {
+        if !self.data_type().is_latency() {
+            return None;
+        }
+        Some(self.entry(initiator, target)? as u64 * self.entry_base_unit)
+    }
+    /// The latency from `initiator` to `target` in nanoseconds (rounded up), if this is a latency structure.
+    pub fn latency_ns(&self, initiator: u32, target: u32) -> Option<u64> {
+        Some(self.latency_ps(initiator, target)?.div_ceil(1000))
+    }
+    /// The bandwidth from `initiator` to `target` in MB/s, if this is a bandwidth structure.
+    pub fn bandwidth_mbps(&self, initiator: u32, target: u32) -> Option<u64> {
+        if !self.data_type().is_bandwidth() {
+            return None;
+        }
+        Some(self.entry(initiator, target)? as u64 * self.entry_base_unit)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheAssociativity {
+    None,
+    DirectMapped,
+    /// Complex Cache Indexing (implementation specific).
+    ComplexCacheIndexing,
+    Unknown(u8),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheWritePolicy {
+    None,
+    WriteBack,
+    WriteThrough,
+    Unknown(u8),
+}
+
+#[derive(Copy, Clone)]
+pub struct MemorySideCacheAttributes(u32);
+impl MemorySideCacheAttributes {
+    /// The total number of cache levels for this memory proximity domain.
+    pub const fn total_cache_levels(&self) -> u8 {
+        (self.0 & 0xF) as u8
+    }
+    /// The cache level described by this structure (1 is the level closest to the initiator).
+    pub const fn cache_level(&self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+    pub const fn cache_associativity(&self) -> CacheAssociativity {
+        match ((self.0 >> 8) & 0xF) as u8 {
+            0 => CacheAssociativity::None,
+            1 => CacheAssociativity::DirectMapped,
+            2 => CacheAssociativity::ComplexCacheIndexing,
+            other => CacheAssociativity::Unknown(other),
+        }
+    }
+    pub const fn write_policy(&self) -> CacheWritePolicy {
+        match ((self.0 >> 12) & 0xF) as u8 {
+            0 => CacheWritePolicy::None,
+            1 => CacheWritePolicy::WriteBack,
+            2 => CacheWritePolicy::WriteThrough,
+            other => CacheWritePolicy::Unknown(other),
+        }
+    }
+    /// Cache line size in bytes.
+    pub const fn cache_line_size(&self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Side Cache Information Structure
+///
+/// Describes one level of memory side cache in front of a memory proximity domain.
+pub struct MemorySideCacheInformation {
+    /// 2 - Memory Side Cache Information Structure
+    pub r#type: u16,
+    reserved0: u16,
+    pub length: u32,
+    /// The memory proximity domain this cache sits in front of.
+    pub memory_proximity_domain: u32,
+    reserved1: u32,
+    /// Size of the memory side cache in bytes for this memory proximity domain.
+    pub memory_side_cache_size: u64,
+    pub cache_attributes: MemorySideCacheAttributes,
+    reserved2: u16,
+    /// Number of SMBIOS Type 17 handles that follow this structure.
+    pub number_of_smbios_handles: u16,
+    pub smbios_handles: [u16; 0],
+}
+impl MemorySideCacheInformation {
+    /// SMBIOS Type 17 (Memory Device) handles of the memory that makes up this cache.
+    pub const fn smbios_handles(&self) -> &[u16] {
+        let fit = (self.length as usize).saturating_sub(32) / 2;
+        let count = if (self.number_of_smbios_handles as usize) < fit {
+            self.number_of_smbios_handles as usize
+        } else {
+            fit
+        };
+        // SAFETY: the handles lie within the structure's length, which bounds the count.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(32) as *const u16,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum HMATStructure<'a> {
+    MemoryProximityDomainAttributes(&'a MemoryProximityDomainAttributes),
+    SystemLocalityLatencyBandwidthInformation(&'a SystemLocalityLatencyBandwidthInformation),
+    MemorySideCacheInformation(&'a MemorySideCacheInformation),
+    /// A structure type this library doesn't know about (or a reserved one).
+    Unknown(&'a HMATStructureHeader),
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Heterogeneous Memory Attribute Table (HMAT)
+///
+/// Describes the memory attributes, such as memory side cache attributes and bandwidth and latency details, related to memory proximity domains.
+/// OSPM uses it to optimize memory placement on platforms with memory of different performance (HBM, CXL-attached memory, persistent memory...).
+///
+/// The proximity domains in this table are the same as the ones in the SRAT.
+pub struct HMAT {
+    /// - **Signature** - "HMAT"
+    /// - **Revision** - 2
+    pub header: SDTHeader,
+    reserved: u32,
+    /// A list of HMAT structures.
+    pub hmat_structures: [u8; 0],
+}
+impl HMAT {
+    pub const fn structures(&self) -> HMATStructureIter<'_> {
+        HMATStructureIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 4),
+            end: (self as *const _ as *const u8).wrapping_add(self.header.length as usize),
+            _table: core::marker::PhantomData,
+        }
+    }
+    /// Finds the memory-level (not memory side cache) latency or bandwidth information for `data_type`.
+    pub fn locality(
+        &self,
+        data_type: HMATDataType,
+    ) -> Option<&SystemLocalityLatencyBandwidthInformation> {
+        self.structures().find_map(|s| match s {
+            HMATStructure::SystemLocalityLatencyBandwidthInformation(sllbi)
+                if sllbi.flags.memory_hierarchy() == MemoryHierarchy::Memory
+                    && sllbi.data_type() == data_type =>
+            {
+                Some(sllbi)
+            }
+            _ => None,
+        })
+    }
+    /// The memory latency from `initiator` to `target` in nanoseconds, using `data_type` (one of the latency types).
+    pub fn latency_ns(&self, data_type: HMATDataType, initiator: u32, target: u32) -> Option<u64> {
+        self.locality(data_type)?.latency_ns(initiator, target)
+    }
+    /// The memory bandwidth from `initiator` to `target` in MB/s, using `data_type` (one of the bandwidth types).
+    pub fn bandwidth_mbps(
+        &self,
+        data_type: HMATDataType,
+        initiator: u32,
+        target: u32,
+    ) -> Option<u64> {
+        self.locality(data_type)?.bandwidth_mbps(initiator, target)
+    }
+    /// Finds the memory side cache information of `level` for `memory_proximity_domain`.
+    pub fn memory_side_cache(
+        &self,
+        memory_proximity_domain: u32,
+        level: u8,
+    ) -> Option<&MemorySideCacheInformation> {
+        self.structures().find_map(|s| match s {
+            HMATStructure::MemorySideCacheInformation(cache)
+                if cache.memory_proximity_domain == memory_proximity_domain
+                    && { cache.cache_attributes }.cache_level() == level =>
+            {
+                Some(cache)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Iterator over the structures of an HMAT.
+pub struct HMATStructureIter<'a> {
+    next: *const u8,
+    end: *const u8,
+    _table: core::marker::PhantomData<&'a HMAT>,
+}
+impl<'a> Iterator for HMATStructureIter<'a> {
+    type Item = HMATStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let left = (self.end as usize).saturating_sub(self.next as usize);
+        if left < core::mem::size_of::<HMATStructureHeader>() {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const HMATStructureHeader) };
+        let length = header.length as usize;
+        if length < core::mem::size_of::<HMATStructureHeader>() || length > left {
+            // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+            self.next = self.end;
+            return None;
+        }
+        // SAFETY: the structure lies within the table (checked above), and is only cast to a type it's long enough for.
+        let structure = unsafe {
+            match header.r#type {
+                0 if length >= core::mem::size_of::<MemoryProximityDomainAttributes>() => {
+                    HMATStructure::MemoryProximityDomainAttributes(&*(self.next as *const _))
+                }
+                1 if length
+                    >= core::mem::size_of::<SystemLocalityLatencyBandwidthInformation>() =>
+                {
+                    HMATStructure::SystemLocalityLatencyBandwidthInformation(
+                        &*(self.next as *const _),
+                    )
+                }
+                2 if length >= core::mem::size_of::<MemorySideCacheInformation>() => {
+                    HMATStructure::MemorySideCacheInformation(&*(self.next as *const _))
+                }
+                _ => HMATStructure::Unknown(header),
+            }
+        };
+        self.next = self.next.wrapping_add(length);
+        Some(structure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2 initiators and 3 targets, starting at a 2-byte (not 4-byte) aligned offset.
+    fn locality(buffer: &mut [u64; 9], length: u32) -> &SystemLocalityLatencyBandwidthInformation {
+        // SAFETY: the buffer is 72 bytes.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 72) };
+        let structure = &mut bytes[2..];
+        structure[..2].copy_from_slice(&1u16.to_le_bytes());
+        structure[4..8].copy_from_slice(&length.to_le_bytes());
+        structure[12..16].copy_from_slice(&2u32.to_le_bytes());
+        structure[16..20].copy_from_slice(&3u32.to_le_bytes());
+        structure[24..32].copy_from_slice(&100u64.to_le_bytes());
+        for (i, domain) in [0u32, 1, 4, 5, 6].iter().enumerate() {
+            structure[32 + i * 4..][..4].copy_from_slice(&domain.to_le_bytes());
+        }
+        for (i, entry) in [1u16, 2, 0, 4, 5, 6].iter().enumerate() {
+            structure[52 + i * 2..][..2].copy_from_slice(&entry.to_le_bytes());
+        }
+        // SAFETY: the structure is packed, and lies within the buffer.
+        unsafe { &*(structure.as_ptr() as *const SystemLocalityLatencyBandwidthInformation) }
+    }
+
+    #[test]
+    fn entries() {
+        let mut buffer = [0u64; 9];
+        let locality = locality(&mut buffer, 64);
+        assert!(locality.initiator_proximity_domains().eq([0, 1]));
+        assert!(locality.target_proximity_domains().eq([4, 5, 6]));
+        assert_eq!(locality.entry(0, 5), Some(2));
+        assert_eq!(locality.entry(0, 6), None);
+        assert_eq!(locality.entry(1, 6), Some(6));
+        assert_eq!(locality.latency_ps(1, 4), Some(400));
+        assert_eq!(locality.entry(2, 4), None);
+    }
+
+    #[test]
+    fn bounded_by_length() {
+        let mut buffer = [0u64; 9];
+        let locality = locality(&mut buffer, 48);
+        assert!(locality.initiator_proximity_domains().eq([0, 1]));
+        assert!(locality.target_proximity_domains().eq([4, 5]));
+        assert_eq!(locality.entry(0, 4), None);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{GenericAddressAccess, GenericAddressStructure, SDTHeader};
+
+#[derive(Copy, Clone)]
+/// ## HPET Event Timer Block ID
+///
+/// This is the low 32 bits of the General Capabilities and ID register, copied into the table by the firmware.
+pub struct HPETEventTimerBlockID(u32);
+impl HPETEventTimerBlockID {
+    /// Hardware revision ID.
+    pub const fn hardware_rev_id(&self) -> u8 {
+        (self.0 & 0x000000FF) as u8
+    }
+    /// Number of comparators in the 1st timer block.
+    ///
+    /// The field holds the index of the last comparator, so this adds one to give the actual count.
+    pub const fn comparators(&self) -> u8 {
+        ((self.0 & 0x00001F00) >> 8) as u8 + 1
+    }
+    /// If set, the main counter is 64 bits wide. Otherwise it's 32 bits.
+    pub const fn count_size_cap(&self) -> bool {
+        self.0 & 0x00002000 != 0
+    }
+    /// If set, the block supports legacy replacement IRQ routing.
+    pub const fn legacy_replacement_irq_routing_capable(&self) -> bool {
+        self.0 & 0x00008000 != 0
+    }
+    /// PCI vendor ID of the 1st timer block.
+    pub const fn pci_vendor_id(&self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+}
+
+#[derive(Copy, Clone)]
+/// The page protection the HPET's register block has in the system memory map.
+pub enum HPETPageProtection {
+    /// No guarantee for page protection.
+    None,
+    /// 4KB page protected. Access to adjacent 3KB space will not generate machine check or compromise system security.
+    Protected4KB,
+    /// 64KB page protected. Access to adjacent 63KB space will not generate machine check or compromise system security.
+    Protected64KB,
+}
+
+#[derive(Copy, Clone)]
+/// ## HPET Page Protection And OEM Attribute
+pub struct HPETPageProtectionAndOEMAttribute(u8);
+impl HPETPageProtectionAndOEMAttribute {
+    /// Page protection of the timer block's registers.
+    pub const fn page_protection(&self) -> HPETPageProtection {
+        match self.0 & 0x0F {
+            0 => HPETPageProtection::None,
+            1 => HPETPageProtection::Protected4KB,
+            2 => HPETPageProtection::Protected64KB,
+            _ => panic!("HPET page protection set to a reserved value."),
+        }
+    }
+    /// OEM attributes, free for OEM use.
+    pub const fn oem_attribute(&self) -> u8 {
+        self.0 >> 4
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## High Precision Event Timer Description Table (HPET)
+///
+/// Defined by the IA-PC HPET (High Precision Event Timers) Specification, this table describes each event timer block so OSPM can find and use the HPET without the namespace.
+/// One table is published for each event timer block; `hpet_number` tells them apart.
+pub struct HighPrecisionEventTimerTable {
+    /// - **Signature** - "HPET"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    /// Hardware ID of Event Timer Block.
+    pub event_timer_block_id: HPETEventTimerBlockID,
+    /// Base address of the Event Timer Block. Each Event Timer Block consumes 1K of system memory, regardless of how many comparators are actually implemented by the hardware.
+    ///
+    /// Note: Only System Memory space is valid for `address_space_id`.
+    pub base_address: GenericAddressStructure,
+    /// The HPET sequence number. 0 means the 1st table, 1 means the 2nd table, and so on.
+    ///
+    /// This field is written by the BIOS at boot time and should not be altered by any other software.
+    pub hpet_number: u8,
+    /// The minimum clock ticks that can be set without losing interrupts while the counter is programmed to operate in periodic mode.
+    pub main_counter_minimum_clock_tick: u16,
+    /// Page protection and OEM attribute.
+    pub page_protection_and_oem_attribute: HPETPageProtectionAndOEMAttribute,
+}
+
+/// General Capabilities and ID Register.
+pub const HPET_GENERAL_CAPABILITIES_ID: u64 = 0x000;
+/// General Configuration Register.
+pub const HPET_GENERAL_CONFIGURATION: u64 = 0x010;
+/// General Interrupt Status Register.
+pub const HPET_GENERAL_INTERRUPT_STATUS: u64 = 0x020;
+/// Main Counter Value Register.
+pub const HPET_MAIN_COUNTER_VALUE: u64 = 0x0F0;
+/// Timer N Configuration and Capability Register, for timer 0. Add `HPET_TIMER_STRIDE * N` for timer N.
+pub const HPET_TIMER_CONFIGURATION: u64 = 0x100;
+/// Timer N Comparator Value Register, for timer 0. Add `HPET_TIMER_STRIDE * N` for timer N.
+pub const HPET_TIMER_COMPARATOR: u64 = 0x108;
+/// Timer N FSB Interrupt Route Register, for timer 0. Add `HPET_TIMER_STRIDE * N` for timer N.
+pub const HPET_TIMER_FSB_ROUTE: u64 = 0x110;
+/// Distance between the register sets of two consecutive timers.
+pub const HPET_TIMER_STRIDE: u64 = 0x20;
+
+#[derive(Copy, Clone)]
+/// ## HPET General Capabilities and ID Register
+pub struct HPETCapabilities(u64);
+impl HPETCapabilities {
+    /// The same layout as the table's Event Timer Block ID.
+    pub const fn block_id(&self) -> HPETEventTimerBlockID {
+        HPETEventTimerBlockID(self.0 as u32)
+    }
+    /// Main counter tick period in femtoseconds (10^-15 seconds). Must not be zero, and must be less than or equal to 0x05F5E100 (100 nanoseconds).
+    pub const fn counter_clk_period(&self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## HPET Timer N Configuration and Capability Register
+pub struct HPETTimerConfiguration(u64);
+impl HPETTimerConfiguration {
+    /// - 0 - The timer interrupt is edge triggered.
+    /// - 1 - The timer interrupt is level triggered.
+    pub const fn int_type_cnf(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+    /// Set if the timer is allowed to cause an interrupt.
+    pub const fn int_enb_cnf(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+    /// Set if the timer is in periodic mode. Otherwise it's in one-shot mode.
+    pub const fn type_cnf(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+    /// Set if the timer supports periodic mode.
+    pub const fn per_int_cap(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+    /// Set if the timer's comparator is 64 bits wide.
+    pub const fn size_cap(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+    /// Set if the timer is forced into 32-bit mode.
+    pub const fn mode32_cnf(&self) -> bool {
+        self.0 & (1 << 8) != 0
+    }
+    /// The I/O APIC input the timer's interrupt is routed to.
+    pub const fn int_route_cnf(&self) -> u8 {
+        ((self.0 >> 9) & 0x1F) as u8
+    }
+    /// Set if the timer delivers its interrupt as an FSB (MSI-like) message.
+    pub const fn fsb_en_cnf(&self) -> bool {
+        self.0 & (1 << 14) != 0
+    }
+    /// Set if the timer supports FSB interrupt delivery.
+    pub const fn fsb_int_del_cap(&self) -> bool {
+        self.0 & (1 << 15) != 0
+    }
+    /// Bit N is set if the timer's interrupt can be routed to I/O APIC input N.
+    pub const fn int_route_cap(&self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+}
+
+const TN_INT_TYPE_CNF: u64 = 1 << 1;
+const TN_INT_ENB_CNF: u64 = 1 << 2;
+const TN_TYPE_CNF: u64 = 1 << 3;
+const TN_VAL_SET_CNF: u64 = 1 << 6;
+const TN_INT_ROUTE_CNF: u64 = 0x1F << 9;
+const TN_FSB_EN_CNF: u64 = 1 << 14;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the HPET driver can run into.
+pub enum HPETError {
+    /// The timer index is past the last comparator.
+    NoSuchTimer(u8),
+    /// Periodic mode was asked of a timer without `per_int_cap`.
+    PeriodicNotSupported(u8),
+    /// The timer can't route its interrupt to the asked-for I/O APIC input.
+    RouteNotSupported(u8),
+    /// COUNTER_CLK_PERIOD is zero or above 0x05F5E100 (100 ns), which the spec doesn't allow.
+    InvalidPeriod(u32),
+}
+
+/// The longest main counter tick period the spec allows, in femtoseconds (100 ns).
+pub const HPET_MAX_PERIOD_FS: u32 = 0x05F5E100;
+
+/// ## HPET Driver
+///
+/// A small driver for one event timer block, going through `GenericAddressAccess` for its registers (every register is a 64-bit System Memory access relative to `base_address`).
+pub struct HighPrecisionEventTimer<A: GenericAddressAccess> {
+    /// The event timer block's register base.
+    pub base_address: GenericAddressStructure,
+    access: A,
+    capabilities: HPETCapabilities,
+    last_counter: u64,
+    wraps: u64,
+}
+impl<A: GenericAddressAccess> HighPrecisionEventTimer<A> {
+    /// Builds a driver for the block at `base_address` and reads its capabilities.
+    ///
+    /// Fails if the block reports a tick period the spec doesn't allow, since every time conversion divides by it.
+    pub fn new(base_address: GenericAddressStructure, access: A) -> Result<Self, HPETError> {
+        let mut hpet = Self {
+            base_address,
+            access,
+            capabilities: HPETCapabilities(0),
+            last_counter: 0,
+            wraps: 0,
+        };
+        hpet.capabilities = HPETCapabilities(hpet.read(HPET_GENERAL_CAPABILITIES_ID));
+        let period = hpet.period_fs();
+        if period == 0 || period > HPET_MAX_PERIOD_FS {
+            return Err(HPETError::InvalidPeriod(period));
+        }
+        Ok(hpet)
+    }
+    pub fn from_table(table: &HighPrecisionEventTimerTable, access: A) -> Result<Self, HPETError> {
+        Self::new(table.base_address, access)
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.access
+    }
+
+    fn register(&self, offset: u64) -> GenericAddressStructure {
+        GenericAddressStructure {
+            address_space_id: 0,
+            reg_bit_width: 64,
+            reg_bit_offset: 0,
+            access_size: 4,
+            address: self.base_address.address + offset,
+        }
+    }
+    /// Reads the 64-bit register at `offset` from the block's base.
+    pub fn read(&mut self, offset: u64) -> u64 {
+        let reg = self.register(offset);
+        self.access.read(&reg)
+    }
+    /// Writes the 64-bit register at `offset` from the block's base.
+    pub fn write(&mut self, offset: u64, value: u64) {
+        let reg = self.register(offset);
+        self.access.write(&reg, value)
+    }
+
+    /// The General Capabilities and ID register, as read when the driver was built.
+    pub const fn capabilities(&self) -> HPETCapabilities {
+        self.capabilities
+    }
+    /// Main counter tick period, in femtoseconds.
+    pub const fn period_fs(&self) -> u32 {
+        self.capabilities.counter_clk_period()
+    }
+    /// Main counter frequency, in Hz.
+    pub const fn frequency(&self) -> u64 {
+        1_000_000_000_000_000 / self.period_fs() as u64
+    }
+    /// Number of comparators (timers) in the block.
+    pub const fn timers(&self) -> u8 {
+        self.capabilities.block_id().comparators()
+    }
+    /// Converts a number of main counter ticks into nanoseconds.
+    pub const fn ticks_to_ns(&self, ticks: u64) -> u64 {
+        (ticks as u128 * self.period_fs() as u128 / 1_000_000) as u64
+    }
+    /// Converts nanoseconds into main counter ticks, rounding up.
+    pub const fn ns_to_ticks(&self, ns: u64) -> u64 {
+        (ns as u128 * 1_000_000).div_ceil(self.period_fs() as u128) as u64
+    }
+
+    /// Starts or halts the main counter (ENABLE_CNF).
+    pub fn enable(&mut self, enable: bool) {
+        let config = self.read(HPET_GENERAL_CONFIGURATION);
+        self.write(
+            HPET_GENERAL_CONFIGURATION,
+            if enable { config | 1 } else { config & !1 },
+        );
+    }
+    /// Turns legacy replacement routing (LEG_RT_CNF) on or off.
+    ///
+    /// When on, timer 0 replaces IRQ0 (and I/O APIC input 2) and timer 1 replaces IRQ8 (the RTC).
+    pub fn legacy_replacement(&mut self, enable: bool) {
+        let config = self.read(HPET_GENERAL_CONFIGURATION);
+        self.write(
+            HPET_GENERAL_CONFIGURATION,
+            if enable { config | 2 } else { config & !2 },
+        );
+    }
+    /// The raw main counter value.
+    ///
+    /// Only write the main counter while it's halted, the spec leaves it undefined otherwise.
+    pub fn counter(&mut self) -> u64 {
+        self.read(HPET_MAIN_COUNTER_VALUE)
+    }
+    /// A monotonic 64-bit tick count.
+    ///
+    /// On blocks with a 32-bit main counter, wraparounds are counted in software, so this must be called at least once per wrap (about 5 minutes at 14.318 MHz).
+    pub fn monotonic(&mut self) -> u64 {
+        let counter = self.counter();
+        if self.capabilities.block_id().count_size_cap() {
+            return counter;
+        }
+        let counter = counter & 0xFFFF_FFFF;
+        if counter < self.last_counter {
+            self.wraps += 1;
+        }
+        self.last_counter = counter;
+        self.wraps << 32 | counter
+    }
+    /// Nanoseconds since the main counter was last reset, built on `monotonic`.
+    pub fn monotonic_ns(&mut self) -> u64 {
+        let ticks = self.monotonic();
+        self.ticks_to_ns(ticks)
+    }
+
+    /// Reads timer `n`'s configuration and capability register.
+    pub fn timer(&mut self, n: u8) -> Result<HPETTimerConfiguration, HPETError> {
+        if n >= self.timers() {
+            return Err(HPETError::NoSuchTimer(n));
+        }
+        Ok(HPETTimerConfiguration(self.read(
+            HPET_TIMER_CONFIGURATION + HPET_TIMER_STRIDE * n as u64,
+        )))
+    }
+    /// Reads timer `n`'s comparator.
+    pub fn comparator(&mut self, n: u8) -> Result<u64, HPETError> {
+        self.timer(n)?;
+        Ok(self.read(HPET_TIMER_COMPARATOR + HPET_TIMER_STRIDE * n as u64))
+    }
+    fn configure(
+        &mut self,
+        n: u8,
+        route: u8,
+        level: bool,
+        periodic: bool,
+    ) -> Result<u64, HPETError> {
+        let timer = self.timer(n)?;
+        if periodic && !timer.per_int_cap() {
+            return Err(HPETError::PeriodicNotSupported(n));
+        }
+        if route >= 32 || timer.int_route_cap() & (1 << route) == 0 {
+            return Err(HPETError::RouteNotSupported(route));
+        }
+        let mut config =
+            timer.0 & !(TN_INT_TYPE_CNF | TN_TYPE_CNF | TN_INT_ROUTE_CNF | TN_FSB_EN_CNF);
+        config |= TN_INT_ENB_CNF | (route as u64) << 9;
+        if level {
+            config |= TN_INT_TYPE_CNF;
+        }
+        if periodic {
+            config |= TN_TYPE_CNF | TN_VAL_SET_CNF;
+        }
+        Ok(config)
+    }
+    /// Arms timer `n` to fire once, `ticks` main counter ticks from now, on I/O APIC input `route`.
+    pub fn one_shot(&mut self, n: u8, ticks: u64, route: u8, level: bool) -> Result<(), HPETError> {
+        let config = self.configure(n, route, level, false)?;
+        let offset = HPET_TIMER_STRIDE * n as u64;
+        self.write(HPET_TIMER_CONFIGURATION + offset, config);
+        let deadline = self.counter().wrapping_add(ticks);
+        self.write(HPET_TIMER_COMPARATOR + offset, deadline);
+        Ok(())
+    }
+    /// Sets timer `n` to fire every `period` main counter ticks on I/O APIC input `route`.
+    ///
+    /// `period` should be at least the table's `main_counter_minimum_clock_tick`, or interrupts can be lost.
+    pub fn periodic(
+        &mut self,
+        n: u8,
+        period: u64,
+        route: u8,
+        level: bool,
+    ) -> Result<(), HPETError> {
+        let config = self.configure(n, route, level, true)?;
+        let offset = HPET_TIMER_STRIDE * n as u64;
+        // With Tn_VAL_SET_CNF set, the first write sets the comparator and the second sets the period.
+        self.write(HPET_TIMER_CONFIGURATION + offset, config);
+        let first = self.counter().wrapping_add(period);
+        self.write(HPET_TIMER_COMPARATOR + offset, first);
+        self.write(HPET_TIMER_COMPARATOR + offset, period);
+        Ok(())
+    }
+    /// Stops timer `n` from raising interrupts.
+    pub fn disable_timer(&mut self, n: u8) -> Result<(), HPETError> {
+        let timer = self.timer(n)?;
+        self.write(
+            HPET_TIMER_CONFIGURATION + HPET_TIMER_STRIDE * n as u64,
+            timer.0 & !TN_INT_ENB_CNF,
+        );
+        Ok(())
+    }
+    /// Acknowledges timer `n`'s level-triggered interrupt in the General Interrupt Status register.
+    pub fn acknowledge(&mut self, n: u8) -> Result<(), HPETError> {
+        if n >= self.timers() {
+            return Err(HPETError::NoSuchTimer(n));
+        }
+        self.write(HPET_GENERAL_INTERRUPT_STATUS, 1 << n);
+        Ok(())
+    }
+}
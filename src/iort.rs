@@ -0,0 +1,565 @@
+use crate::{SDT_HEADER_SIZE, SDTHeader};
+use core::ffi::CStr;
+
+#[derive(Copy, Clone)]
+pub struct IDMappingFlags(u32);
+impl IDMappingFlags {
+    /// If set, this mapping doesn't translate a range of IDs; it gives the single ID the node itself uses (its own MSIs, for example),
+    /// and `input_base` is ignored.
+    pub const fn single_mapping(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ID Mapping
+///
+/// Maps a range of IDs coming into a node (requester IDs, stream IDs...) onto a range of IDs going out to another node (an SMMU or an ITS group).
+pub struct IDMapping {
+    /// The lowest value in the input range.
+    pub input_base: u32,
+    /// The number of IDs in the range **minus one**.
+    pub number_of_ids: u32,
+    /// The lowest value in the output range.
+    pub output_base: u32,
+    /// Offset, from the start of the IORT, of the node the output IDs go to.
+    pub output_reference: u32,
+    pub flags: IDMappingFlags,
+}
+impl IDMapping {
+    /// Returns true if `id` is in the input range.
+    pub const fn contains(&self, id: u32) -> bool {
+        id >= self.input_base && id - self.input_base <= self.number_of_ids
+    }
+    /// Maps `id` to its output ID.  A single mapping always gives `output_base`.
+    ///
+    /// Returns `None` if `id` isn't in the input range, or if the output ID doesn't fit in 32 bits.
+    pub const fn map(&self, id: u32) -> Option<u32> {
+        let flags = self.flags;
+        if flags.single_mapping() {
+            return Some(self.output_base);
+        }
+        if !self.contains(id) {
+            return None;
+        }
+        self.output_base.checked_add(id - self.input_base)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// The common beginning of every IORT node.
+pub struct IORTNodeHeader {
+    /// - **0** - ITS Group
+    /// - **1** - Named Component
+    /// - **2** - Root Complex
+    /// - **3** - SMMUv1 or SMMUv2
+    /// - **4** - SMMUv3
+    /// - **5** - PMCG
+    /// - **6** - Memory Range
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u8,
+    /// Length of the node in bytes.
+    pub length: u16,
+    pub revision: u8,
+    /// A unique ID for this node, used to refer to it from outside the IORT (revision E.c and up; reserved before that).
+    pub identifier: u32,
+    pub number_of_id_mappings: u32,
+    /// Offset, from the start of this node, of its array of ID mappings.
+    pub reference_to_id_array: u32,
+}
+impl IORTNodeHeader {
+    pub const fn id_mappings(&self) -> &[IDMapping] {
+        self.array(self.reference_to_id_array, self.number_of_id_mappings)
+    }
+    /// The `count` entries at `offset` from the start of the node, cut short at the node's length.
+    const fn array<T>(&self, offset: u32, count: u32) -> &[T] {
+        let fits =
+            (self.length as usize).saturating_sub(offset as usize) / core::mem::size_of::<T>();
+        let count = if (count as usize) < fits {
+            count as usize
+        } else {
+            fits
+        };
+        if count == 0 {
+            return &[];
+        }
+        // SAFETY: the entries lie within the node's length, which bounds the count, and `IORT::node_at` checked that length against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(offset as usize) as *const T,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ITS Group Node
+///
+/// A group of GIC ITSs that a device's MSIs can be sent to. The identifiers match `GICInterruptTranslationService::gic_its_id` in the MADT.
+pub struct ITSGroupNode {
+    /// - **Type** - 0
+    pub header: IORTNodeHeader,
+    pub number_of_its: u32,
+    pub its_identifiers: [u32; 0],
+}
+impl ITSGroupNode {
+    pub const fn its_identifiers(&self) -> &[u32] {
+        self.header.array(20, self.number_of_its)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Access Properties
+pub struct MemoryAccessProperties {
+    /// - **0** - The device isn't fully coherent
+    /// - **1** - The device is fully coherent
+    pub cache_coherency: u32,
+    /// - **Bit 0** - Transient
+    /// - **Bit 1** - Write allocate
+    /// - **Bit 2** - Read allocate
+    /// - **Bit 3** - Override the allocation hints from the device
+    pub allocation_hints: u8,
+    reserved: u16,
+    /// - **Bit 0** - Coherent path to memory
+    /// - **Bit 1** - Device attributes are cacheable and inner shareable
+    /// - **Bit 2** - Device attributes are cacheable and outer shareable (rev E.b and up: Outer Shareable)
+    pub memory_access_flags: u8,
+}
+impl MemoryAccessProperties {
+    pub const fn coherent(&self) -> bool {
+        self.cache_coherency == 1
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Named Component Node
+///
+/// A device that isn't behind a PCI root complex, named by its full path in the namespace.
+pub struct NamedComponentNode {
+    /// - **Type** - 1
+    pub header: IORTNodeHeader,
+    /// - **Bit 0** - Stall supported
+    /// - **Bits [[5:1]]** - Substream width (PASID bits) minus 1
+    pub node_flags: u32,
+    pub memory_access_properties: MemoryAccessProperties,
+    /// The number of address bits the device can generate.
+    pub device_memory_address_size_limit: u8,
+    pub device_object_name: [u8; 0],
+}
+impl NamedComponentNode {
+    /// The full path of the device in the namespace (for example "\_SB.ETH0").
+    pub fn device_object_name(&self) -> &CStr {
+        // SAFETY: the name runs to the end of the node, and `IORT::node_at` checked the node's length against the table.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(29),
+                (self.header.length as usize).saturating_sub(29),
+            )
+        };
+        CStr::from_bytes_until_nul(bytes).unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Root Complex Node
+///
+/// A PCI root complex. The ID mappings take the requester ID (bus << 8 | device << 3 | function) as input.
+pub struct RootComplexNode {
+    /// - **Type** - 2
+    pub header: IORTNodeHeader,
+    pub memory_access_properties: MemoryAccessProperties,
+    /// - **0** - The root complex doesn't support ATS
+    /// - **1** - The root complex supports ATS
+    pub ats_attribute: u32,
+    /// The PCI segment number, as in the MCFG and the _SEG method.
+    pub pci_segment_number: u32,
+    /// The number of address bits the root complex can generate (revision 1 and up).
+    pub memory_address_size_limit: u8,
+    /// Bits [[4:0]] are the max PASID width (revision 3 and up).
+    pub pasid_capabilities: u16,
+    reserved: u8,
+    /// - **Bit 0** - PASID forwarding supported (revision 4 and up)
+    pub flags: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SMMU Interrupt
+pub struct SMMUInterrupt {
+    pub gsiv: u32,
+    /// - **Bit 0** - 0 = Level-triggered, 1 = Edge-triggered
+    pub flags: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SMMUv1 or SMMUv2 Node
+pub struct SMMUv1v2Node {
+    /// - **Type** - 3
+    pub header: IORTNodeHeader,
+    pub base_address: u64,
+    /// The size of the SMMU's address space.
+    pub span: u64,
+    /// - **0** - Generic SMMUv1
+    /// - **1** - Generic SMMUv2
+    /// - **2** - Arm MMU-400
+    /// - **3** - Arm MMU-500
+    /// - **4** - Arm MMU-401
+    /// - **5** - Cavium ThunderX SMMUv2
+    pub model: u32,
+    /// - **Bit 0** - DVM supported
+    /// - **Bit 1** - Coherent page table walk
+    pub flags: u32,
+    /// Offset, from the start of this node, of the global interrupt array.
+    pub reference_to_global_interrupt_array: u32,
+    pub number_of_context_interrupts: u32,
+    /// Offset, from the start of this node, of the context interrupt array.
+    pub reference_to_context_interrupt_array: u32,
+    pub number_of_pmu_interrupts: u32,
+    /// Offset, from the start of this node, of the PMU interrupt array.
+    pub reference_to_pmu_interrupt_array: u32,
+    /// SMMU_NSgIrpt, then SMMU_NSgCfgIrpt.
+    pub global_interrupts: [SMMUInterrupt; 2],
+}
+impl SMMUv1v2Node {
+    pub const fn context_interrupts(&self) -> &[SMMUInterrupt] {
+        self.header.array(
+            self.reference_to_context_interrupt_array,
+            self.number_of_context_interrupts,
+        )
+    }
+    pub const fn pmu_interrupts(&self) -> &[SMMUInterrupt] {
+        self.header.array(
+            self.reference_to_pmu_interrupt_array,
+            self.number_of_pmu_interrupts,
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SMMUv3Flags(u32);
+impl SMMUv3Flags {
+    /// If set, the COHACC bit of the SMMU_IDR0 register is overridden (the page table walk is coherent).
+    pub const fn cohacc_override(&self) -> bool {
+        self.0 & 0b00001 != 0
+    }
+    /// - **0** - HTTU isn't overridden
+    /// - **1** - Access flag updates are supported
+    /// - **2** - Access and dirty flag updates are supported
+    pub const fn httu_override(&self) -> u8 {
+        ((self.0 >> 1) & 0b11) as u8
+    }
+    /// If set, `proximity_domain` is valid.
+    pub const fn proximity_domain_valid(&self) -> bool {
+        self.0 & 0b01000 != 0
+    }
+    /// If set, `device_id_mapping_index` is valid.
+    pub const fn device_id_mapping_index_valid(&self) -> bool {
+        self.0 & 0b10000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SMMUv3 Node
+pub struct SMMUv3Node {
+    /// - **Type** - 4
+    pub header: IORTNodeHeader,
+    pub base_address: u64,
+    pub flags: SMMUv3Flags,
+    reserved: u32,
+    /// Base address of the VATOS page, or 0 if there is none.
+    pub vatos_address: u64,
+    /// - **0** - Generic SMMUv3
+    /// - **1** - HiSilicon Hi161x SMMUv3
+    /// - **2** - Cavium CN99xx SMMUv3
+    pub model: u32,
+    /// GSIV of the event interrupt (0 if it's an MSI).
+    pub event: u32,
+    /// GSIV of the PRI interrupt (0 if it's an MSI).
+    pub pri: u32,
+    /// GSIV of the global error interrupt (0 if it's an MSI).
+    pub gerr: u32,
+    /// GSIV of the sync interrupt (0 if it's an MSI).
+    pub sync: u32,
+    /// The proximity domain the SMMU belongs to, if `flags.proximity_domain_valid()` is set.
+    pub proximity_domain: u32,
+    /// The index of the ID mapping the SMMU's own MSIs go through, if `flags.device_id_mapping_index_valid()` is set.
+    pub device_id_mapping_index: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Performance Monitoring Counter Group (PMCG) Node
+pub struct PMCGNode {
+    /// - **Type** - 5
+    pub header: IORTNodeHeader,
+    pub page0_base_address: u64,
+    /// GSIV of the overflow interrupt (0 if it's an MSI).
+    pub overflow_interrupt_gsiv: u32,
+    /// Offset, from the start of the IORT, of the node this PMCG monitors.
+    pub node_reference: u32,
+    pub page1_base_address: u64,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Range Descriptor
+pub struct MemoryRangeDescriptor {
+    pub physical_range_offset: u64,
+    pub physical_range_length: u64,
+    reserved: u32,
+}
+
+#[derive(Copy, Clone)]
+pub struct RMRFlags(u32);
+impl RMRFlags {
+    /// If set, the OS may remap the ranges once it no longer needs them identity-mapped.
+    pub const fn remapping_permitted(&self) -> bool {
+        self.0 & 0b01 != 0
+    }
+    /// If set, the ranges must be mapped with privileged access.
+    pub const fn access_privileged(&self) -> bool {
+        self.0 & 0b10 != 0
+    }
+    /// The memory attributes the ranges must be mapped with (0x00 is Device-nGnRnE ... 0x05 is Normal Write-Back cacheable).
+    pub const fn access_attributes(&self) -> u8 {
+        (self.0 >> 2) as u8
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Reserved Memory Range (RMR) Node
+///
+/// Memory ranges that the devices mapped by this node's ID mappings use (set up by firmware) and must stay identity-mapped in the SMMU.
+pub struct RMRNode {
+    /// - **Type** - 6
+    pub header: IORTNodeHeader,
+    pub flags: RMRFlags,
+    pub number_of_memory_range_descriptors: u32,
+    /// Offset, from the start of this node, of the memory range descriptor array.
+    pub reference_to_memory_range_descriptors: u32,
+}
+impl RMRNode {
+    pub const fn memory_range_descriptors(&self) -> &[MemoryRangeDescriptor] {
+        self.header.array(
+            self.reference_to_memory_range_descriptors,
+            self.number_of_memory_range_descriptors,
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum IORTNode<'a> {
+    ITSGroup(&'a ITSGroupNode),
+    NamedComponent(&'a NamedComponentNode),
+    RootComplex(&'a RootComplexNode),
+    SMMUv1v2(&'a SMMUv1v2Node),
+    SMMUv3(&'a SMMUv3Node),
+    PMCG(&'a PMCGNode),
+    RMR(&'a RMRNode),
+    /// A node type this library doesn't know about (or a reserved one).
+    Unknown(&'a IORTNodeHeader),
+}
+impl<'a> IORTNode<'a> {
+    pub const fn header(&self) -> &'a IORTNodeHeader {
+        match *self {
+            Self::ITSGroup(node) => &node.header,
+            Self::NamedComponent(node) => &node.header,
+            Self::RootComplex(node) => &node.header,
+            Self::SMMUv1v2(node) => &node.header,
+            Self::SMMUv3(node) => &node.header,
+            Self::PMCG(node) => &node.header,
+            Self::RMR(node) => &node.header,
+            Self::Unknown(header) => header,
+        }
+    }
+    pub const fn id_mappings(&self) -> &'a [IDMapping] {
+        self.header().id_mappings()
+    }
+    pub const fn is_smmu(&self) -> bool {
+        matches!(self, Self::SMMUv1v2(_) | Self::SMMUv3(_))
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## ID Resolution
+///
+/// Where a device's ID ends up after going through the IORT node graph.
+pub struct IDResolution<'a> {
+    /// The SMMU the device sits behind, if any.
+    pub smmu: Option<IORTNode<'a>>,
+    /// The stream ID of the device at that SMMU.
+    pub stream_id: Option<u32>,
+    /// The ITS group the device's MSIs go to, if any.
+    pub its_group: Option<&'a ITSGroupNode>,
+    /// The device ID the ITS sees for the device's MSIs.
+    pub device_id: Option<u32>,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## IO Remapping Table (IORT)
+///
+/// Describes the IO topology of Arm systems: how PCI root complexes and named components are connected to SMMUs and GIC ITSs,
+/// and how the IDs their devices send (requester IDs, stream IDs) are translated on the way.
+pub struct IORT {
+    /// - **Signature** - "IORT"
+    /// - **Revision** - 5 (E.e/E.f)
+    pub header: SDTHeader,
+    pub number_of_iort_nodes: u32,
+    /// Offset, from the start of this table, of the first node.
+    pub offset_to_array_of_iort_nodes: u32,
+    reserved: u32,
+}
+impl IORT {
+    pub const fn nodes(&self) -> IORTNodeIter<'_> {
+        IORTNodeIter {
+            iort: self,
+            offset: self.offset_to_array_of_iort_nodes,
+            remaining: self.number_of_iort_nodes,
+        }
+    }
+    /// The node at `offset` from the start of the table, as found in `output_reference` and `node_reference`.
+    ///
+    /// Returns `None` if the node doesn't lie within the table, or is shorter than its header.
+    pub fn node_at(&self, offset: u32) -> Option<IORTNode<'_>> {
+        let header_size = core::mem::size_of::<IORTNodeHeader>();
+        let left = (self.header.length as usize).saturating_sub(offset as usize);
+        if (offset as usize) < SDT_HEADER_SIZE + 12 || left < header_size {
+            return None;
+        }
+        let ptr = (self as *const _ as *const u8).wrapping_add(offset as usize);
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(ptr as *const IORTNodeHeader) };
+        let length = header.length as usize;
+        if length < header_size || length > left {
+            return None;
+        }
+        let fits = |size: usize| length >= size;
+        // SAFETY: the node lies within the table (checked above), and is only cast to a type it's long enough for.
+        unsafe {
+            Some(match header.r#type {
+                0 if fits(core::mem::size_of::<ITSGroupNode>()) => {
+                    IORTNode::ITSGroup(&*(ptr as *const _))
+                }
+                1 if fits(core::mem::size_of::<NamedComponentNode>()) => {
+                    IORTNode::NamedComponent(&*(ptr as *const _))
+                }
+                2 if fits(core::mem::size_of::<RootComplexNode>()) => {
+                    IORTNode::RootComplex(&*(ptr as *const _))
+                }
+                3 if fits(core::mem::size_of::<SMMUv1v2Node>()) => {
+                    IORTNode::SMMUv1v2(&*(ptr as *const _))
+                }
+                4 if fits(core::mem::size_of::<SMMUv3Node>()) => {
+                    IORTNode::SMMUv3(&*(ptr as *const _))
+                }
+                5 if fits(core::mem::size_of::<PMCGNode>()) => IORTNode::PMCG(&*(ptr as *const _)),
+                6 if fits(core::mem::size_of::<RMRNode>()) => IORTNode::RMR(&*(ptr as *const _)),
+                _ => IORTNode::Unknown(header),
+            })
+        }
+    }
+    /// The root complex node of PCI segment `segment`.
+    pub fn root_complex(&self, segment: u32) -> Option<&RootComplexNode> {
+        self.nodes().find_map(|node| match node {
+            IORTNode::RootComplex(rc) if rc.pci_segment_number == segment => Some(rc),
+            _ => None,
+        })
+    }
+    /// The named component node whose namespace path is `name` (for example "\_SB.ETH0").
+    pub fn named_component(&self, name: &[u8]) -> Option<&NamedComponentNode> {
+        self.nodes().find_map(|node| match node {
+            IORTNode::NamedComponent(nc) if nc.device_object_name().to_bytes() == name => Some(nc),
+            _ => None,
+        })
+    }
+    /// Resolves the requester ID (bus << 8 | device << 3 | function) of a PCI device in segment `segment`.
+    pub fn resolve_pci(&self, segment: u32, requester_id: u32) -> Option<IDResolution<'_>> {
+        let rc = self.root_complex(segment)?;
+        Some(self.resolve(IORTNode::RootComplex(rc), requester_id))
+    }
+    /// Resolves the stream ID of a named component's device.
+    pub fn resolve_named_component(&self, name: &[u8], id: u32) -> Option<IDResolution<'_>> {
+        let nc = self.named_component(name)?;
+        Some(self.resolve(IORTNode::NamedComponent(nc), id))
+    }
+    /// Follows `id` from `node` through the ID mappings until it reaches an ITS group, or a node that doesn't map it any further.
+    ///
+    /// The ID going into the first SMMU on the way is its stream ID, and the ID reaching the ITS group is the device ID.
+    ///
+    /// Single mappings are only followed from root complexes and named components;
+    /// on an SMMU they describe the SMMU's own MSIs, not the devices behind it (the same thing Linux does).
+    pub fn resolve<'a>(&'a self, mut node: IORTNode<'a>, mut id: u32) -> IDResolution<'a> {
+        let mut resolution = IDResolution {
+            smmu: None,
+            stream_id: None,
+            its_group: None,
+            device_id: None,
+        };
+        // A loop in the node graph would otherwise keep us here forever.
+        for _ in 0..16 {
+            if let IORTNode::ITSGroup(its) = node {
+                resolution.its_group = Some(its);
+                resolution.device_id = Some(id);
+                break;
+            }
+            if node.is_smmu() && resolution.smmu.is_none() {
+                resolution.smmu = Some(node);
+                resolution.stream_id = Some(id);
+            }
+            let follow_single =
+                matches!(node, IORTNode::RootComplex(_) | IORTNode::NamedComponent(_));
+            let Some(mapping) = node.id_mappings().iter().find(|mapping| {
+                if ({ mapping.flags }).single_mapping() {
+                    follow_single
+                } else {
+                    mapping.contains(id)
+                }
+            }) else {
+                break;
+            };
+            let (Some(next), Some(mapped)) =
+                (self.node_at(mapping.output_reference), mapping.map(id))
+            else {
+                break;
+            };
+            id = mapped;
+            node = next;
+        }
+        resolution
+    }
+}
+
+/// Iterator over the nodes of an IORT.
+pub struct IORTNodeIter<'a> {
+    iort: &'a IORT,
+    offset: u32,
+    remaining: u32,
+}
+impl<'a> Iterator for IORTNodeIter<'a> {
+    type Item = IORTNode<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // `node_at` turns down nodes shorter than their header, so a zero length can't loop forever.
+        let node = self.iort.node_at(self.offset)?;
+        self.offset += node.header().length as u32;
+        self.remaining -= 1;
+        Some(node)
+    }
+}
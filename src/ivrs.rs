@@ -0,0 +1,642 @@
+use crate::{SDT_HEADER_SIZE, SDTHeader};
+
+#[derive(Copy, Clone)]
+pub struct IVInfo(u32);
+impl IVInfo {
+    /// If set, the IVHDs' EFR register images are valid.
+    pub const fn efr_supported(&self) -> bool {
+        self.0 & 1 != 0
+    }
+    /// The guest virtual address size the IOMMUs support.
+    pub const fn gva_size(&self) -> u8 {
+        ((self.0 >> 5) & 0b111) as u8
+    }
+    /// The number of physical address bits the IOMMUs support.
+    pub const fn pa_size(&self) -> u8 {
+        ((self.0 >> 8) & 0x7F) as u8
+    }
+    /// The number of virtual address bits the IOMMUs support.
+    pub const fn va_size(&self) -> u8 {
+        ((self.0 >> 15) & 0x7F) as u8
+    }
+    /// If set, the IOMMUs can't update the accessed and dirty bits of the host page tables.
+    pub const fn ht_ats_resv(&self) -> bool {
+        self.0 & (1 << 22) != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// ## IVHD DTE Setting
+///
+/// The device table entry bits an IVHD device entry asks for.
+pub struct DTESetting(u8);
+impl DTESetting {
+    pub const fn new(value: u8) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+    /// INIT interrupts pass through untranslated.
+    pub const fn init_pass(&self) -> bool {
+        self.0 & 0b00000001 != 0
+    }
+    /// ExtInt interrupts pass through untranslated.
+    pub const fn eint_pass(&self) -> bool {
+        self.0 & 0b00000010 != 0
+    }
+    /// NMIs pass through untranslated.
+    pub const fn nmi_pass(&self) -> bool {
+        self.0 & 0b00000100 != 0
+    }
+    /// What happens to system management messages:
+    /// - **0b00** - Target aborted
+    /// - **0b01** - Forwarded untranslated
+    /// - **0b10** - Remapped through the interrupt remapping table
+    pub const fn sys_mgt(&self) -> u8 {
+        (self.0 >> 4) & 0b11
+    }
+    /// LINT0 interrupts pass through untranslated.
+    pub const fn lint0_pass(&self) -> bool {
+        self.0 & 0b01000000 != 0
+    }
+    /// LINT1 interrupts pass through untranslated.
+    pub const fn lint1_pass(&self) -> bool {
+        self.0 & 0b10000000 != 0
+    }
+}
+impl core::fmt::Debug for DTESetting {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "DTESetting({:#04x})", self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpecialDeviceVariety {
+    IOAPIC,
+    HPET,
+    Unknown(u8),
+}
+impl SpecialDeviceVariety {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::IOAPIC,
+            2 => Self::HPET,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ACPI HID Device Entry
+///
+/// An IVHD device entry (type 0xF0) naming an ACPI device (by _HID, _CID and _UID) instead of a PCI device.
+/// Only type 0x40 IVHDs have these.
+pub struct ACPIHIDDeviceEntry {
+    pub r#type: u8,
+    /// The device ID the IOMMU sees for the device.
+    pub device_id: u16,
+    pub dte_setting: DTESetting,
+    /// The _HID of the device (an ASCII string, null padded).
+    pub hid: [u8; 8],
+    /// The _CID of the device (an ASCII string, null padded), or zeros.
+    pub cid: [u8; 8],
+    /// - **0** - No _UID
+    /// - **1** - Integer _UID (`uid` is a little-endian integer)
+    /// - **2** - String _UID (`uid` is an ASCII string)
+    pub uid_format: u8,
+    pub uid_length: u8,
+    pub uid: [u8; 0],
+}
+impl ACPIHIDDeviceEntry {
+    pub const fn length(&self) -> usize {
+        22 + self.uid_length as usize
+    }
+    /// The _HID, without the null padding.
+    pub fn hid(&self) -> &[u8] {
+        let len = self.hid.iter().position(|&b| b == 0).unwrap_or(8);
+        &self.hid[..len]
+    }
+    pub const fn uid(&self) -> &[u8] {
+        // SAFETY: the _UID lies within the entry's `length()`, which `IVHDDeviceEntryIter` checked against the IVHD.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(22),
+                self.uid_length as usize,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+/// An IVHD device entry, decoded.
+///
+/// "Start of range" entries open a range of device IDs that the next `RangeEnd` closes; `IVHD::device_ranges` pairs them up.
+pub enum IVHDDeviceEntry<'a> {
+    /// Type 0x00 or 0x40: padding.
+    Pad,
+    /// Type 0x01: the setting applies to every device ID.
+    All { dte_setting: DTESetting },
+    /// Type 0x02
+    Select {
+        device_id: u16,
+        dte_setting: DTESetting,
+    },
+    /// Type 0x03
+    RangeStart {
+        device_id: u16,
+        dte_setting: DTESetting,
+    },
+    /// Type 0x04
+    RangeEnd { device_id: u16 },
+    /// Type 0x42: the device's requests reach the IOMMU as `source_id`.
+    AliasSelect {
+        device_id: u16,
+        dte_setting: DTESetting,
+        source_id: u16,
+    },
+    /// Type 0x43: every device of the range reaches the IOMMU as `source_id`.
+    AliasRangeStart {
+        device_id: u16,
+        dte_setting: DTESetting,
+        source_id: u16,
+    },
+    /// Type 0x46
+    ExtendedSelect {
+        device_id: u16,
+        dte_setting: DTESetting,
+        extended_data: u32,
+    },
+    /// Type 0x47
+    ExtendedRangeStart {
+        device_id: u16,
+        dte_setting: DTESetting,
+        extended_data: u32,
+    },
+    /// Type 0x48: an I/O APIC or HPET, by its ID (`handle`), and the device ID its interrupts come from.
+    Special {
+        handle: u8,
+        device_id: u16,
+        variety: SpecialDeviceVariety,
+        dte_setting: DTESetting,
+    },
+    /// Type 0xF0
+    ACPIHID(&'a ACPIHIDDeviceEntry),
+    /// A reserved fixed-size entry type.
+    Unknown(u8),
+}
+
+/// Iterator over the device entries of an IVHD.
+pub struct IVHDDeviceEntryIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _ivhd: core::marker::PhantomData<&'a IVHD>,
+}
+impl<'a> Iterator for IVHDDeviceEntryIter<'a> {
+    type Item = IVHDDeviceEntry<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the slice stops at the end of the IVHD.
+        let bytes = unsafe { core::slice::from_raw_parts(self.next, self.remaining.min(8)) };
+        let entry_type = bytes[0];
+        let length = match entry_type {
+            0x00..=0x3F => 4,
+            0x40..=0x7F => 8,
+            // SAFETY: the _UID length lies within the IVHD, checked by the guard.
+            0xF0 if self.remaining >= 22 => 22 + unsafe { *self.next.add(21) } as usize,
+            // Other variable-length entries don't say how long they are; nothing after them can be found.
+            _ => usize::MAX,
+        };
+        if length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let device_id = u16::from_le_bytes([bytes[1], bytes[2]]);
+        let dte_setting = DTESetting(bytes[3]);
+        let entry = match entry_type {
+            0x00 | 0x40 => IVHDDeviceEntry::Pad,
+            0x01 => IVHDDeviceEntry::All { dte_setting },
+            0x02 => IVHDDeviceEntry::Select {
+                device_id,
+                dte_setting,
+            },
+            0x03 => IVHDDeviceEntry::RangeStart {
+                device_id,
+                dte_setting,
+            },
+            0x04 => IVHDDeviceEntry::RangeEnd { device_id },
+            0x42 => IVHDDeviceEntry::AliasSelect {
+                device_id,
+                dte_setting,
+                source_id: u16::from_le_bytes([bytes[5], bytes[6]]),
+            },
+            0x43 => IVHDDeviceEntry::AliasRangeStart {
+                device_id,
+                dte_setting,
+                source_id: u16::from_le_bytes([bytes[5], bytes[6]]),
+            },
+            0x46 => IVHDDeviceEntry::ExtendedSelect {
+                device_id,
+                dte_setting,
+                extended_data: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            },
+            0x47 => IVHDDeviceEntry::ExtendedRangeStart {
+                device_id,
+                dte_setting,
+                extended_data: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            },
+            0x48 => IVHDDeviceEntry::Special {
+                handle: bytes[4],
+                device_id: u16::from_le_bytes([bytes[5], bytes[6]]),
+                variety: SpecialDeviceVariety::from_u8(bytes[7]),
+                dte_setting,
+            },
+            // SAFETY: the entry (at least 22 bytes) lies within the IVHD, checked above.
+            0xF0 => IVHDDeviceEntry::ACPIHID(unsafe { &*(self.next as *const _) }),
+            other => IVHDDeviceEntry::Unknown(other),
+        };
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        Some(entry)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Device Settings
+///
+/// What the IVHD device entries say about a run of device IDs (a single device is a run of one).
+pub struct DeviceSettings {
+    pub first_device_id: u16,
+    /// The last device ID of the run (inclusive).
+    pub last_device_id: u16,
+    pub dte_setting: DTESetting,
+    /// The device ID the IOMMU sees for these devices' requests, if it isn't their own.
+    pub alias: Option<u16>,
+    /// The extended DTE setting of an extended entry.
+    pub extended_data: Option<u32>,
+}
+impl DeviceSettings {
+    pub const fn contains(&self, device_id: u16) -> bool {
+        device_id >= self.first_device_id && device_id <= self.last_device_id
+    }
+    /// The device ID the IOMMU sees for `device_id`'s requests.
+    pub const fn requester_id(&self, device_id: u16) -> u16 {
+        match self.alias {
+            Some(alias) => alias,
+            None => device_id,
+        }
+    }
+}
+
+/// Iterator that pairs up the range entries of an IVHD, giving the settings of every run of device IDs it describes.
+///
+/// Later runs override earlier ones for the device IDs they share.
+pub struct DeviceSettingsIter<'a> {
+    entries: IVHDDeviceEntryIter<'a>,
+}
+impl Iterator for DeviceSettingsIter<'_> {
+    type Item = DeviceSettings;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (first, dte_setting, alias, extended_data) = match self.entries.next()? {
+                IVHDDeviceEntry::All { dte_setting } => {
+                    return Some(DeviceSettings {
+                        first_device_id: 0,
+                        last_device_id: u16::MAX,
+                        dte_setting,
+                        alias: None,
+                        extended_data: None,
+                    });
+                }
+                IVHDDeviceEntry::Select {
+                    device_id,
+                    dte_setting,
+                } => return Some(single(device_id, dte_setting, None, None)),
+                IVHDDeviceEntry::AliasSelect {
+                    device_id,
+                    dte_setting,
+                    source_id,
+                } => return Some(single(device_id, dte_setting, Some(source_id), None)),
+                IVHDDeviceEntry::ExtendedSelect {
+                    device_id,
+                    dte_setting,
+                    extended_data,
+                } => return Some(single(device_id, dte_setting, None, Some(extended_data))),
+                IVHDDeviceEntry::RangeStart {
+                    device_id,
+                    dte_setting,
+                } => (device_id, dte_setting, None, None),
+                IVHDDeviceEntry::AliasRangeStart {
+                    device_id,
+                    dte_setting,
+                    source_id,
+                } => (device_id, dte_setting, Some(source_id), None),
+                IVHDDeviceEntry::ExtendedRangeStart {
+                    device_id,
+                    dte_setting,
+                    extended_data,
+                } => (device_id, dte_setting, None, Some(extended_data)),
+                _ => continue,
+            };
+            // A range start without its end (the IVHD ran out) describes nothing.
+            let last = self.entries.find_map(|entry| match entry {
+                IVHDDeviceEntry::RangeEnd { device_id } => Some(device_id),
+                _ => None,
+            })?;
+            if last < first {
+                continue;
+            }
+            return Some(DeviceSettings {
+                first_device_id: first,
+                last_device_id: last,
+                dte_setting,
+                alias,
+                extended_data,
+            });
+        }
+    }
+}
+const fn single(
+    device_id: u16,
+    dte_setting: DTESetting,
+    alias: Option<u16>,
+    extended_data: Option<u32>,
+) -> DeviceSettings {
+    DeviceSettings {
+        first_device_id: device_id,
+        last_device_id: device_id,
+        dte_setting,
+        alias,
+        extended_data,
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct IVHDFlags(u8);
+impl IVHDFlags {
+    pub const fn ht_tun_en(&self) -> bool {
+        self.0 & 0b00000001 != 0
+    }
+    pub const fn pass_pw(&self) -> bool {
+        self.0 & 0b00000010 != 0
+    }
+    pub const fn res_pass_pw(&self) -> bool {
+        self.0 & 0b00000100 != 0
+    }
+    pub const fn isoc(&self) -> bool {
+        self.0 & 0b00001000 != 0
+    }
+    /// If set, the IOMMU supports an IOTLB (type 0x10 only).
+    pub const fn iotlb_sup(&self) -> bool {
+        self.0 & 0b00010000 != 0
+    }
+    pub const fn coherent(&self) -> bool {
+        self.0 & 0b00100000 != 0
+    }
+    /// Type 0x10 only.
+    pub const fn pref_sup(&self) -> bool {
+        self.0 & 0b01000000 != 0
+    }
+    /// Type 0x10 only.
+    pub const fn ppr_sup(&self) -> bool {
+        self.0 & 0b10000000 != 0
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## I/O Virtualization Hardware Definition (IVHD)
+///
+/// One IOMMU, and the devices behind it.
+///
+/// Comes in three types that can all describe the same IOMMU: 0x10 (legacy), 0x11 (with the EFR image) and 0x40 (0x11 plus ACPI HID device entries).
+/// OSPM uses the highest type it supports (see `IVRS::preferred_ivhd_type`) and ignores the others.
+pub struct IVHD {
+    /// 0x10, 0x11 or 0x40
+    pub r#type: u8,
+    pub flags: IVHDFlags,
+    /// Length, in bytes, of the whole structure (device entries included).
+    pub length: u16,
+    /// The device ID of the IOMMU itself.
+    pub device_id: u16,
+    /// Offset of the IOMMU capability block in its configuration space.
+    pub capability_offset: u16,
+    pub iommu_base_address: u64,
+    pub pci_segment_group: u16,
+    /// - **Bits [[4:0]]** - MSInum: the MSI vector the IOMMU's event log interrupt uses
+    /// - **Bits [[12:8]]** - UnitID: the HyperTransport unit ID of the IOMMU
+    pub iommu_info: u16,
+    /// IOMMU Feature Reporting (type 0x10) or IOMMU Attributes (types 0x11 and 0x40).
+    pub iommu_feature_info: u32,
+}
+impl IVHD {
+    const fn header_length(&self) -> usize {
+        if self.r#type == 0x10 { 24 } else { 40 }
+    }
+    /// The image of the IOMMU's Extended Feature Register (types 0x11 and 0x40 only).
+    pub const fn efr_register_image(&self) -> Option<u64> {
+        if self.r#type == 0x10 || self.length < 40 {
+            return None;
+        }
+        // SAFETY: the register image lies within the structure's length (at least 40, checked above), which `IVRSStructureIter` checked against the table.
+        Some(unsafe { ((self as *const _ as *const u8).add(24) as *const u64).read_unaligned() })
+    }
+    /// The image of the IOMMU's Extended Feature 2 Register (types 0x11 and 0x40 only).
+    pub const fn efr2_register_image(&self) -> Option<u64> {
+        if self.r#type == 0x10 || self.length < 40 {
+            return None;
+        }
+        // SAFETY: the register image lies within the structure's length (at least 40, checked above), which `IVRSStructureIter` checked against the table.
+        Some(unsafe { ((self as *const _ as *const u8).add(32) as *const u64).read_unaligned() })
+    }
+    pub const fn device_entries(&self) -> IVHDDeviceEntryIter<'_> {
+        IVHDDeviceEntryIter {
+            next: (self as *const _ as *const u8).wrapping_add(self.header_length()),
+            remaining: (self.length as usize).saturating_sub(self.header_length()),
+            _ivhd: core::marker::PhantomData,
+        }
+    }
+    /// The settings of every run of device IDs behind this IOMMU, ranges paired up.
+    pub const fn device_ranges(&self) -> DeviceSettingsIter<'_> {
+        DeviceSettingsIter {
+            entries: self.device_entries(),
+        }
+    }
+    /// The settings of `device_id`, if it's behind this IOMMU.
+    pub fn device_settings(&self, device_id: u16) -> Option<DeviceSettings> {
+        self.device_ranges()
+            .filter(|settings| settings.contains(device_id))
+            .last()
+    }
+    /// The I/O APICs and HPETs this IOMMU remaps the interrupts of: (variety, handle, device ID).
+    pub fn special_devices(&self) -> impl Iterator<Item = (SpecialDeviceVariety, u8, u16)> + '_ {
+        self.device_entries().filter_map(|entry| match entry {
+            IVHDDeviceEntry::Special {
+                handle,
+                device_id,
+                variety,
+                ..
+            } => Some((variety, handle, device_id)),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct IVMDFlags(u8);
+impl IVMDFlags {
+    /// If set, the range has to be identity mapped (unity mapped) for the devices.
+    pub const fn unity(&self) -> bool {
+        self.0 & 0b0001 != 0
+    }
+    /// If set, the devices can read the range.
+    pub const fn read(&self) -> bool {
+        self.0 & 0b0010 != 0
+    }
+    /// If set, the devices can write the range.
+    pub const fn write(&self) -> bool {
+        self.0 & 0b0100 != 0
+    }
+    /// If set, the range is an exclusion range: the IOMMU lets the devices' accesses to it through untranslated.
+    pub const fn exclusion_range(&self) -> bool {
+        self.0 & 0b1000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## I/O Virtualization Memory Definition (IVMD)
+///
+/// Memory that some devices need to keep reaching through the IOMMU (like DMAR's RMRR).
+pub struct IVMD {
+    /// - **0x20** - Every device
+    /// - **0x21** - The device `device_id`
+    /// - **0x22** - The devices `device_id` to `auxiliary_data`
+    pub r#type: u8,
+    pub flags: IVMDFlags,
+    /// 32
+    pub length: u16,
+    pub device_id: u16,
+    /// The last device ID of the range (type 0x22).
+    pub auxiliary_data: u16,
+    reserved: u64,
+    pub ivmd_start_address: u64,
+    pub ivmd_memory_block_length: u64,
+}
+impl IVMD {
+    /// Returns true if this memory definition applies to `device_id`.
+    pub const fn applies_to(&self, device_id: u16) -> bool {
+        match self.r#type {
+            0x20 => true,
+            0x21 => self.device_id == device_id,
+            0x22 => device_id >= self.device_id && device_id <= self.auxiliary_data,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum IVRSStructure<'a> {
+    IVHD(&'a IVHD),
+    IVMD(&'a IVMD),
+    /// A structure type this library doesn't know about, by its type.
+    Unknown(u8),
+}
+
+/// Iterator over the IVHDs and IVMDs of an IVRS.
+pub struct IVRSStructureIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _ivrs: core::marker::PhantomData<&'a IVRS>,
+}
+impl<'a> Iterator for IVRSStructureIter<'a> {
+    type Item = IVRSStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the type and length lie within the table, checked above.
+        let (structure_type, length) = unsafe {
+            (
+                *self.next,
+                (self.next.add(2) as *const u16).read_unaligned() as usize,
+            )
+        };
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 4 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        // SAFETY: every structure is checked against its length before the cast.
+        Some(match structure_type {
+            0x10 | 0x11 | 0x40 if length >= core::mem::size_of::<IVHD>() => {
+                IVRSStructure::IVHD(unsafe { &*(ptr as *const _) })
+            }
+            0x20..=0x22 if length >= core::mem::size_of::<IVMD>() => {
+                IVRSStructure::IVMD(unsafe { &*(ptr as *const _) })
+            }
+            other => IVRSStructure::Unknown(other),
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## I/O Virtualization Reporting Structure (IVRS)
+///
+/// Defined by the AMD I/O Virtualization Technology (IOMMU) specification, this table describes the AMD-Vi IOMMUs of the platform,
+/// which devices are behind each of them (and with which device table settings), and the memory some devices need to keep reaching.
+pub struct IVRS {
+    /// - **Signature** - "IVRS"
+    /// - **Revision** - 2 (0x02 for tables with type 0x11/0x40 IVHDs)
+    pub header: SDTHeader,
+    pub iv_info: IVInfo,
+    reserved: u64,
+}
+impl IVRS {
+    pub const fn structures(&self) -> IVRSStructureIter<'_> {
+        IVRSStructureIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 12),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 12),
+            _ivrs: core::marker::PhantomData,
+        }
+    }
+    /// The highest IVHD type in the table, which is the one to use.
+    pub fn preferred_ivhd_type(&self) -> Option<u8> {
+        self.structures()
+            .filter_map(|structure| match structure {
+                IVRSStructure::IVHD(ivhd) => Some(ivhd.r#type),
+                _ => None,
+            })
+            .max()
+    }
+    /// The IVHDs of the preferred type: one per IOMMU.
+    pub fn hardware_definitions(&self) -> impl Iterator<Item = &IVHD> {
+        let preferred = self.preferred_ivhd_type();
+        self.structures()
+            .filter_map(move |structure| match structure {
+                IVRSStructure::IVHD(ivhd) if Some(ivhd.r#type) == preferred => Some(ivhd),
+                _ => None,
+            })
+    }
+    pub fn memory_definitions(&self) -> impl Iterator<Item = &IVMD> {
+        self.structures().filter_map(|structure| match structure {
+            IVRSStructure::IVMD(ivmd) => Some(ivmd),
+            _ => None,
+        })
+    }
+    /// The IOMMU `device_id` of segment `segment` is behind, and its settings there.
+    pub fn device_settings(&self, segment: u16, device_id: u16) -> Option<(&IVHD, DeviceSettings)> {
+        self.hardware_definitions()
+            .filter(|ivhd| ivhd.pci_segment_group == segment)
+            .find_map(|ivhd| Some((ivhd, ivhd.device_settings(device_id)?)))
+    }
+}
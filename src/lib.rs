@@ -5,26 +5,51 @@
 
 #![no_std]
 
+pub mod apei;
+pub mod bert;
 pub mod bgrt;
+pub mod cedt;
 pub mod cpep;
+pub mod cper;
+pub mod dbg2;
+pub mod device;
+pub mod dmar;
 pub mod dsdt;
+pub mod ec;
 pub mod ecdt;
+pub mod einj;
+pub mod erst;
+pub mod event;
 pub mod facs;
 pub mod fadt;
-//pub mod hpet; // JJ here, this is a reserved signature from acpi.
+pub mod fpdt;
+pub mod gtdt;
+pub mod hest;
+pub mod hmat;
+pub mod hpet;
+pub mod iort;
+pub mod ivrs;
 pub mod madt;
-//pub mod mcfg; // JJ here, this is a reserved signature from acpi.
+pub mod mcfg;
+pub mod mpst;
 pub mod msct;
+pub mod nfit;
+pub mod osc;
+pub mod osi;
 pub mod pcct;
+pub mod pptt;
 pub mod psdt;
+pub mod ras2;
 pub mod rasf;
+pub mod rhct;
 pub mod rsdp;
 pub mod rsdt;
 pub mod sbst;
 pub mod slit;
-//pub mod spcr; // JJ here, this is a reserved signature from acpi.
+pub mod spcr;
 pub mod srat;
 pub mod ssdt;
+pub mod tpm2;
 pub mod xsdt;
 
 #[derive(Clone, Copy)]
@@ -63,6 +88,101 @@ pub struct GenericAddressStructure {
     pub address: u64,
 }
 
+/// ## Generic Address Structure Access
+///
+/// This library doesn't touch any hardware on its own.  Whatever needs to read or write a register described by a Generic Address Structure
+/// (the EC driver, for example) goes through this trait, and the OS implements it with whatever port I/O, MMIO, PCI config, etc. it has.
+pub trait GenericAddressAccess {
+    /// Reads the register described by `gas`, returning the value right-aligned.
+    fn read(&mut self, gas: &GenericAddressStructure) -> u64;
+    /// Writes `value` (right-aligned) to the register described by `gas`.
+    fn write(&mut self, gas: &GenericAddressStructure, value: u64);
+}
+
+/// ## Physical Memory Mapping
+///
+/// Some tables only hand out the physical address of more data (the FPDT's performance tables, for example).
+/// Whatever needs to follow such a pointer goes through this trait, and the OS implements it with its page tables (or an identity map).
+///
+/// # Safety
+///
+/// The tables behind the mapping are read as references that live as long as the borrow of the mapper (`&'m self` gives `&'m` tables).
+/// So when `map` returns `Some(pointer)`, `pointer` must be valid for reads of `length` bytes, and stay that way (mapped, and not written to)
+/// for as long as the mapper is borrowed.
+pub unsafe trait PhysicalMapper {
+    /// Makes `length` bytes of physical memory at `address` readable, returning where they are (or `None` if they can't be mapped).
+    fn map(&self, address: u64, length: usize) -> Option<*const u8>;
+}
+
+/// ## AML Method Evaluation
+///
+/// There is no AML interpreter in this library.  Whatever needs a control method run (GPE _Lxx/_Exx methods, _STA, _HID...) goes through this trait,
+/// and the interpreter implements it once it's there.
+pub trait AMLEvaluator {
+    /// What the interpreter returns when a method fails.
+    type Error;
+    /// Evaluates the object at the absolute namespace path `path` (e.g. "\\_GPE._L1A") with the integer arguments `args`.
+    ///
+    /// Returns `Ok(None)` if there's no such object.  Methods that return nothing, or something other than an integer, give `Ok(Some(0))`.
+    fn evaluate(&mut self, path: &[u8], args: &[u64]) -> Result<Option<u64>, Self::Error>;
+    /// Evaluates the object at `path` with the arguments `args`, copying the buffer or string it returns into `out` (strings without their null terminator).
+    ///
+    /// Returns `Ok(None)` if there's no such object or it returned something else, and otherwise the length of the result,
+    /// which can be more than `out.len()` (the rest is cut off).
+    ///
+    /// The default treats every object as missing, for interpreters that only hand out integers; methods such as _OSC need this implemented.
+    fn evaluate_buffer(
+        &mut self,
+        path: &[u8],
+        args: &[AMLArgument<'_>],
+        out: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        let _ = (path, args, out);
+        Ok(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// An argument passed to a control method by `AMLEvaluator::evaluate_buffer`.
+pub enum AMLArgument<'a> {
+    Integer(u64),
+    Buffer(&'a [u8]),
+}
+
+/// Converts a UUID string ("aabbccdd-eeff-gghh-iijj-kkllmmnnoopp") into its 16-byte buffer form, exactly like the ASL ToUUID macro.
+///
+/// The first three groups are stored little-endian and the last two as-is, which is also the layout of an EFI_GUID in memory.
+///
+/// Panics (at compile time, when used in a const) if the string isn't a well-formed UUID.
+pub const fn uuid(text: &str) -> [u8; 16] {
+    // Offsets of each output byte's two hex digits within the string.
+    const ORDER: [usize; 16] = [6, 4, 2, 0, 11, 9, 16, 14, 19, 21, 24, 26, 28, 30, 32, 34];
+    const fn hex(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("uuid: invalid hex digit"),
+        }
+    }
+    let text = text.as_bytes();
+    if text.len() != 36
+        || text[8] != b'-'
+        || text[13] != b'-'
+        || text[18] != b'-'
+        || text[23] != b'-'
+    {
+        panic!("uuid: expected the aabbccdd-eeff-gghh-iijj-kkllmmnnoopp format");
+    }
+    let mut out = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        out[i] = hex(text[ORDER[i]]) << 4 | hex(text[ORDER[i] + 1]);
+        i += 1;
+    }
+    out
+}
+
 pub const SDT_HEADER_SIZE: usize = core::mem::size_of::<SDTHeader>();
 
 #[derive(Copy, Clone)]
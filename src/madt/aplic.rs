@@ -0,0 +1,40 @@
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Advanced Platform Level Interrupt Controller (APLIC) Structure
+///
+/// An APLIC handles wired interrupts, delivering them to harts directly (through its IDCs) or as MSIs to the harts' IMSICs.
+pub struct AdvancedPlatformLevelInterruptController {
+    /// 26 - Advanced Platform Level Interrupt Controller Structure
+    pub r#type: u8,
+    /// 36
+    pub length: u8,
+    /// - **0x00** - Invalid
+    /// - **0x01** - APLIC v1
+    ///
+    /// Other values are reserved.
+    pub version: u8,
+    /// Unique ID of the APLIC, as in the RINTC structures' external interrupt controller ID.
+    pub aplic_id: u8,
+    /// There aren't any flags yet; all the bits are reserved.
+    pub flags: u32,
+    /// Unique hardware ID (like an ACPI _HID) of the APLIC.
+    pub hardware_id: [u8; 8],
+    /// The number of interrupt delivery controls (IDCs) of the APLIC. 0 means it only delivers MSIs.
+    pub number_of_idcs: u16,
+    /// The number of wired interrupt sources of the APLIC.
+    pub total_external_interrupt_sources_supported: u16,
+    /// The global system interrupt number where the APLIC's interrupt inputs start.
+    pub global_system_interrupt_base: u32,
+    /// The physical base address of the APLIC's registers.
+    pub aplic_address: u64,
+    /// Size, in bytes, of the APLIC's register space.
+    pub aplic_size: u32,
+}
+impl AdvancedPlatformLevelInterruptController {
+    /// Returns true if the global system interrupt `gsi` is an input of this APLIC.
+    pub const fn handles(&self, gsi: u32) -> bool {
+        gsi >= self.global_system_interrupt_base
+            && gsi - self.global_system_interrupt_base
+                < self.total_external_interrupt_sources_supported as u32
+    }
+}
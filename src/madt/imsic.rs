@@ -0,0 +1,32 @@
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Incoming MSI Controller (IMSIC) Structure
+///
+/// There's one IMSIC structure in the MADT for the whole system: it describes what every hart's IMSIC looks like.
+/// Where each hart's interrupt files are comes from the hart's RINTC structure.
+pub struct IncomingMSIController {
+    /// 25 - Incoming MSI Controller Structure
+    pub r#type: u8,
+    /// 16
+    pub length: u8,
+    /// - **0x00** - Invalid
+    /// - **0x01** - IMSIC v1
+    ///
+    /// Other values are reserved.
+    pub version: u8,
+    reserved: u8,
+    /// There aren't any flags yet; all the bits are reserved.
+    pub flags: u32,
+    /// The number of interrupt identities supported by the supervisor mode interrupt file of each hart (63 to 2047).
+    pub number_of_supervisor_mode_interrupt_identities: u16,
+    /// The number of interrupt identities supported by the guest mode interrupt files of each hart (0 to 2047).
+    pub number_of_guest_mode_interrupt_identities: u16,
+    /// The number of guest index bits in MSI target addresses (0 to 7).
+    pub guest_index_bits: u8,
+    /// The number of hart index bits in MSI target addresses (0 to 15).
+    pub hart_index_bits: u8,
+    /// The number of group index bits in MSI target addresses (0 to 7).
+    pub group_index_bits: u8,
+    /// The LSB of the group index bits in MSI target addresses (0 to 55).
+    pub group_index_shift: u8,
+}
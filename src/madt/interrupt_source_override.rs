@@ -39,11 +39,11 @@ impl MPSINTIFlags {
     }
     /// Trigger mode of the APIC I/O Input signals.
     pub const fn trigger_mode(&self) -> InterruptSourceOverrideTriggerMode {
-        match self.0 & 0b1100 {
+        match (self.0 & 0b1100) >> 2 {
             0b00 => InterruptSourceOverrideTriggerMode::Conform,
             0b01 => InterruptSourceOverrideTriggerMode::EdgeTriggered,
             0b10 => panic!(
-                "InterruptSourceOverride's Polarity 2-bit flag set to 0b10, which is a reserved value."
+                "InterruptSourceOverride's Trigger Mode 2-bit flag set to 0b10, which is a reserved value."
             ),
             0b11 => InterruptSourceOverrideTriggerMode::LevelTriggered,
             _ => unreachable!(),
@@ -3,6 +3,7 @@ pub mod ioapic;
 pub mod local_apic_nmi;
 pub mod nmi_source;
 pub mod proc_local_apic;
+pub mod aplic;
 pub mod bridge_io_pic;
 pub mod core_pic;
 pub mod extend_io_pic;
@@ -12,6 +13,7 @@ pub mod gic_msi_frame;
 pub mod gic_redistributor;
 pub mod giccpu_interface;
 pub mod hyper_transport_pic;
+pub mod imsic;
 pub mod iosapic;
 pub mod legacy_io_pic;
 pub mod local_api_address_override;
@@ -21,7 +23,9 @@ pub mod lpc_pic;
 pub mod msi_pic;
 pub mod multiprocessor_wakeup;
 pub mod platform_interrupt_source;
+pub mod plic;
 pub mod processor_local_x2apic;
+pub mod rintc;
 
 use crate::SDTHeader;
 
@@ -68,6 +72,7 @@ impl MADTFlags {
 /// - **Intel processor-based systems** - The Intel Advanced Programmable Interrupt Controller (APIC) and Intel Streamlined Advanced Programmable Interrupt.
 /// - **ARM processor-based systems** - The Generic Interrupt Controller (GIC).
 /// - **LoongArch processor-based systems** - the LoongArch Programmable Interrupt Controller (LPIC).
+/// - **RISC-V processor-based systems** - The RISC-V Advanced Interrupt Architecture (AIA) or the Platform Level Interrupt Controller (PLIC).
 ///
 /// The choice of interrupt model(s) to support is up to the platform designer.
 /// The interrupt model cannot be dynamically changed by system firmware; OSPM will choose which model to use and install support for that model at the time of installation.
@@ -0,0 +1,40 @@
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Platform Level Interrupt Controller (PLIC) Structure
+///
+/// A PLIC handles wired interrupts, routing them to hart contexts.
+pub struct PlatformLevelInterruptController {
+    /// 27 - Platform Level Interrupt Controller Structure
+    pub r#type: u8,
+    /// 36
+    pub length: u8,
+    /// - **0x00** - Invalid
+    /// - **0x01** - PLIC v1
+    ///
+    /// Other values are reserved.
+    pub version: u8,
+    /// Unique ID of the PLIC, as in the RINTC structures' external interrupt controller ID.
+    pub plic_id: u8,
+    /// Unique hardware ID (like an ACPI _HID) of the PLIC.
+    pub hardware_id: [u8; 8],
+    /// The number of wired interrupt sources of the PLIC.
+    pub total_external_interrupt_sources_supported: u16,
+    /// The highest priority the PLIC supports.
+    pub max_priority: u16,
+    /// There aren't any flags yet; all the bits are reserved.
+    pub flags: u32,
+    /// Size, in bytes, of the PLIC's register space.
+    pub plic_size: u32,
+    /// The physical base address of the PLIC's registers.
+    pub plic_address: u64,
+    /// The global system interrupt number where the PLIC's interrupt inputs start.
+    pub global_system_interrupt_base: u32,
+}
+impl PlatformLevelInterruptController {
+    /// Returns true if the global system interrupt `gsi` is an input of this PLIC.
+    pub const fn handles(&self, gsi: u32) -> bool {
+        gsi >= self.global_system_interrupt_base
+            && gsi - self.global_system_interrupt_base
+                < self.total_external_interrupt_sources_supported as u32
+    }
+}
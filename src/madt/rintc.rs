@@ -0,0 +1,65 @@
+#[derive(Copy, Clone)]
+/// ## RINTC Flags
+pub struct RINTCFlags(u32);
+impl RINTCFlags {
+    /// If this bit is set, the hart is ready for use. If this bit is clear and the Online Capable bit is set,
+    /// system hardware supports enabling this hart during OS runtime.<br>
+    /// If this bit is clear and the Online Capable bit is also clear, this hart is unusable, and OSPM shall ignore the contents of the RINTC Structure.
+    pub const fn enabled(&self) -> bool {
+        self.0 & 0b01 != 0
+    }
+    /// The information conveyed by this bit depends on the value of the Enabled bit.
+    /// If the Enabled bit is set, this bit is reserved and must be zero.
+    /// Otherwise, if this bit is set, system hardware supports enabling this hart during OS runtime.
+    pub const fn online_capable(&self) -> bool {
+        self.0 & 0b10 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RISC-V Hart Local Interrupt Controller (RINTC) Structure
+///
+/// Each hart in a RISC-V system has a RINTC record in the MADT, and a processor device object in the DSDT.
+/// It also says which external interrupt controller (APLIC or PLIC context, or IMSIC interrupt file) delivers interrupts to the hart.
+pub struct RISCVHartLocalInterruptController {
+    /// 24 - RISC-V Hart Local Interrupt Controller Structure
+    pub r#type: u8,
+    /// 36
+    pub length: u8,
+    /// - **0x00** - Invalid
+    /// - **0x01** - RINTC v1
+    ///
+    /// Other values are reserved.
+    pub version: u8,
+    reserved: u8,
+    /// RINTC flags.
+    pub flags: RINTCFlags,
+    /// The ID of the hart (the value of its mhartid CSR).
+    pub hart_id: u64,
+    /// The OS associates this RINTC Structure with a processor device object in the namespace
+    /// when the _UID child object of the processor device evaluates to a numeric value that matches the numeric value in this field.
+    pub acpi_processor_uid: u32,
+    /// For harts whose external interrupts come from an APLIC or PLIC (without an IMSIC):
+    ///
+    /// - **Bits 31:24** - The APLIC or PLIC ID
+    /// - **Bits 15:0** - The interrupt delivery control (IDC) index of the hart on the APLIC, or its context index on the PLIC
+    ///
+    /// The rest of the bits are reserved.
+    pub external_interrupt_controller_id: u32,
+    /// The physical base address of the hart's supervisor mode IMSIC interrupt file (0 if there's no IMSIC).
+    pub imsic_base_address: u64,
+    /// Size, in bytes, of the hart's IMSIC interrupt files (the supervisor one and the guest ones).
+    pub imsic_size: u32,
+}
+impl RISCVHartLocalInterruptController {
+    /// The APLIC or PLIC ID from `external_interrupt_controller_id`.
+    pub const fn external_interrupt_controller(&self) -> u8 {
+        (self.external_interrupt_controller_id >> 24) as u8
+    }
+    /// The IDC index (APLIC) or context index (PLIC) from `external_interrupt_controller_id`.
+    pub const fn external_interrupt_context(&self) -> u16 {
+        self.external_interrupt_controller_id as u16
+    }
+}
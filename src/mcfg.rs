@@ -0,0 +1,154 @@
+use crate::{GenericAddressAccess, GenericAddressStructure, SDT_HEADER_SIZE, SDTHeader};
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Mapped Enhanced Configuration Space Base Address Allocation Structure
+///
+/// Each structure describes the ECAM region of one PCI segment group over a range of buses.
+pub struct MCFGAllocation {
+    /// Base address of the enhanced configuration mechanism.
+    ///
+    /// This is where bus 0 of the segment would be, even if `start_bus` isn't 0.
+    pub base_address: u64,
+    /// PCI Segment Group Number.
+    pub pci_segment_group: u16,
+    /// Start PCI bus number decoded by this host bridge.
+    pub start_bus: u8,
+    /// End PCI bus number decoded by this host bridge.
+    pub end_bus: u8,
+    reserved: u32,
+}
+impl MCFGAllocation {
+    /// Returns true if `bus` is decoded by this allocation.
+    pub const fn contains_bus(&self, bus: u8) -> bool {
+        bus >= self.start_bus && bus <= self.end_bus
+    }
+    /// The physical address of `offset` in the configuration space of bus/device/function.
+    ///
+    /// Returns `None` if the bus isn't in `start_bus..=end_bus`, the device is past 31, the function is past 7, or the offset is past 4095.
+    pub const fn config_address(
+        &self,
+        bus: u8,
+        device: u8,
+        function: u8,
+        offset: u16,
+    ) -> Option<u64> {
+        if !self.contains_bus(bus) || device > 31 || function > 7 || offset > 0xFFF {
+            return None;
+        }
+        Some(
+            self.base_address
+                + ((bus as u64) << 20
+                    | (device as u64) << 15
+                    | (function as u64) << 12
+                    | offset as u64),
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## PCI Express Memory-mapped Configuration Space base address Description Table (MCFG)
+///
+/// Defined by the PCI Firmware Specification, this table tells OSPM where the Enhanced Configuration Access Mechanism (ECAM) regions are, so PCI Express configuration space can be reached through memory instead of the legacy 0xCF8/0xCFC ports.
+pub struct MCFG {
+    /// - **Signature** - "MCFG"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    reserved: u64,
+    /// A list of Memory Mapped Enhanced Configuration Space Base Address Allocation structures.
+    pub allocations: [MCFGAllocation; 0],
+}
+impl MCFG {
+    pub const fn allocations(&self) -> &[MCFGAllocation] {
+        let count = (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 8)
+            / core::mem::size_of::<MCFGAllocation>();
+        // SAFETY: the allocations lie within the table's length, which bounds the count.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(SDT_HEADER_SIZE + 8) as *const MCFGAllocation,
+                count,
+            )
+        }
+    }
+    /// Finds the allocation decoding `bus` of segment group `segment`.
+    pub fn allocation(&self, segment: u16, bus: u8) -> Option<&MCFGAllocation> {
+        self.allocations()
+            .iter()
+            .find(|a| a.pci_segment_group == segment && a.contains_bus(bus))
+    }
+    /// The physical ECAM address of `offset` in the configuration space of segment/bus/device/function.
+    pub fn config_address(
+        &self,
+        segment: u16,
+        bus: u8,
+        device: u8,
+        function: u8,
+        offset: u16,
+    ) -> Option<u64> {
+        self.allocation(segment, bus)?
+            .config_address(bus, device, function, offset)
+    }
+    /// Translates a PCI Configuration space Generic Address Structure (like the FADT's `reset_reg`) into its ECAM address.
+    ///
+    /// For that address space, the GAS address is laid out as:
+    /// - **Bits [[47:32]]** - Device
+    /// - **Bits [[31:16]]** - Function
+    /// - **Bits [[15:0]]** - Offset
+    ///
+    /// and always refers to segment 0, bus 0.
+    pub fn gas_address(&self, gas: &GenericAddressStructure) -> Option<u64> {
+        let address = gas.address;
+        if gas.address_space_id != 0x02 || address >> 48 != 0 {
+            return None;
+        }
+        let device = (address >> 32) as u16;
+        let function = (address >> 16) as u16;
+        if device > 31 || function > 7 {
+            return None;
+        }
+        self.config_address(0, 0, device as u8, function as u8, address as u16)
+    }
+}
+
+/// ## PCI Configuration Space over ECAM
+///
+/// Wraps another `GenericAddressAccess` so PCI Configuration space (0x02) registers are reached through the MCFG's ECAM regions, as System Memory accesses of the same width.
+/// Every other address space is passed through untouched.
+///
+/// Like a PCI master abort, a configuration access that no allocation decodes reads back all ones and drops writes.
+pub struct ECAMAccess<'a, A: GenericAddressAccess> {
+    pub mcfg: &'a MCFG,
+    pub inner: A,
+}
+impl<'a, A: GenericAddressAccess> ECAMAccess<'a, A> {
+    pub const fn new(mcfg: &'a MCFG, inner: A) -> Self {
+        Self { mcfg, inner }
+    }
+    fn translate(&self, gas: &GenericAddressStructure) -> Option<GenericAddressStructure> {
+        Some(GenericAddressStructure {
+            address_space_id: 0x00,
+            address: self.mcfg.gas_address(gas)?,
+            ..*gas
+        })
+    }
+}
+impl<A: GenericAddressAccess> GenericAddressAccess for ECAMAccess<'_, A> {
+    fn read(&mut self, gas: &GenericAddressStructure) -> u64 {
+        if gas.address_space_id != 0x02 {
+            return self.inner.read(gas);
+        }
+        match self.translate(gas) {
+            Some(memory) => self.inner.read(&memory),
+            None => u64::MAX,
+        }
+    }
+    fn write(&mut self, gas: &GenericAddressStructure, value: u64) {
+        if gas.address_space_id != 0x02 {
+            return self.inner.write(gas, value);
+        }
+        if let Some(memory) = self.translate(gas) {
+            self.inner.write(&memory, value)
+        }
+    }
+}
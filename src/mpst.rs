@@ -0,0 +1,533 @@
+use crate::{
+    GenericAddressAccess, SDT_HEADER_SIZE, SDTHeader,
+    pcct::{PCCChannel, PCCError, PlatformCommunicationsChannel},
+};
+
+/// The PCC command that executes the MPST command in the command register.
+pub const MPST_PCC_COMMAND: u8 = 0x03;
+
+// Offsets of the MPST fields in the PCC communication space.
+const COMMAND_REGISTER: usize = 0;
+const STATUS_REGISTER: usize = 4;
+const POWER_STATE_ID: usize = 8;
+const POWER_NODE_ID: usize = 12;
+const ENERGY_CONSUMED: usize = 16;
+const AVERAGE_POWER: usize = 24;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## MPST Commands
+pub enum MPSTCommand {
+    /// Gets the current power state of a memory power node.
+    GetMemoryPowerState,
+    /// Moves a memory power node into a power state.
+    SetMemoryPowerState,
+    /// Gets the average power (in mW) of a memory power node.
+    GetAveragePowerConsumed,
+    /// Gets the energy consumed by a memory power node.
+    GetMemoryEnergyConsumed,
+    Unknown(u32),
+}
+impl MPSTCommand {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::GetMemoryPowerState,
+            2 => Self::SetMemoryPowerState,
+            3 => Self::GetAveragePowerConsumed,
+            4 => Self::GetMemoryEnergyConsumed,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u32(&self) -> u32 {
+        match *self {
+            Self::GetMemoryPowerState => 1,
+            Self::SetMemoryPowerState => 2,
+            Self::GetAveragePowerConsumed => 3,
+            Self::GetMemoryEnergyConsumed => 4,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The status register of the MPST shared memory region, after a command.
+pub enum MPSTStatus {
+    Success,
+    NotValid,
+    NotSupported,
+    Busy,
+    Failed,
+    Aborted,
+    InvalidData,
+    Unknown(u32),
+}
+impl MPSTStatus {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::Success,
+            1 => Self::NotValid,
+            2 => Self::NotSupported,
+            3 => Self::Busy,
+            4 => Self::Failed,
+            5 => Self::Aborted,
+            6 => Self::InvalidData,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u32(&self) -> u32 {
+        match *self {
+            Self::Success => 0,
+            Self::NotValid => 1,
+            Self::NotSupported => 2,
+            Self::Busy => 3,
+            Self::Failed => 4,
+            Self::Aborted => 5,
+            Self::InvalidData => 6,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct MemoryPowerNodeFlags(u8);
+impl MemoryPowerNodeFlags {
+    /// If clear, OSPM ignores the node.
+    pub const fn enabled(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// If set, the node can be put in a lower power state with `SetMemoryPowerState` (if clear, the platform manages it by itself).
+    pub const fn power_managed(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// If set, the memory range of the node can be hot removed.
+    pub const fn hot_pluggable(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Power State Structure
+pub struct MemoryPowerState {
+    /// The value to ask for this state with `SetMemoryPowerState` (and what `GetMemoryPowerState` returns in it).
+    pub power_state_value: u8,
+    /// Index of the state's Memory Power State Characteristics structure.
+    pub power_state_information_index: u8,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Power Node Structure
+///
+/// A range of memory that can be put in a lower power state as a whole.
+pub struct MemoryPowerNode {
+    pub flags: MemoryPowerNodeFlags,
+    reserved0: u8,
+    /// Unique ID of the node, used in the MPST commands.
+    pub memory_power_node_id: u16,
+    /// Length, in bytes, of the structure.
+    pub length: u32,
+    pub base_address_low: u32,
+    pub base_address_high: u32,
+    pub length_low: u32,
+    pub length_high: u32,
+    pub number_of_power_states: u32,
+    /// The number of memory components (DIMMs, ranks...) in the node.
+    pub number_of_physical_components: u32,
+    /// Followed by `number_of_power_states` Memory Power State structures, then `number_of_physical_components` component IDs.
+    pub power_states: [MemoryPowerState; 0],
+}
+impl MemoryPowerNode {
+    pub const fn base_address(&self) -> u64 {
+        (self.base_address_high as u64) << 32 | self.base_address_low as u64
+    }
+    /// Length, in bytes, of the memory range of the node.
+    pub const fn range_length(&self) -> u64 {
+        (self.length_high as u64) << 32 | self.length_low as u64
+    }
+    /// Returns true if `address` is in the memory range of the node.
+    pub const fn contains(&self, address: u64) -> bool {
+        address >= self.base_address() && address - self.base_address() < self.range_length()
+    }
+    pub const fn power_states(&self) -> &[MemoryPowerState] {
+        // SAFETY: the power states lie within the node's `size()`, which `MemoryPowerNodeIter` checked against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(32) as *const MemoryPowerState,
+                self.number_of_power_states as usize,
+            )
+        }
+    }
+    /// IDs of the memory components (as in the memory topology of the platform) in the node.
+    pub const fn physical_component_ids(&self) -> &[u16] {
+        // SAFETY: the component IDs lie within the node's `size()`, which `MemoryPowerNodeIter` checked against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(32 + 2 * self.number_of_power_states as usize)
+                    as *const u16,
+                self.number_of_physical_components as usize,
+            )
+        }
+    }
+    /// Size, in bytes, of the node with its power states and component IDs.
+    ///
+    /// Firmware doesn't agree on what goes in `length`, so the size comes from the counts instead.
+    pub const fn size(&self) -> usize {
+        32 + 2 * self.number_of_power_states as usize
+            + 2 * self.number_of_physical_components as usize
+    }
+    /// The power state of the node with the value `value`.
+    pub fn power_state(&self, value: u8) -> Option<&MemoryPowerState> {
+        self.power_states()
+            .iter()
+            .find(|state| state.power_state_value == value)
+    }
+}
+
+/// Iterator over the Memory Power Node structures of an MPST.
+pub struct MemoryPowerNodeIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    count: u16,
+    _mpst: core::marker::PhantomData<&'a MPST>,
+}
+impl<'a> Iterator for MemoryPowerNodeIter<'a> {
+    type Item = &'a MemoryPowerNode;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 || self.remaining < core::mem::size_of::<MemoryPowerNode>() {
+            return None;
+        }
+        // SAFETY: the node's fixed part lies within the table, checked above; the rest is checked below.
+        let node = unsafe { &*(self.next as *const MemoryPowerNode) };
+        let size = node.size();
+        if size > self.remaining {
+            self.count = 0;
+            return None;
+        }
+        self.next = self.next.wrapping_add(size);
+        self.remaining -= size;
+        self.count -= 1;
+        Some(node)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct MemoryPowerStateCharacteristicsFlags(u8);
+impl MemoryPowerStateCharacteristicsFlags {
+    /// If set, the memory keeps its contents in this state.
+    pub const fn memory_content_preserved(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// If set, the platform can put the node in this state by itself.
+    pub const fn autonomous_power_state_entry(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// If set, the platform brings the node out of this state by itself when it's accessed.
+    pub const fn autonomous_power_state_exit(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Power State Characteristics Structure
+pub struct MemoryPowerStateCharacteristics {
+    /// - **Bits 5:0** - Structure type (1)
+    /// - **Bits 7:6** - Structure revision (1)
+    pub power_state_structure_id: u8,
+    pub flags: MemoryPowerStateCharacteristicsFlags,
+    reserved0: u16,
+    /// Average power consumed in MPS0 (the active state), in mW.
+    pub average_power_consumed_mps0: u32,
+    /// Power saved in this state, relative to MPS0, in percent.
+    pub relative_power_saving: u32,
+    /// Time, in ns, to get from this state back to MPS0.
+    pub exit_latency: u64,
+    reserved1: u64,
+}
+impl MemoryPowerStateCharacteristics {
+    pub const fn structure_type(&self) -> u8 {
+        self.power_state_structure_id & 0x3F
+    }
+    pub const fn revision(&self) -> u8 {
+        self.power_state_structure_id >> 6
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Power State Table (MPST)
+///
+/// Describes the ranges of memory (memory power nodes) that OSPM can put in lower power states, and what those states cost and save.
+/// The states are changed with commands over the PCC subspace `pcc_id`.
+pub struct MPST {
+    /// - **Signature** - "MPST"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    /// ID of the PCC subspace the MPST commands go through.
+    pub pcc_id: u8,
+    reserved0: [u8; 3],
+    pub memory_power_node_count: u16,
+    reserved1: u16,
+    pub memory_power_nodes: [u8; 0],
+}
+impl MPST {
+    pub const fn memory_power_nodes(&self) -> MemoryPowerNodeIter<'_> {
+        MemoryPowerNodeIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 8),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 8),
+            count: self.memory_power_node_count,
+            _mpst: core::marker::PhantomData,
+        }
+    }
+    /// The memory power node with the ID `id`.
+    pub fn memory_power_node(&self, id: u16) -> Option<&MemoryPowerNode> {
+        self.memory_power_nodes()
+            .find(|node| node.memory_power_node_id == id)
+    }
+    /// The Memory Power State Characteristics structures, which come right after the last memory power node.
+    pub fn power_state_characteristics(&self) -> &[MemoryPowerStateCharacteristics] {
+        let nodes: usize = self.memory_power_nodes().map(MemoryPowerNode::size).sum();
+        let offset = SDT_HEADER_SIZE + 8 + nodes;
+        let end = self.header.length as usize;
+        if offset + 4 > end {
+            return &[];
+        }
+        // SAFETY: the count lies within the table (checked above), and the structures are cut short at its end.
+        unsafe {
+            let base = (self as *const _ as *const u8).add(offset);
+            let count = (base as *const u16).read_unaligned() as usize;
+            let fits = (end - offset - 4) / core::mem::size_of::<MemoryPowerStateCharacteristics>();
+            core::slice::from_raw_parts(
+                base.add(4) as *const MemoryPowerStateCharacteristics,
+                count.min(fits),
+            )
+        }
+    }
+    /// The characteristics of the power state `state`.
+    pub fn characteristics(
+        &self,
+        state: &MemoryPowerState,
+    ) -> Option<&MemoryPowerStateCharacteristics> {
+        self.power_state_characteristics()
+            .get(state.power_state_information_index as usize)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the memory power state driver can run into.
+pub enum MPSTError {
+    PCC(PCCError),
+    /// The platform finished the command, but the status register isn't `Success`.
+    Status(MPSTStatus),
+    /// There's no memory power node with this ID in the MPST.
+    UnknownNode(u16),
+    /// The node can't be put in power states by OSPM (it isn't enabled, or isn't power managed).
+    NotPowerManaged(u16),
+    /// The node has no power state with this value.
+    UnknownPowerState(u8),
+}
+impl From<PCCError> for MPSTError {
+    fn from(value: PCCError) -> Self {
+        Self::PCC(value)
+    }
+}
+
+/// ## Memory Power State Driver
+///
+/// Issues the MPST commands (get/set the power state of a memory power node, read its power and energy) over the MPST's PCC subspace.
+pub struct MemoryPowerStateController<'a, A: GenericAddressAccess> {
+    mpst: &'a MPST,
+    channel: PCCChannel<A>,
+}
+impl<'a, A: GenericAddressAccess> MemoryPowerStateController<'a, A> {
+    /// Sets up the driver on the PCCT subspace the MPST points to.
+    pub fn new(
+        mpst: &'a MPST,
+        pcct: &PlatformCommunicationsChannel,
+        access: A,
+    ) -> Result<Self, MPSTError> {
+        // The MPST shared memory region takes the regular subspace signature, which the channel starts out with.
+        let channel = PCCChannel::new(pcct, mpst.pcc_id, access)?;
+        Ok(Self { mpst, channel })
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.channel.into_inner()
+    }
+    /// The PCC channel the commands go through (to tune its poll limit, for example).
+    pub fn channel(&mut self) -> &mut PCCChannel<A> {
+        &mut self.channel
+    }
+
+    fn node(&self, node_id: u16) -> Result<&'a MemoryPowerNode, MPSTError> {
+        self.mpst
+            .memory_power_node(node_id)
+            .ok_or(MPSTError::UnknownNode(node_id))
+    }
+    /// Runs `command` on the node `node_id` (with `power_state` in the power state ID field).
+    pub fn execute(
+        &mut self,
+        command: MPSTCommand,
+        node_id: u16,
+        power_state: u8,
+    ) -> Result<(), MPSTError> {
+        self.channel.wait_free()?;
+        self.channel
+            .write(COMMAND_REGISTER, 4, command.as_u32() as u64)?;
+        self.channel.write(POWER_NODE_ID, 4, node_id as u64)?;
+        self.channel.write(POWER_STATE_ID, 4, power_state as u64)?;
+        self.channel.send(MPST_PCC_COMMAND)?;
+        match MPSTStatus::from_u32(self.channel.read(STATUS_REGISTER, 4)? as u32) {
+            MPSTStatus::Success => Ok(()),
+            status => Err(MPSTError::Status(status)),
+        }
+    }
+
+    /// The value of the power state the node `node_id` is in.
+    pub fn get_power_state(&mut self, node_id: u16) -> Result<u8, MPSTError> {
+        self.node(node_id)?;
+        self.execute(MPSTCommand::GetMemoryPowerState, node_id, 0)?;
+        Ok(self.channel.read(POWER_STATE_ID, 4)? as u8)
+    }
+    /// Moves the node `node_id` into the power state with the value `power_state`.
+    pub fn set_power_state(&mut self, node_id: u16, power_state: u8) -> Result<(), MPSTError> {
+        let node = self.node(node_id)?;
+        if !node.flags.enabled() || !node.flags.power_managed() {
+            return Err(MPSTError::NotPowerManaged(node_id));
+        }
+        if node.power_state(power_state).is_none() {
+            return Err(MPSTError::UnknownPowerState(power_state));
+        }
+        self.execute(MPSTCommand::SetMemoryPowerState, node_id, power_state)
+    }
+    /// The average power consumed by the node `node_id`, in mW.
+    pub fn average_power(&mut self, node_id: u16) -> Result<u64, MPSTError> {
+        self.node(node_id)?;
+        self.execute(MPSTCommand::GetAveragePowerConsumed, node_id, 0)?;
+        Ok(self.channel.read(AVERAGE_POWER, 8)?)
+    }
+    /// The energy consumed by the node `node_id`.
+    pub fn energy_consumed(&mut self, node_id: u16) -> Result<u64, MPSTError> {
+        self.node(node_id)?;
+        self.execute(MPSTCommand::GetMemoryEnergyConsumed, node_id, 0)?;
+        Ok(self.channel.read(ENERGY_CONSUMED, 8)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcct::fake::*;
+
+    fn field(space: &[u8], offset: usize) -> u32 {
+        let mut value = [0; 4];
+        value.copy_from_slice(&space[offset..offset + 4]);
+        u32::from_le_bytes(value)
+    }
+
+    /// Node 5 is at power state 2, and can only be moved to it.
+    fn platform(memory: &mut [u8; SHARED_MEMORY_SIZE]) -> bool {
+        if memory[4] != MPST_PCC_COMMAND {
+            return true;
+        }
+        let space = &mut memory[8..];
+        let status = if field(space, POWER_NODE_ID) != 5 {
+            MPSTStatus::NotValid
+        } else {
+            match MPSTCommand::from_u32(field(space, COMMAND_REGISTER)) {
+                MPSTCommand::GetMemoryPowerState => {
+                    space[POWER_STATE_ID..][..4].copy_from_slice(&2u32.to_le_bytes());
+                    MPSTStatus::Success
+                }
+                MPSTCommand::SetMemoryPowerState if field(space, POWER_STATE_ID) == 2 => {
+                    MPSTStatus::Success
+                }
+                MPSTCommand::SetMemoryPowerState => MPSTStatus::Failed,
+                MPSTCommand::GetAveragePowerConsumed => {
+                    space[AVERAGE_POWER..][..8].copy_from_slice(&1500u64.to_le_bytes());
+                    MPSTStatus::Success
+                }
+                MPSTCommand::GetMemoryEnergyConsumed => {
+                    space[ENERGY_CONSUMED..][..8].copy_from_slice(&0x1234u64.to_le_bytes());
+                    MPSTStatus::Success
+                }
+                MPSTCommand::Unknown(_) => MPSTStatus::NotSupported,
+            }
+        };
+        space[STATUS_REGISTER..][..4].copy_from_slice(&status.as_u32().to_le_bytes());
+        false
+    }
+
+    /// An MPST on PCC subspace 0: node 5 is power managed with states 0 and 2, node 6 isn't.
+    fn mpst() -> [u64; 16] {
+        let mut table = [0u64; 16];
+        // SAFETY: the buffer is 128 bytes, and the table is 116.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(table.as_mut_ptr() as *mut u8, 128) };
+        bytes[..4].copy_from_slice(b"MPST");
+        bytes[4..8].copy_from_slice(&116u32.to_le_bytes());
+        bytes[SDT_HEADER_SIZE + 4..][..2].copy_from_slice(&2u16.to_le_bytes());
+        for (i, (flags, id)) in [(0b011u8, 5u16), (0b001, 6)].iter().enumerate() {
+            let node = &mut bytes[SDT_HEADER_SIZE + 8 + i * 36..][..36];
+            node[0] = *flags;
+            node[2..4].copy_from_slice(&id.to_le_bytes());
+            node[4..8].copy_from_slice(&36u32.to_le_bytes());
+            node[24..28].copy_from_slice(&2u32.to_le_bytes());
+            node[32..36].copy_from_slice(&[0, 0, 2, 1]);
+        }
+        table
+    }
+
+    fn controller<'a>(
+        mpst: &'a [u64; 16],
+        pcct: &[u64; 16],
+    ) -> MemoryPowerStateController<'a, FakePCC> {
+        // SAFETY: the buffer is laid out like an MPST, and aligned.
+        let mpst = unsafe { &*(mpst.as_ptr() as *const MPST) };
+        MemoryPowerStateController::new(mpst, table(pcct), FakePCC::new(platform)).unwrap()
+    }
+
+    #[test]
+    fn commands() {
+        let (mpst, pcct) = (mpst(), pcct());
+        let mut mpsc = controller(&mpst, &pcct);
+        assert_eq!(mpsc.get_power_state(5), Ok(2));
+        mpsc.set_power_state(5, 2).unwrap();
+        assert_eq!(mpsc.average_power(5), Ok(1500));
+        assert_eq!(mpsc.energy_consumed(5), Ok(0x1234));
+        assert_eq!(
+            mpsc.set_power_state(5, 0),
+            Err(MPSTError::Status(MPSTStatus::Failed))
+        );
+        let pcc = mpsc.into_inner();
+        assert_eq!(pcc.doorbells, 5);
+        // The regular subspace signature.
+        assert_eq!(pcc.read_memory(0, 4), 0x50434300);
+    }
+
+    #[test]
+    fn checks_before_sending() {
+        let (mpst, pcct) = (mpst(), pcct());
+        let mut mpsc = controller(&mpst, &pcct);
+        assert_eq!(mpsc.get_power_state(9), Err(MPSTError::UnknownNode(9)));
+        assert_eq!(
+            mpsc.set_power_state(6, 0),
+            Err(MPSTError::NotPowerManaged(6))
+        );
+        assert_eq!(
+            mpsc.set_power_state(5, 1),
+            Err(MPSTError::UnknownPowerState(1))
+        );
+        assert_eq!(mpsc.into_inner().doorbells, 0);
+    }
+
+    #[test]
+    fn waits_for_the_previous_command() {
+        let (mpst, pcct) = (mpst(), pcct());
+        let mut mpsc = controller(&mpst, &pcct);
+        mpsc.channel().access_mut().in_flight = 3;
+        mpsc.set_power_state(5, 2).unwrap();
+        assert!(!mpsc.into_inner().clobbered);
+    }
+}
@@ -0,0 +1,603 @@
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    srat::{SRAT, affinity::memory::MemoryAffinity},
+    uuid,
+};
+
+/// Volatile Memory Region
+pub const VOLATILE_MEMORY_REGION: [u8; 16] = uuid("7305944F-FDDA-44E3-B16C-3F22D252E5D0");
+/// Byte Addressable Persistent Memory (PM) Region
+pub const PERSISTENT_MEMORY_REGION: [u8; 16] = uuid("66F0D379-B4F3-4074-AC43-0D3318B78CDB");
+/// NVDIMM Control Region
+pub const NVDIMM_CONTROL_REGION: [u8; 16] = uuid("92F701F6-13B4-405D-910B-299367E8234C");
+/// NVDIMM Block Data Window Region
+pub const NVDIMM_BLOCK_DATA_WINDOW_REGION: [u8; 16] = uuid("91AF0530-5D86-470E-A6B0-0A2DB9408249");
+/// RAM Disk supporting a Virtual Disk Region - Volatile
+pub const RAM_DISK_VIRTUAL_DISK_VOLATILE: [u8; 16] = uuid("77AB535A-45FC-624B-5560-F7B281D1F96E");
+/// RAM Disk supporting a Virtual CD Region - Volatile
+pub const RAM_DISK_VIRTUAL_CD_VOLATILE: [u8; 16] = uuid("3D5ABD30-4175-87CE-6D64-D2ADE523C4BB");
+/// RAM Disk supporting a Virtual Disk Region - Persistent
+pub const RAM_DISK_VIRTUAL_DISK_PERSISTENT: [u8; 16] = uuid("5CEA02C9-4D07-69D3-269F-4496FBE096F9");
+/// RAM Disk supporting a Virtual CD Region - Persistent
+pub const RAM_DISK_VIRTUAL_CD_PERSISTENT: [u8; 16] = uuid("08018188-42CD-BB48-100F-5387D53DED3D");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// What an SPA range is, by its Address Range Type GUID.
+pub enum AddressRangeType {
+    VolatileMemory,
+    PersistentMemory,
+    NVDIMMControlRegion,
+    NVDIMMBlockDataWindow,
+    RAMDiskVirtualDiskVolatile,
+    RAMDiskVirtualCDVolatile,
+    RAMDiskVirtualDiskPersistent,
+    RAMDiskVirtualCDPersistent,
+    Unknown([u8; 16]),
+}
+impl AddressRangeType {
+    pub const fn from_guid(guid: [u8; 16]) -> Self {
+        match guid {
+            VOLATILE_MEMORY_REGION => Self::VolatileMemory,
+            PERSISTENT_MEMORY_REGION => Self::PersistentMemory,
+            NVDIMM_CONTROL_REGION => Self::NVDIMMControlRegion,
+            NVDIMM_BLOCK_DATA_WINDOW_REGION => Self::NVDIMMBlockDataWindow,
+            RAM_DISK_VIRTUAL_DISK_VOLATILE => Self::RAMDiskVirtualDiskVolatile,
+            RAM_DISK_VIRTUAL_CD_VOLATILE => Self::RAMDiskVirtualCDVolatile,
+            RAM_DISK_VIRTUAL_DISK_PERSISTENT => Self::RAMDiskVirtualDiskPersistent,
+            RAM_DISK_VIRTUAL_CD_PERSISTENT => Self::RAMDiskVirtualCDPersistent,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// ## NFIT Device Handle
+///
+/// Names an NVDIMM by where it sits in the platform.
+pub struct NFITDeviceHandle(u32);
+impl NFITDeviceHandle {
+    pub const fn new(value: u32) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    pub const fn dimm_number(&self) -> u8 {
+        (self.0 & 0xF) as u8
+    }
+    pub const fn memory_channel_number(&self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+    pub const fn memory_controller_id(&self) -> u8 {
+        ((self.0 >> 8) & 0xF) as u8
+    }
+    pub const fn socket_id(&self) -> u8 {
+        ((self.0 >> 12) & 0xF) as u8
+    }
+    pub const fn node_controller_id(&self) -> u16 {
+        ((self.0 >> 16) & 0xFFF) as u16
+    }
+}
+impl core::fmt::Debug for NFITDeviceHandle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "NFITDeviceHandle({:#x})", self.0)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct NFITStructureHeader {
+    pub r#type: u16,
+    /// Length, in bytes, of the whole structure.
+    pub length: u16,
+}
+
+#[derive(Copy, Clone)]
+pub struct SPARangeFlags(u16);
+impl SPARangeFlags {
+    /// If set, the control region is only for hot add/online operations.
+    pub const fn control_region_for_management(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// If set, `proximity_domain` is valid.
+    pub const fn proximity_domain_valid(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// If set, the SPA location cookie is valid.
+    pub const fn spa_location_cookie_valid(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## System Physical Address (SPA) Range Structure
+///
+/// A range of system physical addresses and what's behind it (persistent memory, an NVDIMM control region...).
+pub struct SPARange {
+    /// - **Type** - 0
+    /// - **Length** - 56, or 64 with the SPA location cookie
+    pub header: NFITStructureHeader,
+    /// The index other structures use to refer to this one (never 0).
+    pub spa_range_structure_index: u16,
+    pub flags: SPARangeFlags,
+    reserved: u32,
+    /// The proximity domain (as in the SRAT) of the range, if `proximity_domain_valid` is set.
+    pub proximity_domain: u32,
+    pub address_range_type_guid: [u8; 16],
+    pub system_physical_address_range_base: u64,
+    pub system_physical_address_range_length: u64,
+    /// The EFI memory attributes of the range (EFI_MEMORY_UC, EFI_MEMORY_WB, EFI_MEMORY_NV...).
+    pub address_range_memory_mapping_attribute: u64,
+}
+impl SPARange {
+    pub const fn address_range_type(&self) -> AddressRangeType {
+        AddressRangeType::from_guid(self.address_range_type_guid)
+    }
+    pub const fn contains(&self, address: u64) -> bool {
+        address >= self.system_physical_address_range_base
+            && address - self.system_physical_address_range_base
+                < self.system_physical_address_range_length
+    }
+    /// A platform-chosen value that stays the same across boots for the same range, to tell namespaces apart.
+    pub const fn spa_location_cookie(&self) -> Option<u64> {
+        if self.header.length < 64 || !({ self.flags }).spa_location_cookie_valid() {
+            return None;
+        }
+        // SAFETY: the cookie lies within the structure's length (at least 64, checked above), which `NFITStructureIter` checked against the table.
+        Some(unsafe { ((self as *const _ as *const u8).add(56) as *const u64).read_unaligned() })
+    }
+    /// The SRAT Memory Affinity structure covering the start of this range (in the same proximity domain, if the range gives one).
+    pub fn memory_affinity<'s>(&self, srat: &'s SRAT) -> Option<&'s MemoryAffinity> {
+        let base = self.system_physical_address_range_base;
+        let domain = if ({ self.flags }).proximity_domain_valid() {
+            Some(self.proximity_domain)
+        } else {
+            None
+        };
+        srat.memory_affinities().find(|affinity| {
+            ({ affinity.flags }).enabled()
+                && affinity.contains(base)
+                && domain.is_none_or(|domain| affinity.proximity_domain == domain)
+        })
+    }
+    /// The proximity domain of this range: its own, or else the one of the SRAT memory range covering it.
+    pub fn resolve_proximity_domain(&self, srat: &SRAT) -> Option<u32> {
+        if ({ self.flags }).proximity_domain_valid() {
+            return Some(self.proximity_domain);
+        }
+        self.memory_affinity(srat)
+            .map(|affinity| affinity.proximity_domain)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct NVDIMMStateFlags(u16);
+impl NVDIMMStateFlags {
+    /// The last data save to the NVDIMM failed.
+    pub const fn save_failed(&self) -> bool {
+        self.0 & 0b0000001 != 0
+    }
+    /// The last data restore from the NVDIMM failed.
+    pub const fn restore_failed(&self) -> bool {
+        self.0 & 0b0000010 != 0
+    }
+    /// The platform flush of data to the NVDIMM failed; data may have been lost.
+    pub const fn flush_failed(&self) -> bool {
+        self.0 & 0b0000100 != 0
+    }
+    /// The NVDIMM isn't armed: it can't save data on power loss (it may be read-only).
+    pub const fn not_armed(&self) -> bool {
+        self.0 & 0b0001000 != 0
+    }
+    /// The NVDIMM saw SMART or health events before OSPM took over.
+    pub const fn smart_health_events_observed(&self) -> bool {
+        self.0 & 0b0010000 != 0
+    }
+    /// Firmware is set up to send health event notifications for the NVDIMM.
+    pub const fn firmware_health_notifications_enabled(&self) -> bool {
+        self.0 & 0b0100000 != 0
+    }
+    /// Firmware didn't map the NVDIMM into system physical address space.
+    pub const fn map_failed(&self) -> bool {
+        self.0 & 0b1000000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Memory Device to System Physical Address Range Mapping Structure
+///
+/// Which part of an SPA range an NVDIMM region backs, and how it's interleaved.
+pub struct MemoryDeviceToSPARangeMap {
+    /// - **Type** - 1
+    /// - **Length** - 48
+    pub header: NFITStructureHeader,
+    pub nfit_device_handle: NFITDeviceHandle,
+    /// The SMBIOS Type 17 handle of the NVDIMM.
+    pub nvdimm_physical_id: u16,
+    /// Unique (per NVDIMM) ID of the region.
+    pub nvdimm_region_id: u16,
+    /// The SPA range this region is in (0 if it isn't mapped).
+    pub spa_range_structure_index: u16,
+    pub nvdimm_control_region_structure_index: u16,
+    /// Size, in bytes, of the region on the NVDIMM.
+    pub nvdimm_region_size: u64,
+    /// Offset, in the SPA range, of the first byte of this region.
+    pub region_offset: u64,
+    /// Address, in the NVDIMM's own address space, of the start of the region.
+    pub nvdimm_physical_address_region_base: u64,
+    /// The interleave structure of the region (0 if it isn't interleaved).
+    pub interleave_structure_index: u16,
+    /// The number of NVDIMMs the SPA range is interleaved across.
+    pub interleave_ways: u16,
+    pub nvdimm_state_flags: NVDIMMStateFlags,
+    reserved: u16,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Interleave Structure
+///
+/// The layout of one NVDIMM's lines in an interleaved SPA range.
+pub struct Interleave {
+    /// - **Type** - 2
+    pub header: NFITStructureHeader,
+    pub interleave_structure_index: u16,
+    reserved: u16,
+    pub number_of_lines_described: u32,
+    /// Size, in bytes, of a line.
+    pub line_size: u32,
+    pub line_offsets: [u32; 0],
+}
+impl Interleave {
+    /// The offset of every line, in multiples of `line_size`, from the region offset.
+    pub const fn line_offsets(&self) -> &[u32] {
+        let fits = (self.header.length as usize).saturating_sub(16) / 4;
+        let count = if (self.number_of_lines_described as usize) < fits {
+            self.number_of_lines_described as usize
+        } else {
+            fits
+        };
+        // SAFETY: the offsets lie within the structure's length, which bounds the count.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(16) as *const u32,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## SMBIOS Management Information Structure
+///
+/// SMBIOS tables for the NVDIMMs that aren't in the system SMBIOS table.
+pub struct SMBIOSManagementInformation {
+    /// - **Type** - 3
+    pub header: NFITStructureHeader,
+    reserved: u32,
+    pub data: [u8; 0],
+}
+impl SMBIOSManagementInformation {
+    pub const fn data(&self) -> &[u8] {
+        // SAFETY: the data runs to the end of the structure's length, which `NFITStructureIter` checked against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(8),
+                (self.header.length as usize).saturating_sub(8),
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Block Control Window Layout
+///
+/// The part of an NVDIMM Control Region structure only there if it has block control windows.
+pub struct BlockControlWindows {
+    /// Size, in bytes, of one block control window.
+    pub size_of_block_control_window: u64,
+    /// Offset of the command register in the block control window.
+    pub command_register_offset: u64,
+    pub size_of_command_register: u64,
+    /// Offset of the status register in the block control window.
+    pub status_register_offset: u64,
+    pub size_of_status_register: u64,
+    /// - **Bit 0** - Block Data Windows Buffered: set if the data windows only hold a copy that has to be committed.
+    pub nvdimm_control_region_flag: u16,
+    reserved: [u8; 6],
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## NVDIMM Control Region Structure
+///
+/// Identifies an NVDIMM (PCI-style IDs, serial number) and the interface its control region speaks.
+pub struct NVDIMMControlRegion {
+    /// - **Type** - 4
+    /// - **Length** - 32, or 80 with block control windows
+    pub header: NFITStructureHeader,
+    pub nvdimm_control_region_structure_index: u16,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub revision_id: u16,
+    pub subsystem_vendor_id: u16,
+    pub subsystem_device_id: u16,
+    pub subsystem_revision_id: u16,
+    /// - **Bit 0** - `manufacturing_location` and `manufacturing_date` are valid
+    pub valid_fields: u8,
+    pub manufacturing_location: u8,
+    /// Year (BCD, low byte) and week (BCD, high byte).
+    pub manufacturing_date: u16,
+    reserved: u16,
+    pub serial_number: u32,
+    /// The interface the control region implements (as in the JEDEC registry, like 0x0201 for byte addressable energy backed).
+    pub region_format_interface_code: u16,
+    pub number_of_block_control_windows: u16,
+}
+impl NVDIMMControlRegion {
+    pub const fn block_control_windows(&self) -> Option<&BlockControlWindows> {
+        if self.number_of_block_control_windows == 0 || self.header.length < 80 {
+            return None;
+        }
+        // SAFETY: the windows lie within the structure's length (at least 80, checked above), which `NFITStructureIter` checked against the table.
+        Some(unsafe { &*((self as *const _ as *const u8).add(32) as *const BlockControlWindows) })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## NVDIMM Block Data Window Region Structure
+pub struct NVDIMMBlockDataWindowRegion {
+    /// - **Type** - 5
+    /// - **Length** - 40
+    pub header: NFITStructureHeader,
+    pub nvdimm_control_region_structure_index: u16,
+    pub number_of_block_data_windows: u16,
+    /// Offset of the first block data window in the region.
+    pub block_data_window_start_offset: u64,
+    pub size_of_block_data_window: u64,
+    /// Size, in bytes, of the memory reachable through the block data windows.
+    pub block_accessible_memory_capacity: u64,
+    /// Address, in the NVDIMM's own address space, of the first block reachable through the windows.
+    pub start_address_for_first_block: u64,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Flush Hint Address Structure
+///
+/// Writes to any of these addresses flush the NVDIMM's memory controller write buffers, so stores reach persistence.
+pub struct FlushHintAddress {
+    /// - **Type** - 6
+    pub header: NFITStructureHeader,
+    pub nfit_device_handle: NFITDeviceHandle,
+    pub number_of_flush_hint_addresses: u16,
+    reserved: [u8; 6],
+    pub flush_hint_addresses: [u64; 0],
+}
+impl FlushHintAddress {
+    pub const fn flush_hint_addresses(&self) -> &[u64] {
+        let fits = (self.header.length as usize).saturating_sub(16) / 8;
+        let count = if (self.number_of_flush_hint_addresses as usize) < fits {
+            self.number_of_flush_hint_addresses as usize
+        } else {
+            fits
+        };
+        // SAFETY: the addresses lie within the structure's length, which bounds the count.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(16) as *const u64,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct PlatformCapabilitiesFlags(u32);
+impl PlatformCapabilitiesFlags {
+    /// CPU caches are flushed to the NVDIMMs on power loss.
+    pub const fn cpu_cache_flush_to_nvdimm(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// Memory controller write buffers are flushed to the NVDIMMs on power loss.
+    pub const fn memory_controller_flush_to_nvdimm(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// Byte addressable persistent memory can be mirrored in hardware.
+    pub const fn hardware_mirroring(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Platform Capabilities Structure
+pub struct PlatformCapabilities {
+    /// - **Type** - 7
+    /// - **Length** - 16
+    pub header: NFITStructureHeader,
+    /// The highest bit of `capabilities` that's valid; the bits above it mean nothing.
+    pub highest_valid_capability: u8,
+    reserved0: [u8; 3],
+    pub capabilities: PlatformCapabilitiesFlags,
+    reserved1: u32,
+}
+impl PlatformCapabilities {
+    /// The capabilities, with the bits past `highest_valid_capability` cleared.
+    pub const fn valid_capabilities(&self) -> PlatformCapabilitiesFlags {
+        let mask = match self.highest_valid_capability {
+            31.. => u32::MAX,
+            highest => (1 << (highest + 1)) - 1,
+        };
+        PlatformCapabilitiesFlags(self.capabilities.0 & mask)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum NFITStructure<'a> {
+    SPARange(&'a SPARange),
+    MemoryDeviceToSPARangeMap(&'a MemoryDeviceToSPARangeMap),
+    Interleave(&'a Interleave),
+    SMBIOSManagementInformation(&'a SMBIOSManagementInformation),
+    NVDIMMControlRegion(&'a NVDIMMControlRegion),
+    NVDIMMBlockDataWindowRegion(&'a NVDIMMBlockDataWindowRegion),
+    FlushHintAddress(&'a FlushHintAddress),
+    PlatformCapabilities(&'a PlatformCapabilities),
+    /// A structure type this library doesn't know about (or a reserved one).
+    Unknown(&'a NFITStructureHeader),
+}
+
+/// Iterator over the structures of an NFIT.
+pub struct NFITStructureIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _nfit: core::marker::PhantomData<&'a NFIT>,
+}
+impl<'a> Iterator for NFITStructureIter<'a> {
+    type Item = NFITStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 4 {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(self.next as *const NFITStructureHeader) };
+        let length = header.length as usize;
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 4 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        let fits = |size: usize| length >= size;
+        // SAFETY: every structure is checked against its length before the cast.
+        Some(unsafe {
+            match header.r#type {
+                0 if fits(core::mem::size_of::<SPARange>()) => {
+                    NFITStructure::SPARange(&*(ptr as *const _))
+                }
+                1 if fits(core::mem::size_of::<MemoryDeviceToSPARangeMap>()) => {
+                    NFITStructure::MemoryDeviceToSPARangeMap(&*(ptr as *const _))
+                }
+                2 if fits(core::mem::size_of::<Interleave>()) => {
+                    NFITStructure::Interleave(&*(ptr as *const _))
+                }
+                3 if fits(core::mem::size_of::<SMBIOSManagementInformation>()) => {
+                    NFITStructure::SMBIOSManagementInformation(&*(ptr as *const _))
+                }
+                4 if fits(core::mem::size_of::<NVDIMMControlRegion>()) => {
+                    NFITStructure::NVDIMMControlRegion(&*(ptr as *const _))
+                }
+                5 if fits(core::mem::size_of::<NVDIMMBlockDataWindowRegion>()) => {
+                    NFITStructure::NVDIMMBlockDataWindowRegion(&*(ptr as *const _))
+                }
+                6 if fits(core::mem::size_of::<FlushHintAddress>()) => {
+                    NFITStructure::FlushHintAddress(&*(ptr as *const _))
+                }
+                7 if fits(core::mem::size_of::<PlatformCapabilities>()) => {
+                    NFITStructure::PlatformCapabilities(&*(ptr as *const _))
+                }
+                _ => NFITStructure::Unknown(header),
+            }
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## NVDIMM Firmware Interface Table (NFIT)
+///
+/// Describes the NVDIMMs of the platform: the system physical address ranges they back (and how they're interleaved),
+/// their control and block data window regions, and how to flush writes to them.
+pub struct NFIT {
+    /// - **Signature** - "NFIT"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    reserved: u32,
+}
+impl NFIT {
+    pub const fn structures(&self) -> NFITStructureIter<'_> {
+        NFITStructureIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 4),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 4),
+            _nfit: core::marker::PhantomData,
+        }
+    }
+    pub fn spa_ranges(&self) -> impl Iterator<Item = &SPARange> {
+        self.structures().filter_map(|structure| match structure {
+            NFITStructure::SPARange(spa) => Some(spa),
+            _ => None,
+        })
+    }
+    /// The SPA range with the index `index`.
+    pub fn spa_range(&self, index: u16) -> Option<&SPARange> {
+        self.spa_ranges()
+            .find(|spa| spa.spa_range_structure_index == index)
+    }
+    /// The NVDIMM regions that back the SPA range with the index `index`.
+    pub fn mappings(&self, index: u16) -> impl Iterator<Item = &MemoryDeviceToSPARangeMap> {
+        self.structures()
+            .filter_map(move |structure| match structure {
+                NFITStructure::MemoryDeviceToSPARangeMap(map)
+                    if map.spa_range_structure_index == index =>
+                {
+                    Some(map)
+                }
+                _ => None,
+            })
+    }
+    pub fn interleave(&self, index: u16) -> Option<&Interleave> {
+        self.structures().find_map(|structure| match structure {
+            NFITStructure::Interleave(interleave)
+                if interleave.interleave_structure_index == index =>
+            {
+                Some(interleave)
+            }
+            _ => None,
+        })
+    }
+    pub fn control_region(&self, index: u16) -> Option<&NVDIMMControlRegion> {
+        self.structures().find_map(|structure| match structure {
+            NFITStructure::NVDIMMControlRegion(region)
+                if region.nvdimm_control_region_structure_index == index =>
+            {
+                Some(region)
+            }
+            _ => None,
+        })
+    }
+    pub fn block_data_window_region(&self, index: u16) -> Option<&NVDIMMBlockDataWindowRegion> {
+        self.structures().find_map(|structure| match structure {
+            NFITStructure::NVDIMMBlockDataWindowRegion(region)
+                if region.nvdimm_control_region_structure_index == index =>
+            {
+                Some(region)
+            }
+            _ => None,
+        })
+    }
+    /// The flush hint addresses of the NVDIMM `handle`.
+    pub fn flush_hints(&self, handle: NFITDeviceHandle) -> Option<&FlushHintAddress> {
+        self.structures().find_map(|structure| match structure {
+            NFITStructure::FlushHintAddress(hints) if { hints.nfit_device_handle } == handle => {
+                Some(hints)
+            }
+            _ => None,
+        })
+    }
+    pub fn platform_capabilities(&self) -> Option<&PlatformCapabilities> {
+        self.structures().find_map(|structure| match structure {
+            NFITStructure::PlatformCapabilities(capabilities) => Some(capabilities),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{AMLArgument, AMLEvaluator, uuid};
+
+/// _OSC UUID for the platform-wide OSPM capabilities, evaluated under \_SB.
+pub const PLATFORM_WIDE_UUID: [u8; 16] = uuid("0811B06E-4A27-44F9-8D60-3CBBC22E7B48");
+/// _OSC UUID for a PCI/PCI Express host bridge device.
+pub const PCI_HOST_BRIDGE_UUID: [u8; 16] = uuid("33DB4D5B-1FF7-401C-9657-7441C03DD766");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## _OSC Status (Capabilities Buffer DWORD 1)
+///
+/// On the way in, OSPM sets only the query bit. On the way out, the platform reports errors in bits 1 to 4.
+pub struct OSCStatus(u32);
+impl OSCStatus {
+    /// If set, OSPM is only querying which capabilities the platform would grant; nothing is actually handed over.
+    pub const QUERY: Self = Self(1 << 0);
+    /// The _OSC method failed. The other bits give the reason.
+    pub const FAILURE: Self = Self(1 << 1);
+    /// The platform does not recognize the UUID.
+    pub const UNRECOGNIZED_UUID: Self = Self(1 << 2);
+    /// The platform does not recognize the revision.
+    pub const UNRECOGNIZED_REVISION: Self = Self(1 << 3);
+    /// The platform cleared some of the requested capability bits.
+    pub const CAPABILITIES_MASKED: Self = Self(1 << 4);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+    /// True if any of the error bits (1 to 4) are set.
+    ///
+    /// Capabilities Masked alone isn't really a failure, the granted bits are still valid; check `contains` if that matters.
+    pub const fn is_error(&self) -> bool {
+        self.0 & 0b11110 != 0
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Platform-Wide _OSC Capabilities DWORD 2
+pub struct PlatformWideCapabilities(u32);
+impl PlatformWideCapabilities {
+    /// OSPM supports the Processor Aggregator Device.
+    pub const PROCESSOR_AGGREGATOR_DEVICE: Self = Self(1 << 0);
+    /// OSPM evaluates _OST after processing a _PPC change notification.
+    pub const PPC_OST: Self = Self(1 << 1);
+    /// OSPM supports _PR3 and the D3cold state.
+    pub const PR3: Self = Self(1 << 2);
+    /// OSPM evaluates _OST during device insertion/ejection processing.
+    pub const INSERTION_EJECTION_OST: Self = Self(1 << 3);
+    /// OSPM supports the ACPI Platform Error Interfaces (APEI).
+    pub const APEI: Self = Self(1 << 4);
+    /// OSPM supports Collaborative Processor Performance Control (_CPC revision 1).
+    pub const CPPC: Self = Self(1 << 5);
+    /// OSPM supports CPPC revision 2.
+    pub const CPPC2: Self = Self(1 << 6);
+    /// OSPM supports platform coordinated low power idle states.
+    pub const PLATFORM_COORDINATED_LPI: Self = Self(1 << 7);
+    /// OSPM supports OS initiated low power idle states.
+    pub const OS_INITIATED_LPI: Self = Self(1 << 8);
+    /// OSPM supports fast thermal sampling (_TSP values below 100 ms).
+    pub const FAST_THERMAL_SAMPLING: Self = Self(1 << 9);
+    /// OSPM supports more than 16 P-states.
+    pub const GREATER_THAN_16_P_STATES: Self = Self(1 << 10);
+    /// OSPM supports the Generic Event Device.
+    pub const GENERIC_EVENT_DEVICE: Self = Self(1 << 11);
+    /// OSPM supports diverse highest performance values in _CPC.
+    pub const CPPC_DIVERSE_HIGHEST: Self = Self(1 << 12);
+    /// OSPM supports ResourceSource in Interrupt resource descriptors.
+    pub const INTERRUPT_RESOURCE_SOURCE: Self = Self(1 << 13);
+    /// OSPM supports any address space for CPPC registers.
+    pub const CPPC_FLEXIBLE_ADDRESS_SPACE: Self = Self(1 << 14);
+    /// OSPM supports Generic Initiator Affinity structures in the SRAT.
+    pub const GENERIC_INITIATOR: Self = Self(1 << 17);
+    /// OSPM supports native USB4 control.
+    pub const NATIVE_USB4: Self = Self(1 << 18);
+    /// OSPM supports battery charge limiting.
+    pub const BATTERY_CHARGE_LIMITING: Self = Self(1 << 19);
+    /// OSPM supports Platform Runtime Mechanism (PRM).
+    pub const PLATFORM_RUNTIME_MECHANISM: Self = Self(1 << 21);
+    /// OSPM supports Functional Fixed Hardware operation regions.
+    pub const FFH_OPREGION: Self = Self(1 << 22);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## PCI Host Bridge _OSC Support Field (DWORD 2)
+pub struct PCISupport(u32);
+impl PCISupport {
+    /// OSPM supports ASL access to the extended (4 KB) PCI Express configuration space.
+    pub const EXTENDED_CONFIG_SPACE: Self = Self(1 << 0);
+    /// OSPM supports native PCI Express Active State Power Management.
+    pub const ASPM: Self = Self(1 << 1);
+    /// OSPM supports PCI Express clock power management.
+    pub const CLOCK_PM: Self = Self(1 << 2);
+    /// OSPM supports PCI segment groups (_SEG).
+    pub const SEGMENT_GROUPS: Self = Self(1 << 3);
+    /// OSPM supports Message Signaled Interrupts.
+    pub const MSI: Self = Self(1 << 4);
+    /// OSPM supports Error Disconnect Recover.
+    pub const EDR: Self = Self(1 << 7);
+    /// OSPM supports _HPX Type 3 records.
+    pub const HPX_TYPE_3: Self = Self(1 << 8);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## PCI Host Bridge _OSC Control Field (DWORD 3)
+///
+/// OSPM sets the features it wants native control of; the platform returns the ones it grants.
+pub struct PCIControl(u32);
+impl PCIControl {
+    /// Native PCI Express hot plug.
+    pub const PCIE_NATIVE_HOT_PLUG: Self = Self(1 << 0);
+    /// Native Standard Hot-Plug Controller (SHPC) hot plug.
+    pub const SHPC_NATIVE_HOT_PLUG: Self = Self(1 << 1);
+    /// Native PCI Express Power Management Events.
+    pub const PCIE_PME: Self = Self(1 << 2);
+    /// Native PCI Express Advanced Error Reporting.
+    pub const PCIE_AER: Self = Self(1 << 3);
+    /// Control of the PCI Express capability structure.
+    pub const PCIE_CAPABILITY_STRUCTURE: Self = Self(1 << 4);
+    /// Control of Latency Tolerance Reporting.
+    pub const LTR: Self = Self(1 << 5);
+    /// Native Downstream Port Containment.
+    pub const DPC: Self = Self(1 << 7);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// The most capability DWORDs any _OSC buffer built here carries.
+pub const OSC_MAX_DWORDS: usize = 3;
+
+#[derive(Copy, Clone)]
+/// ## _OSC Arguments
+///
+/// Everything the interpreter passes when evaluating _OSC:
+///
+/// - **Arg0** - `uuid`
+/// - **Arg1** - `revision`
+/// - **Arg2** - `count()`, the number of DWORDs in the buffer
+/// - **Arg3** - `capabilities_buffer()`, the capabilities buffer
+///
+/// The method returns a buffer of the same length, which `OSCResponse::from_bytes` decodes.
+pub struct OSCRequest {
+    pub uuid: [u8; 16],
+    pub revision: u32,
+    dwords: [u32; OSC_MAX_DWORDS],
+    count: usize,
+}
+impl OSCRequest {
+    /// Platform-wide capabilities, evaluated at \_SB._OSC.
+    pub const fn platform_wide(query: bool, support: PlatformWideCapabilities) -> Self {
+        Self {
+            uuid: PLATFORM_WIDE_UUID,
+            revision: 1,
+            dwords: [Self::status(query).bits(), support.bits(), 0],
+            count: 2,
+        }
+    }
+    /// PCI host bridge capabilities, evaluated at the host bridge's _OSC.
+    pub const fn pci_host_bridge(query: bool, support: PCISupport, control: PCIControl) -> Self {
+        Self {
+            uuid: PCI_HOST_BRIDGE_UUID,
+            revision: 1,
+            dwords: [Self::status(query).bits(), support.bits(), control.bits()],
+            count: 3,
+        }
+    }
+    const fn status(query: bool) -> OSCStatus {
+        if query {
+            OSCStatus::QUERY
+        } else {
+            OSCStatus::empty()
+        }
+    }
+    /// The number of DWORDs in the capabilities buffer (Arg2).
+    pub const fn count(&self) -> u32 {
+        self.count as u32
+    }
+    /// The capabilities buffer as DWORDs.
+    pub fn dwords(&self) -> &[u32] {
+        &self.dwords[..self.count]
+    }
+    /// The capabilities buffer (Arg3), little-endian. Only the first `count() * 4` bytes are meaningful.
+    pub const fn capabilities_buffer(&self) -> [u8; OSC_MAX_DWORDS * 4] {
+        let mut out = [0; OSC_MAX_DWORDS * 4];
+        let mut i = 0;
+        while i < self.count {
+            let bytes = self.dwords[i].to_le_bytes();
+            out[i * 4] = bytes[0];
+            out[i * 4 + 1] = bytes[1];
+            out[i * 4 + 2] = bytes[2];
+            out[i * 4 + 3] = bytes[3];
+            i += 1;
+        }
+        out
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## _OSC Return Buffer
+pub struct OSCResponse {
+    dwords: [u32; OSC_MAX_DWORDS],
+    count: usize,
+}
+impl OSCResponse {
+    /// Decodes the buffer _OSC returned. Returns `None` if it isn't a whole number of DWORDs, is empty, or is longer than `OSC_MAX_DWORDS`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() || !bytes.len().is_multiple_of(4) || bytes.len() / 4 > OSC_MAX_DWORDS {
+            return None;
+        }
+        let mut dwords = [0; OSC_MAX_DWORDS];
+        for (dword, chunk) in dwords.iter_mut().zip(bytes.chunks_exact(4)) {
+            *dword = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(Self {
+            dwords,
+            count: bytes.len() / 4,
+        })
+    }
+    /// DWORD 1, with the platform's error bits.
+    pub const fn status(&self) -> OSCStatus {
+        OSCStatus(self.dwords[0])
+    }
+    /// The platform-wide capabilities the platform acknowledged.
+    pub const fn platform_wide(&self) -> PlatformWideCapabilities {
+        PlatformWideCapabilities(self.dwords[1])
+    }
+    /// The PCI support field as the platform echoed it.
+    pub const fn pci_support(&self) -> PCISupport {
+        PCISupport(self.dwords[1])
+    }
+    /// The PCI features the platform granted native control of.
+    pub const fn pci_control(&self) -> PCIControl {
+        PCIControl(self.dwords[2])
+    }
+    /// The returned buffer as DWORDs.
+    pub fn dwords(&self) -> &[u32] {
+        &self.dwords[..self.count]
+    }
+}
+
+/// Evaluates the _OSC method at `path` (e.g. "\\_SB.PCI0._OSC") with `request`, and decodes what it returns.
+///
+/// Returns `Ok(None)` if there's no _OSC there, or it didn't return a valid _OSC buffer.
+pub fn run_osc<E: AMLEvaluator>(
+    evaluator: &mut E,
+    path: &[u8],
+    request: &OSCRequest,
+) -> Result<Option<OSCResponse>, E::Error> {
+    let capabilities = request.capabilities_buffer();
+    let args = [
+        AMLArgument::Buffer(&request.uuid),
+        AMLArgument::Integer(request.revision as u64),
+        AMLArgument::Integer(request.count() as u64),
+        AMLArgument::Buffer(&capabilities[..request.count * 4]),
+    ];
+    let mut out = [0; OSC_MAX_DWORDS * 4];
+    match evaluator.evaluate_buffer(path, &args, &mut out)? {
+        Some(length) if length <= out.len() => Ok(OSCResponse::from_bytes(&out[..length])),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A host bridge _OSC that grants everything but AER, and knows nothing about other UUIDs.
+    struct HostBridge;
+    impl AMLEvaluator for HostBridge {
+        type Error = ();
+        fn evaluate(&mut self, _: &[u8], _: &[u64]) -> Result<Option<u64>, ()> {
+            Ok(None)
+        }
+        fn evaluate_buffer(
+            &mut self,
+            path: &[u8],
+            args: &[AMLArgument<'_>],
+            out: &mut [u8],
+        ) -> Result<Option<usize>, ()> {
+            if path != b"\\_SB.PCI0._OSC" {
+                return Ok(None);
+            }
+            let [
+                AMLArgument::Buffer(uuid),
+                AMLArgument::Integer(1),
+                AMLArgument::Integer(count),
+                AMLArgument::Buffer(capabilities),
+            ] = *args
+            else {
+                return Err(());
+            };
+            let mut dwords = [0; OSC_MAX_DWORDS];
+            for (dword, chunk) in dwords.iter_mut().zip(capabilities.chunks_exact(4)) {
+                *dword = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            }
+            if uuid != PCI_HOST_BRIDGE_UUID {
+                dwords[0] |= OSCStatus::FAILURE
+                    .union(OSCStatus::UNRECOGNIZED_UUID)
+                    .bits();
+            } else if dwords[2] & PCIControl::PCIE_AER.bits() != 0 {
+                dwords[0] |= OSCStatus::CAPABILITIES_MASKED.bits();
+                dwords[2] &= !PCIControl::PCIE_AER.bits();
+            }
+            for (chunk, dword) in out.chunks_exact_mut(4).zip(&dwords[..count as usize]) {
+                chunk.copy_from_slice(&dword.to_le_bytes());
+            }
+            Ok(Some(count as usize * 4))
+        }
+    }
+
+    #[test]
+    fn osc() {
+        let control = PCIControl::PCIE_NATIVE_HOT_PLUG.union(PCIControl::PCIE_AER);
+        let request = OSCRequest::pci_host_bridge(false, PCISupport::MSI, control);
+        let response = run_osc(&mut HostBridge, b"\\_SB.PCI0._OSC", &request)
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), OSCStatus::CAPABILITIES_MASKED);
+        assert_eq!(response.pci_support(), PCISupport::MSI);
+        assert_eq!(response.pci_control(), PCIControl::PCIE_NATIVE_HOT_PLUG);
+
+        let request = OSCRequest::platform_wide(true, PlatformWideCapabilities::APEI);
+        let response = run_osc(&mut HostBridge, b"\\_SB.PCI0._OSC", &request)
+            .unwrap()
+            .unwrap();
+        assert!(response.status().contains(OSCStatus::UNRECOGNIZED_UUID));
+        assert_eq!(response.dwords().len(), 2);
+
+        assert!(
+            run_osc(&mut HostBridge, b"\\_SB._OSC", &request)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,79 @@
+/// The Windows version strings firmware commonly tests with _OSI, oldest first.
+///
+/// Pretty much every x86 firmware out there branches on these, and a lot of them only enable features (like the newer thermal or backlight paths)
+/// when the newest string they know about answers true.  Answering all of them true is what everyone else does.
+pub const WINDOWS_OSI_STRINGS: &[&str] = &[
+    "Windows 2000",
+    "Windows 2001",
+    "Windows 2001 SP1",
+    "Windows 2001.1",
+    "Windows 2001 SP2",
+    "Windows 2001.1 SP1",
+    "Windows 2006",
+    "Windows 2006.1",
+    "Windows 2006 SP1",
+    "Windows 2006 SP2",
+    "Windows 2009",
+    "Windows 2012",
+    "Windows 2013",
+    "Windows 2015",
+    "Windows 2016",
+    "Windows 2017",
+    "Windows 2017.2",
+    "Windows 2018",
+    "Windows 2018.2",
+    "Windows 2019",
+    "Windows 2020",
+    "Windows 2021",
+    "Windows 2022",
+];
+
+/// The ACPI feature group strings defined by the spec.
+pub const FEATURE_GROUP_OSI_STRINGS: &[&str] = &[
+    "Module Device",
+    "Processor Device",
+    "3.0 Thermal Model",
+    "3.0 _SCP Extensions",
+    "Processor Aggregator Device",
+    "Extended Address Space Descriptor",
+];
+
+#[derive(Copy, Clone)]
+/// ## Operating System Interfaces (_OSI)
+///
+/// The set of interface strings OSPM answers true to when AML calls `\_OSI(Arg0)`.
+///
+/// The list is the OS's to configure: pass whatever slices of strings it wants to claim (the feature groups, some or all of the Windows strings, vendor strings...).
+pub struct OperatingSystemInterfaces<'a> {
+    interfaces: &'a [&'a [&'a str]],
+}
+impl<'a> OperatingSystemInterfaces<'a> {
+    /// Builds the list out of one or more string slices, e.g. `&[FEATURE_GROUP_OSI_STRINGS, WINDOWS_OSI_STRINGS]`.
+    pub const fn new(interfaces: &'a [&'a [&'a str]]) -> Self {
+        Self { interfaces }
+    }
+    /// Returns true if `interface` (the _OSI argument, without the null terminator) is one of the configured strings.
+    pub fn supports(&self, interface: &[u8]) -> bool {
+        self.iter().any(|s| s.as_bytes() == interface)
+    }
+    /// The value _OSI returns for `interface`: Ones if supported, Zero otherwise.
+    ///
+    /// `integer_64` is whether the interpreter uses 64-bit integers (DSDT revision 2 and up), which decides the width of Ones.
+    pub fn evaluate(&self, interface: &[u8], integer_64: bool) -> u64 {
+        match (self.supports(interface), integer_64) {
+            (false, _) => 0,
+            (true, true) => u64::MAX,
+            (true, false) => u32::MAX as u64,
+        }
+    }
+    /// Iterates over every configured string.
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.interfaces.iter().flat_map(|list| list.iter().copied())
+    }
+}
+impl Default for OperatingSystemInterfaces<'static> {
+    /// The feature group strings plus every known Windows string.
+    fn default() -> Self {
+        Self::new(&[FEATURE_GROUP_OSI_STRINGS, WINDOWS_OSI_STRINGS])
+    }
+}
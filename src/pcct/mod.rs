@@ -1,6 +1,15 @@
 pub mod subspace;
 
-use crate::SDTHeader;
+use crate::{
+    GenericAddressAccess, GenericAddressStructure, SDT_HEADER_SIZE, SDTHeader,
+    pcct::subspace::{
+        GenericCommunicationsChannelCommandField, GenericCommunicationsChannelStatusField,
+        extended::ExtendedPCC,
+        generic::GenericCommunications,
+        hw_reduced::{HWReducedCommunicationsType1, HWReducedCommunicationsType2},
+        hw_reg::HWRegistersBasedCommunications,
+    },
+};
 
 #[derive(Copy, Clone)]
 pub struct PCCGlobalFlags(u32);
@@ -30,3 +39,469 @@ pub struct PlatformCommunicationsChannel {
     /// A list of Platform Communications Channel Subspace structures for this platform. At most 256 subspaces are supported.
     pub pcc_subspace_structure: [u8; 0],
 }
+
+/// The number of status field polls a `PCCChannel` makes before giving up, unless told otherwise.
+pub const DEFAULT_POLL_LIMIT: u32 = 100_000;
+
+/// The signature of a subspace is a bitwise-or of this value with the subspace ID.
+pub const PCC_SIGNATURE: u32 = 0x50434300;
+
+#[derive(Copy, Clone)]
+/// A subspace structure, resolved by its type (`subspace::PCCSubspace` is the type and length header they all start with).
+pub enum PCCSubspaceKind<'a> {
+    Generic(&'a GenericCommunications),
+    HWReducedType1(&'a HWReducedCommunicationsType1),
+    HWReducedType2(&'a HWReducedCommunicationsType2),
+    /// Type 3 (master) or type 4 (slave).
+    Extended(&'a ExtendedPCC),
+    HWRegisters(&'a HWRegistersBasedCommunications),
+    /// A subspace type this library doesn't know about (or a reserved one), by its type.
+    Unknown(u8),
+}
+impl PCCSubspaceKind<'_> {
+    /// The subspace type, as in the subspace structure.
+    pub const fn subspace_type(&self) -> u8 {
+        match *self {
+            Self::Generic(subspace) => subspace.r#type,
+            Self::HWReducedType1(subspace) => subspace.r#type,
+            Self::HWReducedType2(subspace) => subspace.r#type,
+            Self::Extended(subspace) => subspace.r#type,
+            Self::HWRegisters(subspace) => subspace.r#type,
+            Self::Unknown(r#type) => r#type,
+        }
+    }
+}
+
+/// Iterator over the subspaces of a PCCT, in subspace ID order.
+pub struct PCCSubspaceIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    _pcct: core::marker::PhantomData<&'a PlatformCommunicationsChannel>,
+}
+impl<'a> Iterator for PCCSubspaceIter<'a> {
+    type Item = PCCSubspaceKind<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining < 2 {
+            return None;
+        }
+        // SAFETY: the type and length lie within the table, checked above.
+        let (r#type, length) = unsafe { (*self.next, *self.next.add(1) as usize) };
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < 2 || length > self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        let fits = |size: usize| length >= size;
+        // SAFETY: every subspace is checked against its length before the cast.
+        Some(unsafe {
+            match r#type {
+                0 if fits(core::mem::size_of::<GenericCommunications>()) => {
+                    PCCSubspaceKind::Generic(&*(ptr as *const _))
+                }
+                1 if fits(core::mem::size_of::<HWReducedCommunicationsType1>()) => {
+                    PCCSubspaceKind::HWReducedType1(&*(ptr as *const _))
+                }
+                2 if fits(core::mem::size_of::<HWReducedCommunicationsType2>()) => {
+                    PCCSubspaceKind::HWReducedType2(&*(ptr as *const _))
+                }
+                3 | 4 if fits(core::mem::size_of::<ExtendedPCC>()) => {
+                    PCCSubspaceKind::Extended(&*(ptr as *const _))
+                }
+                5 if fits(core::mem::size_of::<HWRegistersBasedCommunications>()) => {
+                    PCCSubspaceKind::HWRegisters(&*(ptr as *const _))
+                }
+                other => PCCSubspaceKind::Unknown(other),
+            }
+        })
+    }
+}
+
+impl PlatformCommunicationsChannel {
+    pub const fn subspaces(&self) -> PCCSubspaceIter<'_> {
+        PCCSubspaceIter {
+            next: (self as *const _ as *const u8).wrapping_add(SDT_HEADER_SIZE + 12),
+            remaining: (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 12),
+            _pcct: core::marker::PhantomData,
+        }
+    }
+    /// The subspace with the ID `id` (its index in the subspace list).
+    pub fn subspace(&self, id: u8) -> Option<PCCSubspaceKind<'_>> {
+        self.subspaces().nth(id as usize)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the PCC channel driver can run into.
+pub enum PCCError {
+    /// There's no subspace with this ID in the PCCT.
+    NoSuchSubspace(u8),
+    /// OSPM can't send commands on this subspace type with a `PCCChannel` (only types 0, 1 and 2 are supported).
+    UnsupportedSubspace(u8),
+    /// The command complete bit didn't get set within the poll limit.
+    Timeout,
+    /// The platform set the error bit of the status field.
+    CommandError,
+    /// An access past the end of the communication space.
+    OutOfBounds { offset: usize, length: usize },
+}
+
+/// ## PCC Channel Driver
+///
+/// Sends commands over a type 0, 1 or 2 subspace: these share the Generic Communications Channel Shared Memory Region layout and a doorbell register.
+/// Everything goes through `GenericAddressAccess` (the shared memory region is accessed as System Memory).
+///
+/// The driver polls for completion; it never waits on the platform interrupt, so the type 2 interrupt ack register isn't touched.
+/// Each table using PCC (MPST, RASF...) lays out the communication space its own way, and reads/writes it with `read`/`write` around `send`.
+pub struct PCCChannel<A: GenericAddressAccess> {
+    pub subspace_id: u8,
+    /// Base address of the shared memory region.
+    pub base_address: u64,
+    /// Length, in bytes, of the shared memory region.
+    pub memory_length: u64,
+    pub doorbell_register: GenericAddressStructure,
+    pub doorbell_preserve: u64,
+    pub doorbell_write: u64,
+    /// Written to the signature field before each command.
+    ///
+    /// Starts out as the subspace signature (0x50434300 | subspace ID); some tables (like the RASF) ask for their own.
+    pub signature: u32,
+    /// How many times to poll the status field before returning `PCCError::Timeout`.
+    pub poll_limit: u32,
+    access: A,
+}
+impl<A: GenericAddressAccess> PCCChannel<A> {
+    pub fn new(
+        pcct: &PlatformCommunicationsChannel,
+        subspace_id: u8,
+        access: A,
+    ) -> Result<Self, PCCError> {
+        let subspace = pcct
+            .subspace(subspace_id)
+            .ok_or(PCCError::NoSuchSubspace(subspace_id))?;
+        Self::from_subspace(subspace, subspace_id, access)
+    }
+    pub fn from_subspace(
+        subspace: PCCSubspaceKind<'_>,
+        subspace_id: u8,
+        access: A,
+    ) -> Result<Self, PCCError> {
+        let (base_address, memory_length, doorbell_register, doorbell_preserve, doorbell_write) =
+            match subspace {
+                PCCSubspaceKind::Generic(s) => (
+                    s.base_address,
+                    s.memory_length,
+                    s.doorbell_register,
+                    s.doorbell_preserve,
+                    s.doorbell_write,
+                ),
+                PCCSubspaceKind::HWReducedType1(s) => (
+                    s.base_address,
+                    s.memory_length,
+                    s.doorbell_register,
+                    s.doorbell_preserve,
+                    s.doorbell_write,
+                ),
+                PCCSubspaceKind::HWReducedType2(s) => (
+                    s.base_address,
+                    s.memory_length,
+                    s.doorbell_register,
+                    s.doorbell_preserve,
+                    s.doorbell_write,
+                ),
+                other => return Err(PCCError::UnsupportedSubspace(other.subspace_type())),
+            };
+        Ok(Self {
+            subspace_id,
+            base_address,
+            memory_length,
+            doorbell_register,
+            doorbell_preserve,
+            doorbell_write,
+            signature: PCC_SIGNATURE | subspace_id as u32,
+            poll_limit: DEFAULT_POLL_LIMIT,
+            access,
+        })
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.access
+    }
+
+    fn memory(&self, offset: u64, bytes: u8) -> GenericAddressStructure {
+        GenericAddressStructure {
+            address_space_id: 0,
+            reg_bit_width: bytes * 8,
+            reg_bit_offset: 0,
+            access_size: bytes.trailing_zeros() as u8 + 1,
+            address: self.base_address + offset,
+        }
+    }
+    /// Length, in bytes, of the communication space (the shared memory region past the signature, command and status fields).
+    pub const fn communication_space_length(&self) -> usize {
+        (self.memory_length as usize).saturating_sub(8)
+    }
+    fn check(&self, offset: usize, length: usize) -> Result<(), PCCError> {
+        match offset.checked_add(length) {
+            Some(end) if end <= self.communication_space_length() => Ok(()),
+            _ => Err(PCCError::OutOfBounds { offset, length }),
+        }
+    }
+    /// Reads `bytes` (1, 2, 4 or 8) bytes at `offset` in the communication space.
+    pub fn read(&mut self, offset: usize, bytes: u8) -> Result<u64, PCCError> {
+        self.check(offset, bytes as usize)?;
+        let reg = self.memory(8 + offset as u64, bytes);
+        Ok(self.access.read(&reg))
+    }
+    /// Writes the low `bytes` (1, 2, 4 or 8) bytes of `value` at `offset` in the communication space.
+    pub fn write(&mut self, offset: usize, bytes: u8, value: u64) -> Result<(), PCCError> {
+        self.check(offset, bytes as usize)?;
+        let reg = self.memory(8 + offset as u64, bytes);
+        self.access.write(&reg, value);
+        Ok(())
+    }
+
+    /// Reads the status field.
+    pub fn status(&mut self) -> GenericCommunicationsChannelStatusField {
+        let reg = self.memory(6, 2);
+        GenericCommunicationsChannelStatusField::new(self.access.read(&reg) as u16)
+    }
+    /// Waits for the platform to complete the previous command, so the communication space is OSPM's to write.
+    ///
+    /// Call it before filling in the communication space for a command: the platform may still be reading it for the last one.
+    pub fn wait_free(&mut self) -> Result<(), PCCError> {
+        self.wait_command_complete()?;
+        Ok(())
+    }
+    fn wait_command_complete(
+        &mut self,
+    ) -> Result<GenericCommunicationsChannelStatusField, PCCError> {
+        for _ in 0..self.poll_limit {
+            let status = self.status();
+            if status.command_complete() {
+                return Ok(status);
+            }
+            core::hint::spin_loop();
+        }
+        Err(PCCError::Timeout)
+    }
+    /// Sends `command` and waits for the platform to complete it.
+    ///
+    /// Waits for the channel to be free, writes the signature and command fields, clears the status field, rings the doorbell and polls the command complete bit.
+    /// Anything the command needs in the communication space goes in after `wait_free` and before this.
+    pub fn send(&mut self, command: u8) -> Result<(), PCCError> {
+        self.wait_command_complete()?;
+        let signature = self.memory(0, 4);
+        self.access.write(&signature, self.signature as u64);
+        let command_field = self.memory(4, 2);
+        let command = GenericCommunicationsChannelCommandField::new(command, false);
+        self.access.write(&command_field, command.bits() as u64);
+        let status = self.memory(6, 2);
+        self.access.write(&status, 0);
+
+        let doorbell = self.doorbell_register;
+        let value = (self.access.read(&doorbell) & self.doorbell_preserve) | self.doorbell_write;
+        self.access.write(&doorbell, value);
+
+        if self.wait_command_complete()?.error() {
+            return Err(PCCError::CommandError);
+        }
+        Ok(())
+    }
+}
+
+/// A simulated PCC subspace for testing the PCC users (MPST, RASF, RAS2) without hardware.
+#[cfg(test)]
+pub(crate) mod fake {
+    use super::*;
+
+    /// Where the simulated shared memory region starts.
+    pub(crate) const SHARED_MEMORY: u64 = 0x1000;
+    pub(crate) const SHARED_MEMORY_SIZE: usize = 256;
+    /// The doorbell is System I/O port 0; ringing it sets these bits, keeping these.
+    pub(crate) const DOORBELL_WRITE: u64 = 0x1;
+    pub(crate) const DOORBELL_PRESERVE: u64 = 0xF0;
+
+    /// The shared memory region and doorbell of a type 0 subspace, with the platform behind them.
+    pub(crate) struct FakePCC {
+        pub memory: [u8; SHARED_MEMORY_SIZE],
+        pub doorbell: u64,
+        pub doorbells: u32,
+        /// Status field reads that still show the previous command in flight.
+        pub in_flight: u32,
+        /// Set if OSPM wrote the shared memory region while a command was in flight.
+        pub clobbered: bool,
+        /// Runs a command (with the whole shared memory region), returning true if it failed.
+        pub platform: fn(&mut [u8; SHARED_MEMORY_SIZE]) -> bool,
+    }
+    impl FakePCC {
+        pub(crate) fn new(platform: fn(&mut [u8; SHARED_MEMORY_SIZE]) -> bool) -> Self {
+            let mut memory = [0; SHARED_MEMORY_SIZE];
+            // The last command is done.
+            memory[6] = 1;
+            Self {
+                memory,
+                doorbell: 0,
+                doorbells: 0,
+                in_flight: 0,
+                clobbered: false,
+                platform,
+            }
+        }
+        pub(crate) fn read_memory(&self, offset: usize, bytes: usize) -> u64 {
+            let mut value = [0; 8];
+            value[..bytes].copy_from_slice(&self.memory[offset..offset + bytes]);
+            u64::from_le_bytes(value)
+        }
+        pub(crate) fn write_memory(&mut self, offset: usize, bytes: usize, value: u64) {
+            self.memory[offset..offset + bytes].copy_from_slice(&value.to_le_bytes()[..bytes]);
+        }
+    }
+    impl GenericAddressAccess for FakePCC {
+        fn read(&mut self, gas: &GenericAddressStructure) -> u64 {
+            if gas.address_space_id == 0x01 {
+                return self.doorbell;
+            }
+            let offset = (gas.address - SHARED_MEMORY) as usize;
+            if offset == 6 && self.in_flight > 0 {
+                self.in_flight -= 1;
+                return 0;
+            }
+            self.read_memory(offset, gas.reg_bit_width as usize / 8)
+        }
+        fn write(&mut self, gas: &GenericAddressStructure, value: u64) {
+            if gas.address_space_id == 0x01 {
+                self.doorbell = value;
+                self.doorbells += 1;
+                let error = (self.platform)(&mut self.memory);
+                self.write_memory(6, 2, 1 | (error as u64) << 2);
+                return;
+            }
+            if self.in_flight > 0 {
+                self.clobbered = true;
+            }
+            self.write_memory(
+                (gas.address - SHARED_MEMORY) as usize,
+                gas.reg_bit_width as usize / 8,
+                value,
+            );
+        }
+    }
+
+    impl<A: GenericAddressAccess> PCCChannel<A> {
+        /// The register access behind the channel, to poke the simulated platform mid-test.
+        pub(crate) fn access_mut(&mut self) -> &mut A {
+            &mut self.access
+        }
+    }
+
+    /// A PCCT with a single type 0 subspace (ID 0) on the `FakePCC` shared memory region and doorbell.
+    pub(crate) fn pcct() -> [u64; 16] {
+        let mut table = [0u64; 16];
+        // SAFETY: the buffer is 128 bytes, and the table is 110.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(table.as_mut_ptr() as *mut u8, 128) };
+        bytes[..4].copy_from_slice(b"PCCT");
+        bytes[4..8].copy_from_slice(&110u32.to_le_bytes());
+        let subspace = &mut bytes[SDT_HEADER_SIZE + 12..];
+        subspace[0] = 0;
+        subspace[1] = 62;
+        subspace[8..16].copy_from_slice(&SHARED_MEMORY.to_le_bytes());
+        subspace[16..24].copy_from_slice(&(SHARED_MEMORY_SIZE as u64).to_le_bytes());
+        // Doorbell: System I/O port 0, 8 bits.
+        subspace[24] = 0x01;
+        subspace[25] = 8;
+        subspace[27] = 1;
+        subspace[36..44].copy_from_slice(&DOORBELL_PRESERVE.to_le_bytes());
+        subspace[44..52].copy_from_slice(&DOORBELL_WRITE.to_le_bytes());
+        table
+    }
+    pub(crate) fn table(buffer: &[u64; 16]) -> &PlatformCommunicationsChannel {
+        // SAFETY: the buffer is laid out like a PCCT, and aligned.
+        unsafe { &*(buffer.as_ptr() as *const PlatformCommunicationsChannel) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::*;
+    use super::*;
+
+    fn echo(memory: &mut [u8; SHARED_MEMORY_SIZE]) -> bool {
+        // Answers with the command code, in the first byte of the communication space.
+        memory[8] = memory[4];
+        false
+    }
+    fn fail(_: &mut [u8; SHARED_MEMORY_SIZE]) -> bool {
+        true
+    }
+
+    fn channel(platform: fn(&mut [u8; SHARED_MEMORY_SIZE]) -> bool) -> PCCChannel<FakePCC> {
+        let pcct = pcct();
+        PCCChannel::new(table(&pcct), 0, FakePCC::new(platform)).unwrap()
+    }
+
+    #[test]
+    fn send() {
+        let mut channel = channel(echo);
+        assert_eq!(channel.memory_length, SHARED_MEMORY_SIZE as u64);
+        channel.access.doorbell = 0xA6;
+        channel.send(0x42).unwrap();
+        assert_eq!(channel.read(0, 1), Ok(0x42));
+        let pcc = channel.into_inner();
+        assert_eq!(pcc.read_memory(0, 4), PCC_SIGNATURE as u64, "subspace 0");
+        assert_eq!(pcc.read_memory(4, 2), 0x42);
+        assert_eq!(pcc.doorbells, 1);
+        assert_eq!(pcc.doorbell, 0xA1);
+    }
+
+    #[test]
+    fn send_errors() {
+        let mut failing = channel(fail);
+        assert_eq!(failing.send(0x01), Err(PCCError::CommandError));
+
+        let mut busy = channel(echo);
+        busy.poll_limit = 5;
+        busy.access.in_flight = u32::MAX;
+        assert_eq!(busy.send(0x01), Err(PCCError::Timeout));
+        assert_eq!(busy.access.doorbells, 0);
+
+        let pcct = pcct();
+        assert_eq!(
+            PCCChannel::new(table(&pcct), 1, FakePCC::new(echo)).err(),
+            Some(PCCError::NoSuchSubspace(1))
+        );
+    }
+
+    #[test]
+    fn wait_free() {
+        let mut channel = channel(echo);
+        channel.access.in_flight = 3;
+        channel.wait_free().unwrap();
+        channel.write(0, 4, 0x1234).unwrap();
+        channel.send(0x01).unwrap();
+        assert!(!channel.access.clobbered);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut channel = channel(echo);
+        let end = SHARED_MEMORY_SIZE - 8;
+        assert_eq!(channel.read(end - 4, 4), Ok(0));
+        assert_eq!(
+            channel.read(end - 2, 4),
+            Err(PCCError::OutOfBounds {
+                offset: end - 2,
+                length: 4
+            })
+        );
+        assert_eq!(
+            channel.write(usize::MAX, 1, 0),
+            Err(PCCError::OutOfBounds {
+                offset: usize::MAX,
+                length: 1
+            })
+        );
+    }
+}
@@ -30,6 +30,12 @@ pub struct PCCSubspace {
 /// OSPM is responsible for populating this field before each command invocation.
 pub struct GenericCommunicationsChannelCommandField(u16);
 impl GenericCommunicationsChannelCommandField {
+    pub const fn new(command: u8, notify_on_completion: bool) -> Self {
+        Self(command as u16 | (notify_on_completion as u16) << 15)
+    }
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
     /// Command code to execute.
     /// 
     /// Command codes are application specific and defined by the consumer of this interface.
@@ -49,6 +55,12 @@ impl GenericCommunicationsChannelCommandField {
 /// ## Generic Communications Channel Status Field
 pub struct GenericCommunicationsChannelStatusField(u16);
 impl GenericCommunicationsChannelStatusField {
+    pub const fn new(value: u16) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
     /// If set, the platform has completed processing the last command.
     pub const fn command_complete(&self) -> bool {
         self.0 & 0b0001 != 0
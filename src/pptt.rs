@@ -0,0 +1,596 @@
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    madt::{giccpu_interface::GICCPUInterface, processor_local_x2apic::ProcessorLocalx2APIC},
+};
+
+/// The most nodes `PPTT::ancestors` follows from a node up to the root.  Real hierarchies are a handful of levels deep.
+pub const MAX_HIERARCHY_DEPTH: usize = 16;
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// The common beginning of every PPTT structure.
+pub struct PPTTStructureHeader {
+    /// - **0** - Processor Hierarchy Node Structure
+    /// - **1** - Cache Type Structure
+    /// - **2** - ID Structure
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u8,
+    /// Length of the structure in bytes.
+    pub length: u8,
+    reserved: u16,
+}
+
+#[derive(Copy, Clone)]
+pub struct ProcessorHierarchyNodeFlags(u32);
+impl ProcessorHierarchyNodeFlags {
+    /// Set if this node of the processor topology represents the boundary of a physical package.
+    pub const fn physical_package(&self) -> bool {
+        self.0 & 0b00001 != 0
+    }
+    /// Set if `acpi_processor_id` is valid.
+    ///
+    /// For leaf nodes, it's the _UID of a processor (matching the MADT); otherwise it's the _UID of a Processor Container.
+    pub const fn acpi_processor_id_valid(&self) -> bool {
+        self.0 & 0b00010 != 0
+    }
+    /// Set if this node is a thread of a core (revision 2 and up).
+    pub const fn processor_is_a_thread(&self) -> bool {
+        self.0 & 0b00100 != 0
+    }
+    /// Set if this node has no children (revision 2 and up).
+    pub const fn node_is_a_leaf(&self) -> bool {
+        self.0 & 0b01000 != 0
+    }
+    /// Set if all children of this node share an identical implementation (revision 2 and up).
+    pub const fn identical_implementation(&self) -> bool {
+        self.0 & 0b10000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Processor Hierarchy Node Structure
+///
+/// Describes one level of the processor hierarchy: a package, cluster, core, thread, or anything in between.
+/// Each node points at its parent, so the hierarchy is a tree with the processors (threads or cores) as leaves.
+pub struct ProcessorHierarchyNode {
+    /// 0 - Processor Hierarchy Node Structure
+    pub r#type: u8,
+    /// 20 + 4 * `number_of_private_resources`
+    pub length: u8,
+    reserved: u16,
+    pub flags: ProcessorHierarchyNodeFlags,
+    /// Offset of this node's parent from the start of the PPTT.  0 means this node has no parent.
+    pub parent: u32,
+    /// For leaf nodes, the ACPI Processor UID of the processor (the same as the one in the MADT GICC or x2APIC structure).
+    /// For non-leaf nodes, the _UID of the matching Processor Container, if one exists.
+    ///
+    /// Only valid if `flags.acpi_processor_id_valid()` is set.
+    pub acpi_processor_id: u32,
+    /// Number of resources private to this node.
+    pub number_of_private_resources: u32,
+    /// Offsets (from the start of the PPTT) of the Cache Type and ID structures private to this node.
+    pub private_resources: [u32; 0],
+}
+impl ProcessorHierarchyNode {
+    /// The offset (from the start of the PPTT) of this node's `index`th private resource.
+    ///
+    /// Nodes that follow an ID structure are only 2-byte aligned, so the array can't be handed out as a `&[u32]`; each entry is read on its own instead.
+    pub fn private_resource(&self, index: usize) -> Option<u32> {
+        if index >= self.number_of_private_resources as usize
+            || 20 + (index + 1) * 4 > self.length as usize
+        {
+            return None;
+        }
+        // SAFETY: the entry lies within the node's length, checked above.
+        unsafe {
+            Some(core::ptr::read_unaligned(
+                (self as *const _ as *const u8).add(20 + index * 4) as *const u32,
+            ))
+        }
+    }
+    /// The offsets of this node's private resources (the ones that fit in its length).
+    pub fn private_resources(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.number_of_private_resources as usize).map_while(move |i| self.private_resource(i))
+    }
+    pub const fn acpi_processor_id(&self) -> Option<u32> {
+        let flags = self.flags;
+        if flags.acpi_processor_id_valid() {
+            Some(self.acpi_processor_id)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheAllocationType {
+    ReadAllocate,
+    WriteAllocate,
+    ReadAndWriteAllocate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheKind {
+    Data,
+    Instruction,
+    Unified,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheWritePolicy {
+    WriteBack,
+    WriteThrough,
+}
+
+#[derive(Copy, Clone)]
+pub struct CacheTypeFlags(u32);
+impl CacheTypeFlags {
+    pub const fn size_valid(&self) -> bool {
+        self.0 & 0b00000001 != 0
+    }
+    pub const fn number_of_sets_valid(&self) -> bool {
+        self.0 & 0b00000010 != 0
+    }
+    pub const fn associativity_valid(&self) -> bool {
+        self.0 & 0b00000100 != 0
+    }
+    pub const fn allocation_type_valid(&self) -> bool {
+        self.0 & 0b00001000 != 0
+    }
+    pub const fn cache_type_valid(&self) -> bool {
+        self.0 & 0b00010000 != 0
+    }
+    pub const fn write_policy_valid(&self) -> bool {
+        self.0 & 0b00100000 != 0
+    }
+    pub const fn line_size_valid(&self) -> bool {
+        self.0 & 0b01000000 != 0
+    }
+    /// Revision 3 and up.
+    pub const fn cache_id_valid(&self) -> bool {
+        self.0 & 0b10000000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+pub struct CacheAttributes(u8);
+impl CacheAttributes {
+    pub const fn allocation_type(&self) -> CacheAllocationType {
+        match self.0 & 0b11 {
+            0 => CacheAllocationType::ReadAllocate,
+            1 => CacheAllocationType::WriteAllocate,
+            _ => CacheAllocationType::ReadAndWriteAllocate,
+        }
+    }
+    pub const fn cache_type(&self) -> CacheKind {
+        match (self.0 >> 2) & 0b11 {
+            0 => CacheKind::Data,
+            1 => CacheKind::Instruction,
+            _ => CacheKind::Unified,
+        }
+    }
+    pub const fn write_policy(&self) -> CacheWritePolicy {
+        if self.0 & 0b10000 != 0 {
+            CacheWritePolicy::WriteThrough
+        } else {
+            CacheWritePolicy::WriteBack
+        }
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Cache Type Structure
+///
+/// Describes one cache. A cache is listed as a private resource of the hierarchy node that owns it,
+/// and points at the next level of cache (L1 to L2, L2 to L3...) even if that one belongs to a node further up.
+///
+/// Every field is only meaningful if its bit in `flags` is set.
+pub struct CacheType {
+    /// 1 - Cache Type Structure
+    pub r#type: u8,
+    /// 24 (28 for revision 3 and up)
+    pub length: u8,
+    reserved: u16,
+    pub flags: CacheTypeFlags,
+    /// Offset of the next level of cache from the start of the PPTT.  0 means this is the last level.
+    pub next_level_of_cache: u32,
+    /// Size of the cache in bytes.
+    pub size: u32,
+    pub number_of_sets: u32,
+    /// Integer number of ways.
+    pub associativity: u8,
+    pub attributes: CacheAttributes,
+    /// Line size in bytes.
+    pub line_size: u16,
+    /// A unique, non-zero identifier for this cache (revision 3 and up).
+    ///
+    /// This field is only there if `length` is 28 or more; use `cache_id()`.
+    pub cache_id: u32,
+}
+impl CacheType {
+    pub const fn size(&self) -> Option<u32> {
+        let flags = self.flags;
+        if flags.size_valid() {
+            Some(self.size)
+        } else {
+            None
+        }
+    }
+    pub const fn number_of_sets(&self) -> Option<u32> {
+        let flags = self.flags;
+        if flags.number_of_sets_valid() {
+            Some(self.number_of_sets)
+        } else {
+            None
+        }
+    }
+    pub const fn associativity(&self) -> Option<u8> {
+        let flags = self.flags;
+        if flags.associativity_valid() {
+            Some(self.associativity)
+        } else {
+            None
+        }
+    }
+    pub const fn allocation_type(&self) -> Option<CacheAllocationType> {
+        let flags = self.flags;
+        if flags.allocation_type_valid() {
+            Some(self.attributes.allocation_type())
+        } else {
+            None
+        }
+    }
+    pub const fn cache_type(&self) -> Option<CacheKind> {
+        let flags = self.flags;
+        if flags.cache_type_valid() {
+            Some(self.attributes.cache_type())
+        } else {
+            None
+        }
+    }
+    pub const fn write_policy(&self) -> Option<CacheWritePolicy> {
+        let flags = self.flags;
+        if flags.write_policy_valid() {
+            Some(self.attributes.write_policy())
+        } else {
+            None
+        }
+    }
+    pub const fn line_size(&self) -> Option<u16> {
+        let flags = self.flags;
+        if flags.line_size_valid() {
+            Some(self.line_size)
+        } else {
+            None
+        }
+    }
+    pub const fn cache_id(&self) -> Option<u32> {
+        let flags = self.flags;
+        if self.length >= 28 && flags.cache_id_valid() {
+            Some(self.cache_id)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ID Structure
+///
+/// Identifies the implementation of a node (normally a physical package), in the same form as the SMBIOS processor ID.
+pub struct IDStructure {
+    /// 2 - ID Structure
+    pub r#type: u8,
+    /// 30
+    pub length: u8,
+    reserved: u16,
+    /// Vendor ID as ASCII (for example "ARMH").
+    pub vendor_id: [u8; 4],
+    /// Vendor specific value to identify the first level of the implementation.
+    pub level_1_id: u64,
+    /// Vendor specific value to identify the second level of the implementation.
+    pub level_2_id: u64,
+    pub major_revision: u16,
+    pub minor_revision: u16,
+    pub spin_revision: u16,
+}
+
+#[derive(Copy, Clone)]
+pub enum PPTTStructure<'a> {
+    ProcessorHierarchyNode(&'a ProcessorHierarchyNode),
+    CacheType(&'a CacheType),
+    ID(&'a IDStructure),
+    /// A structure type this library doesn't know about (or a reserved one).
+    Unknown(&'a PPTTStructureHeader),
+}
+
+#[derive(Copy, Clone)]
+/// ## Processor Topology
+///
+/// Where one processor sits in the hierarchy.
+///
+/// The core is the processor's leaf node, or its parent when the leaf is a thread.
+/// The package is the closest ancestor marked as a physical package, and the cluster is whatever sits directly between the core and the package.
+pub struct ProcessorTopology<'a> {
+    pub pptt: &'a PPTT,
+    pub thread: Option<&'a ProcessorHierarchyNode>,
+    pub core: &'a ProcessorHierarchyNode,
+    pub cluster: Option<&'a ProcessorHierarchyNode>,
+    pub package: Option<&'a ProcessorHierarchyNode>,
+}
+impl ProcessorTopology<'_> {
+    /// An ID for the package shared by every processor in it (the container's _UID if given, otherwise the node's offset in the table).
+    pub fn package_id(&self) -> Option<u32> {
+        self.package.map(|node| self.pptt.node_id(node))
+    }
+    /// An ID for the cluster shared by every processor in it.
+    pub fn cluster_id(&self) -> Option<u32> {
+        self.cluster.map(|node| self.pptt.node_id(node))
+    }
+    /// An ID for the core shared by all of its threads.
+    pub fn core_id(&self) -> u32 {
+        self.pptt.node_id(self.core)
+    }
+    /// The leaf node of the processor (the thread, or the core if it isn't threaded).
+    pub fn leaf(&self) -> &ProcessorHierarchyNode {
+        self.thread.unwrap_or(self.core)
+    }
+    /// The cache of `kind` at `level` (1 for L1...) seen by this processor.
+    pub fn cache(&self, level: u8, kind: CacheKind) -> Option<&CacheType> {
+        self.pptt.cache(self.leaf(), level, kind)
+    }
+    /// The number of cache levels seen by this processor.
+    pub fn cache_levels(&self) -> u8 {
+        self.pptt.cache_levels(self.leaf())
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Processor Properties Topology Table (PPTT)
+///
+/// Describes the topology of the processors (packages, clusters, cores and threads) and the caches each level of the hierarchy owns.
+///
+/// Processors are matched to the MADT through their ACPI Processor UID.
+pub struct PPTT {
+    /// - **Signature** - "PPTT"
+    /// - **Revision** - 3
+    pub header: SDTHeader,
+    /// A list of processor hierarchy node, cache type and ID structures.
+    pub processor_topology_structure: [u8; 0],
+}
+impl PPTT {
+    pub const fn structures(&self) -> PPTTStructureIter<'_> {
+        PPTTStructureIter {
+            pptt: self,
+            offset: SDT_HEADER_SIZE as u32,
+            done: false,
+        }
+    }
+    /// The structure at `offset` from the start of the table, as found in `parent`, `private_resources` and `next_level_of_cache`.
+    pub fn structure_at(&self, offset: u32) -> Option<PPTTStructure<'_>> {
+        let offset = offset as usize;
+        let end = self.header.length as usize;
+        let fits = |size: usize| offset.checked_add(size).is_some_and(|e| e <= end);
+        if offset < SDT_HEADER_SIZE || !fits(core::mem::size_of::<PPTTStructureHeader>()) {
+            return None;
+        }
+        let ptr = (self as *const _ as *const u8).wrapping_add(offset);
+        // SAFETY: the header lies within the table, checked above.
+        let header = unsafe { &*(ptr as *const PPTTStructureHeader) };
+        let length = header.length as usize;
+        if !fits(length) {
+            return None;
+        }
+        // SAFETY: the structure lies within the table (checked above), and is only cast to a type it's long enough for.
+        unsafe {
+            Some(match header.r#type {
+                0 if length >= core::mem::size_of::<ProcessorHierarchyNode>() => {
+                    PPTTStructure::ProcessorHierarchyNode(&*(ptr as *const _))
+                }
+                // Caches before revision 3 stop short of cache_id, which cache_id() checks for;
+                // the whole CacheType still has to lie within the table.
+                1 if length >= core::mem::offset_of!(CacheType, cache_id)
+                    && fits(core::mem::size_of::<CacheType>()) =>
+                {
+                    PPTTStructure::CacheType(&*(ptr as *const _))
+                }
+                2 if length >= core::mem::size_of::<IDStructure>() => {
+                    PPTTStructure::ID(&*(ptr as *const _))
+                }
+                _ => PPTTStructure::Unknown(header),
+            })
+        }
+    }
+    fn node_at(&self, offset: u32) -> Option<&ProcessorHierarchyNode> {
+        match self.structure_at(offset)? {
+            PPTTStructure::ProcessorHierarchyNode(node) => Some(node),
+            _ => None,
+        }
+    }
+    fn cache_at(&self, offset: u32) -> Option<&CacheType> {
+        match self.structure_at(offset)? {
+            PPTTStructure::CacheType(cache) => Some(cache),
+            _ => None,
+        }
+    }
+    /// The offset of `node` from the start of the table.
+    pub fn node_offset(&self, node: &ProcessorHierarchyNode) -> u32 {
+        (node as *const _ as usize - self as *const _ as usize) as u32
+    }
+    /// An ID for `node`: its ACPI Processor ID if valid, otherwise its offset in the table.
+    pub fn node_id(&self, node: &ProcessorHierarchyNode) -> u32 {
+        node.acpi_processor_id()
+            .unwrap_or_else(|| self.node_offset(node))
+    }
+    pub fn parent(&self, node: &ProcessorHierarchyNode) -> Option<&ProcessorHierarchyNode> {
+        match node.parent {
+            0 => None,
+            parent => self.node_at(parent),
+        }
+    }
+    /// `node` followed by each of its ancestors, up to the root (or `MAX_HIERARCHY_DEPTH` nodes, whichever comes first).
+    pub fn ancestors<'a>(
+        &'a self,
+        node: &'a ProcessorHierarchyNode,
+    ) -> impl Iterator<Item = &'a ProcessorHierarchyNode> + 'a {
+        // The depth cap keeps a looping parent chain from hanging us.
+        core::iter::successors(Some(node), move |&node| self.parent(node)).take(MAX_HIERARCHY_DEPTH)
+    }
+    /// The leaf node of the processor whose ACPI Processor UID is `uid`.
+    pub fn processor(&self, uid: u32) -> Option<&ProcessorHierarchyNode> {
+        self.structures().find_map(|s| match s {
+            PPTTStructure::ProcessorHierarchyNode(node)
+                if node.acpi_processor_id() == Some(uid) && !self.has_children(node) =>
+            {
+                Some(node)
+            }
+            _ => None,
+        })
+    }
+    fn has_children(&self, node: &ProcessorHierarchyNode) -> bool {
+        let flags = node.flags;
+        if flags.node_is_a_leaf() {
+            return false;
+        }
+        let offset = self.node_offset(node);
+        self.structures().any(
+            |s| matches!(s, PPTTStructure::ProcessorHierarchyNode(child) if child.parent == offset),
+        )
+    }
+    /// Builds the topology of the processor whose ACPI Processor UID is `uid`.
+    pub fn topology(&self, uid: u32) -> Option<ProcessorTopology<'_>> {
+        let leaf = self.processor(uid)?;
+        let flags = leaf.flags;
+        let (thread, core) = if flags.processor_is_a_thread() {
+            (Some(leaf), self.parent(leaf)?)
+        } else {
+            (None, leaf)
+        };
+        let package = self
+            .ancestors(core)
+            .find(|node| ({ node.flags }).physical_package());
+        let cluster = match (self.parent(core), package) {
+            (Some(parent), Some(package)) if core::ptr::eq(parent, package) => None,
+            (parent, _) => parent,
+        };
+        Some(ProcessorTopology {
+            pptt: self,
+            thread,
+            core,
+            cluster,
+            package,
+        })
+    }
+    /// Builds the topology of the processor described by a MADT GICC structure.
+    pub fn topology_of_gicc(&self, gicc: &GICCPUInterface) -> Option<ProcessorTopology<'_>> {
+        self.topology(gicc.acpi_processor_uid)
+    }
+    /// Builds the topology of the processor described by a MADT x2APIC structure.
+    pub fn topology_of_x2apic(
+        &self,
+        x2apic: &ProcessorLocalx2APIC,
+    ) -> Option<ProcessorTopology<'_>> {
+        self.topology(x2apic.acpi_processor_uid)
+    }
+    /// The cache of `kind` at `level` (1 for L1...) seen by the processor whose leaf node is `leaf`.
+    ///
+    /// Cache levels are counted along the `next_level_of_cache` chains that start at the private caches of the leaf and its ancestors,
+    /// so a private cache that is some other cache's next level keeps that level.
+    pub fn cache<'a>(
+        &'a self,
+        leaf: &'a ProcessorHierarchyNode,
+        level: u8,
+        kind: CacheKind,
+    ) -> Option<&'a CacheType> {
+        let mut found = None;
+        self.walk_caches(leaf, |cache_level, cache| {
+            if found.is_none() && cache_level == level && cache.cache_type() == Some(kind) {
+                found = Some(cache);
+            }
+        });
+        found
+    }
+    /// The number of cache levels seen by the processor whose leaf node is `leaf`.
+    pub fn cache_levels(&self, leaf: &ProcessorHierarchyNode) -> u8 {
+        let mut levels = 0;
+        self.walk_caches(leaf, |cache_level, _| levels = levels.max(cache_level));
+        levels
+    }
+    fn is_next_level_of_cache(&self, offset: u32) -> bool {
+        self.structures().any(
+            |s| matches!(s, PPTTStructure::CacheType(cache) if cache.next_level_of_cache == offset),
+        )
+    }
+    fn walk_caches<'a>(
+        &'a self,
+        leaf: &'a ProcessorHierarchyNode,
+        mut f: impl FnMut(u8, &'a CacheType),
+    ) {
+        for node in self.ancestors(leaf) {
+            for resource in node.private_resources() {
+                // A cache another cache points at is reached (at the right level) through that chain instead.
+                if self.is_next_level_of_cache(resource) {
+                    continue;
+                }
+                let mut level = 1u8;
+                let mut next = self.cache_at(resource);
+                while let Some(cache) = next {
+                    // The level cap keeps a looping chain from hanging us.
+                    if level > 16 {
+                        break;
+                    }
+                    f(level, cache);
+                    level += 1;
+                    next = match cache.next_level_of_cache {
+                        0 => None,
+                        offset => self.cache_at(offset),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the structures of a PPTT.
+pub struct PPTTStructureIter<'a> {
+    pptt: &'a PPTT,
+    offset: u32,
+    done: bool,
+}
+impl<'a> Iterator for PPTTStructureIter<'a> {
+    type Item = PPTTStructure<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Some(structure) = self.pptt.structure_at(self.offset) else {
+            self.done = true;
+            return None;
+        };
+        // SAFETY: structure_at checked that the header is inside the table.
+        let length = unsafe {
+            (*((self.pptt as *const _ as *const u8).add(self.offset as usize)
+                as *const PPTTStructureHeader))
+                .length
+        };
+        // A zero length would loop forever; treat it as the end of the list.
+        match self.offset.checked_add(length as u32) {
+            Some(next) if length != 0 => {
+                self.offset = next;
+                Some(structure)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
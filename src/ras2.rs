@@ -0,0 +1,397 @@
+use crate::{
+    GenericAddressAccess, SDT_HEADER_SIZE, SDTHeader,
+    pcct::{
+        PCCChannel, PlatformCommunicationsChannel,
+        subspace::{
+            GenericCommunicationsChannelCommandField, GenericCommunicationsChannelStatusField,
+        },
+    },
+    rasf::{
+        PATROL_SCRUB, ParameterBlockHeader, PatrolScrubCommand, RASCapabilities, RASCommandStatus,
+        RASFError, RASFeatureChannel,
+    },
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RAS2FeatureType {
+    Memory,
+    Vendor(u8),
+    Unknown(u8),
+}
+impl RAS2FeatureType {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0x00 => Self::Memory,
+            0x80..=0xFF => Self::Vendor(value),
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::Memory => 0x00,
+            Self::Vendor(other) | Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RAS2 Platform Communication Channel Descriptor
+///
+/// One PCC subspace, and the instance of a feature type it's for.
+pub struct RAS2PCCDescriptor {
+    /// ID of the PCC subspace.
+    pub pcc_id: u8,
+    reserved: u16,
+    pub feature_type: u8,
+    /// Which instance of the feature the subspace is for. For memory, it's the proximity domain (as in the SRAT) of the memory.
+    pub instance: u32,
+}
+impl RAS2PCCDescriptor {
+    pub const fn feature(&self) -> RAS2FeatureType {
+        RAS2FeatureType::from_u8(self.feature_type)
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## Platform RAS2 Features Bitmap
+pub struct RAS2Features(u128);
+impl RAS2Features {
+    pub const fn new(value: u128) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u128 {
+        self.0
+    }
+    /// Indicates that the platform supports hardware based patrol scrub of DRAM memory, driven through the PATROL_SCRUB parameter block.
+    pub const fn patrol_scrub(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the values are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RAS2 Platform Communication Channel Shared Memory Region
+///
+/// Same layout as the RASF one, but it uses the regular PCC signature (0x50434300 | subspace ID).
+pub struct RAS2CommunicationChannelSMR {
+    pub signature: u32,
+    /// PCC command field.
+    ///
+    /// Command value 0x01 will execute RAS2 command. The rest of the values are reserved.
+    pub command: GenericCommunicationsChannelCommandField,
+    /// PCC status field.
+    pub status: GenericCommunicationsChannelStatusField,
+    /// - **Byte 0** - Minor Version
+    /// - **Byte 1** - Major Version
+    pub version: u16,
+    /// The RAS features the platform supports.
+    pub features: RAS2Features,
+    /// The RAS features OSPM is invoking the command for.
+    pub set_capabilities: RAS2Features,
+    pub number_of_parameter_blocks: u16,
+    /// See `RASCommandStatus`.
+    pub set_capabilities_status: u32,
+    pub parameter_blocks: [u8; 0],
+}
+impl RAS2CommunicationChannelSMR {
+    pub const fn command_status(&self) -> RASCommandStatus {
+        RASCommandStatus::from_u32(self.set_capabilities_status)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct RAS2PatrolScrubFlags(u32);
+impl RAS2PatrolScrubFlags {
+    /// If set, the patrol scrubber is running on the actual address range.
+    pub const fn scrubber_running(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+/// ## Scrub Parameters (OUTPUT)
+///
+/// Scrub rates are in hours: the time for the scrubber to go over the whole range once.
+pub struct ScrubParametersOut(u32);
+impl ScrubParametersOut {
+    pub const fn current_scrub_rate(&self) -> u8 {
+        self.0 as u8
+    }
+    pub const fn min_scrub_rate(&self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+    pub const fn max_scrub_rate(&self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RAS2 Parameter Block Structure for PATROL_SCRUB
+pub struct RAS2PatrolScrubParameterBlock {
+    /// - **Type** - 0x0000
+    /// - **Version** - 0x0001
+    /// - **Length** - 52
+    pub header: ParameterBlockHeader,
+    /// INPUT: the command to run (see `PatrolScrubCommand`).
+    pub patrol_scrub_command: u16,
+    /// INPUT: the address range to run the command on.
+    ///
+    /// - **Bytes 7:0** - Base address
+    /// - **Bytes 15:8** - Size
+    pub requested_address_range: u128,
+    /// OUTPUT: the address range the platform actually runs the command on.
+    ///
+    /// - **Bytes 7:0** - Base address
+    /// - **Bytes 15:8** - Size
+    pub actual_address_range: u128,
+    /// OUTPUT
+    pub flags: RAS2PatrolScrubFlags,
+    /// OUTPUT
+    pub scrub_parameters_out: ScrubParametersOut,
+    /// INPUT
+    ///
+    /// - **Bit 0** - Enable background scrubbing
+    /// - **Bits 15:8** - Requested scrub rate, in hours
+    pub scrub_parameters_in: u32,
+}
+
+#[derive(Copy, Clone)]
+/// What the platform answered a RAS2 patrol scrub command with.
+pub struct RAS2PatrolScrubState {
+    /// Base address of the range the command actually ran on.
+    pub base_address: u64,
+    /// Size, in bytes, of the range the command actually ran on.
+    pub size: u64,
+    pub flags: RAS2PatrolScrubFlags,
+    pub parameters: ScrubParametersOut,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ACPI RAS2 Feature Table (RAS2)
+///
+/// The successor of the RASF: instead of one PCC subspace for everything, each instance of a RAS feature (the memory of each proximity domain, say) gets its own.
+pub struct RAS2 {
+    /// - **Signature** - "RAS2"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    reserved: u16,
+    pub number_of_pcc_descriptors: u16,
+    pub pcc_descriptors: [RAS2PCCDescriptor; 0],
+}
+impl RAS2 {
+    pub const fn pcc_descriptors(&self) -> &[RAS2PCCDescriptor] {
+        let fits = (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 4)
+            / core::mem::size_of::<RAS2PCCDescriptor>();
+        let count = self.number_of_pcc_descriptors as usize;
+        // SAFETY: the descriptors lie within the table's length, which bounds the count.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(SDT_HEADER_SIZE + 4)
+                    as *const RAS2PCCDescriptor,
+                if count < fits { count } else { fits },
+            )
+        }
+    }
+    /// The PCC descriptor of the memory in the proximity domain `proximity_domain`.
+    pub fn memory_descriptor(&self, proximity_domain: u32) -> Option<&RAS2PCCDescriptor> {
+        self.pcc_descriptors().iter().find(|descriptor| {
+            descriptor.feature() == RAS2FeatureType::Memory
+                && descriptor.instance == proximity_domain
+        })
+    }
+}
+
+/// ## RAS2 Patrol Scrub Driver
+///
+/// Runs PATROL_SCRUB commands on the memory of one proximity domain, over the PCC subspace of its RAS2 descriptor.
+pub struct RAS2PatrolScrubber<A: GenericAddressAccess> {
+    channel: RASFeatureChannel<A>,
+    block: usize,
+    /// The proximity domain whose memory this driver scrubs.
+    pub proximity_domain: u32,
+}
+impl<A: GenericAddressAccess> RAS2PatrolScrubber<A> {
+    /// Sets up the driver on the PCCT subspace of the memory in `proximity_domain`, and finds its PATROL_SCRUB parameter block.
+    pub fn new(
+        ras2: &RAS2,
+        pcct: &PlatformCommunicationsChannel,
+        proximity_domain: u32,
+        access: A,
+    ) -> Result<Self, RASFError> {
+        let descriptor = ras2
+            .memory_descriptor(proximity_domain)
+            .ok_or(RASFError::NotSupported)?;
+        let mut channel = RASFeatureChannel::new(PCCChannel::new(pcct, descriptor.pcc_id, access)?);
+        if !RAS2Features(channel.capabilities()?.bits()).patrol_scrub() {
+            return Err(RASFError::NotSupported);
+        }
+        let block = channel.parameter_block(PATROL_SCRUB)?;
+        Ok(Self {
+            channel,
+            block,
+            proximity_domain,
+        })
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.channel.into_inner().into_inner()
+    }
+    /// The feature channel the commands go through (to tune the PCC poll limit, for example).
+    pub fn channel(&mut self) -> &mut RASFeatureChannel<A> {
+        &mut self.channel
+    }
+
+    /// Runs `command` on the range at `base_address` of `size` bytes, with `scrub_parameters_in` (see `RAS2PatrolScrubParameterBlock`).
+    pub fn execute(
+        &mut self,
+        command: PatrolScrubCommand,
+        base_address: u64,
+        size: u64,
+        scrub_parameters_in: u32,
+    ) -> Result<RAS2PatrolScrubState, RASFError> {
+        let block = self.block;
+        let pcc = self.channel.channel();
+        pcc.wait_free()?;
+        pcc.write(block + 6, 2, command.as_u16() as u64)?;
+        pcc.write(block + 8, 8, base_address)?;
+        pcc.write(block + 16, 8, size)?;
+        pcc.write(block + 48, 4, scrub_parameters_in as u64)?;
+        self.channel.execute(RASCapabilities::new(0b1))?;
+        let pcc = self.channel.channel();
+        Ok(RAS2PatrolScrubState {
+            base_address: pcc.read(block + 24, 8)?,
+            size: pcc.read(block + 32, 8)?,
+            flags: RAS2PatrolScrubFlags(pcc.read(block + 40, 4)? as u32),
+            parameters: ScrubParametersOut(pcc.read(block + 44, 4)? as u32),
+        })
+    }
+    /// Gets the patrol scrub state of the range at `base_address` of `size` bytes.
+    pub fn get_parameters(
+        &mut self,
+        base_address: u64,
+        size: u64,
+    ) -> Result<RAS2PatrolScrubState, RASFError> {
+        self.execute(
+            PatrolScrubCommand::GetPatrolParameters,
+            base_address,
+            size,
+            0,
+        )
+    }
+    /// Starts the patrol scrubber on the range at `base_address` of `size` bytes, going over it once every `scrub_rate` hours.
+    ///
+    /// With `background` set, the scrubber runs in the background (only when the memory is idle).
+    pub fn start(
+        &mut self,
+        base_address: u64,
+        size: u64,
+        scrub_rate: u8,
+        background: bool,
+    ) -> Result<RAS2PatrolScrubState, RASFError> {
+        self.execute(
+            PatrolScrubCommand::StartPatrolScrubber,
+            base_address,
+            size,
+            (scrub_rate as u32) << 8 | background as u32,
+        )
+    }
+    pub fn stop(&mut self) -> Result<RAS2PatrolScrubState, RASFError> {
+        self.execute(PatrolScrubCommand::StopPatrolScrubber, 0, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pcct::{PCC_SIGNATURE, fake::*},
+        rasf::fake::*,
+    };
+
+    /// Scrubs whatever it's asked to at the requested rate, between 1 and 24 hours.
+    fn platform(memory: &mut [u8; SHARED_MEMORY_SIZE]) -> bool {
+        if read(memory, 0, 4) != PCC_SIGNATURE as u64 || read(memory, SET_CAPABILITIES, 8) != 0b1 {
+            return true;
+        }
+        let parameters_in = read(memory, BLOCK + 48, 4);
+        let rate = parameters_in >> 8 & 0xFF;
+        let status = match PatrolScrubCommand::from_u16(read(memory, BLOCK + 6, 2) as u16) {
+            PatrolScrubCommand::StartPatrolScrubber if rate == 0 || rate > 24 => {
+                RASCommandStatus::InvalidData
+            }
+            PatrolScrubCommand::StartPatrolScrubber => {
+                write(memory, BLOCK + 40, 4, 1);
+                write(memory, BLOCK + 44, 4, 24 << 16 | 1 << 8 | rate);
+                RASCommandStatus::Success
+            }
+            PatrolScrubCommand::StopPatrolScrubber => {
+                write(memory, BLOCK + 40, 4, 0);
+                RASCommandStatus::Success
+            }
+            _ => RASCommandStatus::Success,
+        };
+        let (base, size) = (read(memory, BLOCK + 8, 8), read(memory, BLOCK + 16, 8));
+        write(memory, BLOCK + 24, 8, base);
+        write(memory, BLOCK + 32, 8, size);
+        write(memory, STATUS, 4, status.as_u32() as u64);
+        false
+    }
+
+    /// A RAS2 with the memory of proximity domain 1 on PCC subspace 0.
+    fn ras2() -> [u64; 6] {
+        let mut buffer = [0u64; 6];
+        // SAFETY: the buffer is 48 bytes, exactly the table.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 48) };
+        bytes[..4].copy_from_slice(b"RAS2");
+        bytes[4..8].copy_from_slice(&48u32.to_le_bytes());
+        bytes[SDT_HEADER_SIZE + 2..][..2].copy_from_slice(&1u16.to_le_bytes());
+        bytes[SDT_HEADER_SIZE + 8..][..4].copy_from_slice(&1u32.to_le_bytes());
+        buffer
+    }
+
+    fn scrubber(
+        proximity_domain: u32,
+        capabilities: u64,
+    ) -> Result<RAS2PatrolScrubber<FakePCC>, RASFError> {
+        let buffer = ras2();
+        // SAFETY: the buffer is laid out like a RAS2, and aligned.
+        let ras2 = unsafe { &*(buffer.as_ptr() as *const RAS2) };
+        let pcc = pcc(platform, capabilities, 52);
+        RAS2PatrolScrubber::new(ras2, table(&pcct()), proximity_domain, pcc)
+    }
+
+    #[test]
+    fn patrol_scrub() {
+        let mut scrubber = scrubber(1, 0b1).unwrap();
+        let state = scrubber.start(0x8000_0000, 0x1000_0000, 12, true).unwrap();
+        assert_eq!((state.base_address, state.size), (0x8000_0000, 0x1000_0000));
+        assert!(state.flags.scrubber_running());
+        assert_eq!(state.parameters.current_scrub_rate(), 12);
+        assert_eq!(state.parameters.max_scrub_rate(), 24);
+        assert!(!scrubber.stop().unwrap().flags.scrubber_running());
+        assert!(matches!(
+            scrubber.start(0, 0, 48, false),
+            Err(RASFError::Status(RASCommandStatus::InvalidData))
+        ));
+    }
+
+    #[test]
+    fn not_supported() {
+        assert!(matches!(scrubber(2, 0b1), Err(RASFError::NotSupported)));
+        assert!(matches!(scrubber(1, 0b10), Err(RASFError::NotSupported)));
+    }
+
+    #[test]
+    fn waits_for_the_previous_command() {
+        let mut scrubber = scrubber(1, 0b1).unwrap();
+        scrubber.channel().channel().access_mut().in_flight = 3;
+        scrubber.get_parameters(0, 0).unwrap();
+        assert!(!scrubber.into_inner().clobbered);
+    }
+}
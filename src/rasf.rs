@@ -1,27 +1,210 @@
 use crate::{
-    SDTHeader,
-    pcct::subspace::{
-        GenericCommunicationsChannelCommandField, GenericCommunicationsChannelStatusField,
+    GenericAddressAccess, SDTHeader,
+    pcct::{
+        PCCChannel, PCCError, PlatformCommunicationsChannel,
+        subspace::{
+            GenericCommunicationsChannelCommandField, GenericCommunicationsChannelStatusField,
+        },
     },
 };
 
-//#[derive(Copy, Clone)]
-//#[repr(C, packed)]
-//pub struct ParameterBlock {
-//    pub r#type: u16,
-//    pub version: u16,
-//    pub length: u16,
-//    pub patrol_scrub_command: u16,
-//    pub requested_address_range: u128,
-//    pub actual_address_range: u128,
-//    pub flags: u16,
-//    pub requested_speed: u8,
-//}
+/// The signature OSPM writes to the RASF shared memory region ("RASF").
+pub const RASF_PCC_SIGNATURE: u32 = 0x52415346;
+/// The PCC command that executes the RAS command in the parameter blocks.
+pub const RAS_EXECUTE_COMMAND: u8 = 0x01;
+/// Parameter block type of the PATROL_SCRUB parameter block.
+pub const PATROL_SCRUB: u16 = 0x0000;
+
+// Offsets of the RASF (and RAS2) fields in the PCC communication space.
+const RAS_CAPABILITIES: usize = 2;
+const SET_RAS_CAPABILITIES: usize = 18;
+const NUMBER_OF_PARAMETER_BLOCKS: usize = 34;
+const SET_RAS_CAPABILITIES_STATUS: usize = 36;
+const PARAMETER_BLOCKS: usize = 40;
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Parameter Block Header
+///
+/// The start of every parameter block.
+pub struct ParameterBlockHeader {
+    /// - **0x0000** - PATROL_SCRUB
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u16,
+    pub version: u16,
+    /// Length, in bytes, of the whole parameter block.
+    pub length: u16,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PatrolScrubCommand {
+    /// Gets the patrol scrub state of the requested address range (OSPM can give 0 as the base and size to ask for the whole memory).
+    GetPatrolParameters,
+    /// Starts the patrol scrubber on the requested address range, at the requested speed.
+    StartPatrolScrubber,
+    StopPatrolScrubber,
+    Unknown(u16),
+}
+impl PatrolScrubCommand {
+    pub const fn from_u16(value: u16) -> Self {
+        match value {
+            1 => Self::GetPatrolParameters,
+            2 => Self::StartPatrolScrubber,
+            3 => Self::StopPatrolScrubber,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u16(&self) -> u16 {
+        match *self {
+            Self::GetPatrolParameters => 1,
+            Self::StartPatrolScrubber => 2,
+            Self::StopPatrolScrubber => 3,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PatrolScrubSpeed {
+    Slow,
+    Medium,
+    Fast,
+    Unknown(u8),
+}
+impl PatrolScrubSpeed {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0b000 => Self::Slow,
+            0b100 => Self::Medium,
+            0b111 => Self::Fast,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::Slow => 0b000,
+            Self::Medium => 0b100,
+            Self::Fast => 0b111,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct PatrolScrubFlags(u16);
+impl PatrolScrubFlags {
+    pub const fn new(value: u16) -> Self {
+        Self(value)
+    }
+    /// If set, the patrol scrubber is running on the actual address range.
+    pub const fn scrubber_running(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    /// The speed the patrol scrubber is running at, if `scrubber_running` is set.
+    pub const fn current_speed(&self) -> PatrolScrubSpeed {
+        PatrolScrubSpeed::from_u8(((self.0 >> 1) & 0b111) as u8)
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Parameter Block Structure for PATROL_SCRUB
+pub struct PatrolScrubParameterBlock {
+    /// - **Type** - 0x0000
+    /// - **Version** - 0x0001
+    /// - **Length** - 43
+    pub header: ParameterBlockHeader,
+    /// INPUT: the command to run (see `PatrolScrubCommand`).
+    pub patrol_scrub_command: u16,
+    /// INPUT: the address range to run the command on.
+    ///
+    /// - **Bytes 7:0** - Base address
+    /// - **Bytes 15:8** - Size
+    pub requested_address_range: u128,
+    /// OUTPUT: the address range the platform actually runs the command on (the requested one, rounded to what the scrubber can do).
+    ///
+    /// - **Bytes 7:0** - Base address
+    /// - **Bytes 15:8** - Size
+    pub actual_address_range: u128,
+    /// OUTPUT
+    pub flags: PatrolScrubFlags,
+    /// INPUT: bits 2:0 are the speed to run the scrubber at (see `PatrolScrubSpeed`) with `StartPatrolScrubber`.
+    pub requested_speed: u8,
+}
+impl PatrolScrubParameterBlock {
+    pub const fn command(&self) -> PatrolScrubCommand {
+        PatrolScrubCommand::from_u16(self.patrol_scrub_command)
+    }
+    /// The requested address range as (base address, size).
+    pub const fn requested_range(&self) -> (u64, u64) {
+        let range = self.requested_address_range;
+        (range as u64, (range >> 64) as u64)
+    }
+    /// The actual address range as (base address, size).
+    pub const fn actual_range(&self) -> (u64, u64) {
+        let range = self.actual_address_range;
+        (range as u64, (range >> 64) as u64)
+    }
+    pub const fn speed(&self) -> PatrolScrubSpeed {
+        PatrolScrubSpeed::from_u8(self.requested_speed & 0b111)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum RASFParameterBlock<'a> {
+    PatrolScrub(&'a PatrolScrubParameterBlock),
+    /// A parameter block type this library doesn't know about (or a reserved one).
+    Unknown(&'a ParameterBlockHeader),
+}
+
+/// Iterator over the parameter blocks of a RASF shared memory region.
+pub struct ParameterBlockIter<'a> {
+    next: *const u8,
+    count: u16,
+    remaining: usize,
+    _smr: core::marker::PhantomData<&'a RASFCommunicationChannelSMR>,
+}
+impl<'a> Iterator for ParameterBlockIter<'a> {
+    type Item = RASFParameterBlock<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let header_size = core::mem::size_of::<ParameterBlockHeader>();
+        if self.count == 0 || self.remaining < header_size {
+            return None;
+        }
+        // SAFETY: the header lies within the shared memory region, checked above.
+        let header = unsafe { &*(self.next as *const ParameterBlockHeader) };
+        let length = header.length as usize;
+        // A zero length would loop forever, and a longer one runs off the region; treat either as the end of the list.
+        if length < header_size || length > self.remaining {
+            self.count = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        self.count -= 1;
+        Some(match header.r#type {
+            PATROL_SCRUB if length >= core::mem::size_of::<PatrolScrubParameterBlock>() => {
+                // SAFETY: the block lies within the region (checked above), and is only cast if it's long enough.
+                RASFParameterBlock::PatrolScrub(unsafe { &*(ptr as *const _) })
+            }
+            _ => RASFParameterBlock::Unknown(header),
+        })
+    }
+}
 
 #[derive(Copy, Clone)]
 /// ## Platform RAS Capabilities Bitmap
 pub struct RASCapabilities(u128);
 impl RASCapabilities {
+    pub const fn new(value: u128) -> Self {
+        Self(value)
+    }
+    pub const fn bits(&self) -> u128 {
+        self.0
+    }
     /// Indicates that the platform supports hardware based patrol scrub of DRAM memory
     pub const fn hardware_based_patrol_scrub_support(&self) -> bool {
         self.0 & 0b01 != 0
@@ -42,7 +225,7 @@ pub struct RASFCommunicationChannelSMR {
     /// PCC command field.
     ///
     /// See the Platform Communications Channel (PCC).
-    /// 
+    ///
     /// Command value 0x01 will execute RASF command.  The rest of the values are reserved.
     pub command: GenericCommunicationsChannelCommandField,
     /// PCC status field.
@@ -69,19 +252,29 @@ pub struct RASFCommunicationChannelSMR {
     /// - **0b0001** - Not Valid
     /// - **0b0010** - Not Supported
     /// - **0b0011** - Busy
-    /// - **0b0100** - FailedF
+    /// - **0b0100** - Failed
     /// - **0b0101** - Aborted
     /// - **0b0110** - Invalid Data
     pub set_ras_capabilities_status: u32,
 }
 impl RASFCommunicationChannelSMR {
     /// Start of the parameter blocks, the structure of which is shown in the Parameter Block Structure for PATROL_SCRUB.
-    /// 
+    ///
     /// These parameter blocks are used as communication mailbox between the OSPM and the platform, and there is 1 parameter block for each RAS feature.
-    /// 
+    ///
     /// NOTE: There can be only on parameter block per type.
-    pub const fn parameter_blocks(&self) -> ! {
-        todo!()
+    ///
+    /// `memory_length` is the length of the shared memory region, from the PCC subspace; no block past it is handed out.
+    pub const fn parameter_blocks(&self, memory_length: u64) -> ParameterBlockIter<'_> {
+        ParameterBlockIter {
+            next: (self as *const _ as *const u8).wrapping_add(48),
+            count: self.rasf_parameter_block_num,
+            remaining: (memory_length as usize).saturating_sub(48),
+            _smr: core::marker::PhantomData,
+        }
+    }
+    pub const fn command_status(&self) -> RASCommandStatus {
+        RASCommandStatus::from_u32(self.set_ras_capabilities_status)
     }
 }
 
@@ -96,3 +289,354 @@ pub struct RASF {
     /// OSPM should use this value to identify the PCC Sub channel structure in the RASF table
     pub rasf_platform_communication_channel_id: [u8; 12],
 }
+impl RASF {
+    /// The ID of the PCC subspace the RASF commands go through.
+    pub const fn pcc_subspace_id(&self) -> u8 {
+        self.rasf_platform_communication_channel_id[0]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The Set RAS Capabilities Status field, after a command.
+pub enum RASCommandStatus {
+    Success,
+    NotValid,
+    NotSupported,
+    Busy,
+    Failed,
+    Aborted,
+    InvalidData,
+    Unknown(u32),
+}
+impl RASCommandStatus {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::Success,
+            1 => Self::NotValid,
+            2 => Self::NotSupported,
+            3 => Self::Busy,
+            4 => Self::Failed,
+            5 => Self::Aborted,
+            6 => Self::InvalidData,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u32(&self) -> u32 {
+        match *self {
+            Self::Success => 0,
+            Self::NotValid => 1,
+            Self::NotSupported => 2,
+            Self::Busy => 3,
+            Self::Failed => 4,
+            Self::Aborted => 5,
+            Self::InvalidData => 6,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Errors the RAS feature drivers (RASF and RAS2) can run into.
+pub enum RASFError {
+    PCC(PCCError),
+    /// The platform finished the command, but the Set RAS Capabilities Status isn't `Success`.
+    Status(RASCommandStatus),
+    /// The shared memory region has no parameter block of this type.
+    NoParameterBlock(u16),
+    /// The platform doesn't expose the feature to OSPM.
+    NotSupported,
+}
+impl From<PCCError> for RASFError {
+    fn from(value: PCCError) -> Self {
+        Self::PCC(value)
+    }
+}
+
+/// ## RAS Feature Channel
+///
+/// The part of the RASF and RAS2 shared memory regions that's the same for every feature: the capabilities bitmaps, the parameter blocks and the command status.
+/// Feature drivers (like `PatrolScrubber`) wait for the channel to be free (`channel().wait_free()`), fill in their parameter block, then `execute`.
+pub struct RASFeatureChannel<A: GenericAddressAccess> {
+    channel: PCCChannel<A>,
+}
+impl<A: GenericAddressAccess> RASFeatureChannel<A> {
+    pub fn new(channel: PCCChannel<A>) -> Self {
+        Self { channel }
+    }
+    /// Gives back the PCC channel this driver was built with.
+    pub fn into_inner(self) -> PCCChannel<A> {
+        self.channel
+    }
+    /// The PCC channel the commands go through (to read and write the parameter blocks, for example).
+    pub fn channel(&mut self) -> &mut PCCChannel<A> {
+        &mut self.channel
+    }
+
+    fn read_u128(&mut self, offset: usize) -> Result<u128, PCCError> {
+        let low = self.channel.read(offset, 8)? as u128;
+        let high = self.channel.read(offset + 8, 8)? as u128;
+        Ok(high << 64 | low)
+    }
+    fn write_u128(&mut self, offset: usize, value: u128) -> Result<(), PCCError> {
+        self.channel.write(offset, 8, value as u64)?;
+        self.channel.write(offset + 8, 8, (value >> 64) as u64)
+    }
+    /// The RAS capabilities (or features, for RAS2) bitmap the platform reports.
+    pub fn capabilities(&mut self) -> Result<RASCapabilities, RASFError> {
+        Ok(RASCapabilities(self.read_u128(RAS_CAPABILITIES)?))
+    }
+    /// Finds the parameter block of type `r#type`, returning its offset in the communication space.
+    pub fn parameter_block(&mut self, r#type: u16) -> Result<usize, RASFError> {
+        let count = self.channel.read(NUMBER_OF_PARAMETER_BLOCKS, 2)?;
+        let mut offset = PARAMETER_BLOCKS;
+        for _ in 0..count {
+            let block_type = self.channel.read(offset, 2)? as u16;
+            let length = self.channel.read(offset + 4, 2)? as usize;
+            if block_type == r#type {
+                return Ok(offset);
+            }
+            // A zero length would loop forever; treat it as the end of the list.
+            if length == 0 {
+                break;
+            }
+            offset += length;
+        }
+        Err(RASFError::NoParameterBlock(r#type))
+    }
+    /// Runs the commands in the parameter blocks of the features in `set_capabilities`, and checks the Set RAS Capabilities Status.
+    pub fn execute(&mut self, set_capabilities: RASCapabilities) -> Result<(), RASFError> {
+        self.write_u128(SET_RAS_CAPABILITIES, set_capabilities.0)?;
+        self.channel.send(RAS_EXECUTE_COMMAND)?;
+        match RASCommandStatus::from_u32(self.channel.read(SET_RAS_CAPABILITIES_STATUS, 4)? as u32)
+        {
+            RASCommandStatus::Success => Ok(()),
+            status => Err(RASFError::Status(status)),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+/// What the platform answered a patrol scrub command with.
+pub struct PatrolScrubState {
+    /// Base address of the range the command actually ran on.
+    pub base_address: u64,
+    /// Size, in bytes, of the range the command actually ran on.
+    pub size: u64,
+    pub flags: PatrolScrubFlags,
+}
+
+/// ## RASF Patrol Scrub Driver
+///
+/// Runs PATROL_SCRUB commands over the RASF PCC subspace.
+///
+/// A command runs on the capability whose bit OSPM sets in Set RAS Capabilities,
+/// and the one exposed to software for patrol scrub is bit 1 (bit 0 only says the hardware scrubs on its own).
+pub struct PatrolScrubber<A: GenericAddressAccess> {
+    channel: RASFeatureChannel<A>,
+    block: usize,
+}
+impl<A: GenericAddressAccess> PatrolScrubber<A> {
+    /// Sets up the driver on the PCCT subspace the RASF points to, and finds the PATROL_SCRUB parameter block.
+    pub fn new(
+        rasf: &RASF,
+        pcct: &PlatformCommunicationsChannel,
+        access: A,
+    ) -> Result<Self, RASFError> {
+        let mut channel = PCCChannel::new(pcct, rasf.pcc_subspace_id(), access)?;
+        channel.signature = RASF_PCC_SIGNATURE;
+        let mut channel = RASFeatureChannel::new(channel);
+        if !channel
+            .capabilities()?
+            .hardware_based_patrol_scrub_support_and_exposed_to_software()
+        {
+            return Err(RASFError::NotSupported);
+        }
+        let block = channel.parameter_block(PATROL_SCRUB)?;
+        Ok(Self { channel, block })
+    }
+    /// Gives back the register access this driver was built with.
+    pub fn into_inner(self) -> A {
+        self.channel.into_inner().into_inner()
+    }
+    /// The feature channel the commands go through (to tune the PCC poll limit, for example).
+    pub fn channel(&mut self) -> &mut RASFeatureChannel<A> {
+        &mut self.channel
+    }
+
+    /// Runs `command` on the range at `base_address` of `size` bytes, at `speed`.
+    pub fn execute(
+        &mut self,
+        command: PatrolScrubCommand,
+        base_address: u64,
+        size: u64,
+        speed: PatrolScrubSpeed,
+    ) -> Result<PatrolScrubState, RASFError> {
+        let block = self.block;
+        let pcc = self.channel.channel();
+        pcc.wait_free()?;
+        pcc.write(block + 6, 2, command.as_u16() as u64)?;
+        pcc.write(block + 8, 8, base_address)?;
+        pcc.write(block + 16, 8, size)?;
+        pcc.write(block + 42, 1, speed.as_u8() as u64)?;
+        self.channel.execute(RASCapabilities(0b10))?;
+        let pcc = self.channel.channel();
+        Ok(PatrolScrubState {
+            base_address: pcc.read(block + 24, 8)?,
+            size: pcc.read(block + 32, 8)?,
+            flags: PatrolScrubFlags(pcc.read(block + 40, 2)? as u16),
+        })
+    }
+    /// Gets the patrol scrub state of the range at `base_address` of `size` bytes (0 and 0 for the whole memory).
+    pub fn get_parameters(
+        &mut self,
+        base_address: u64,
+        size: u64,
+    ) -> Result<PatrolScrubState, RASFError> {
+        self.execute(
+            PatrolScrubCommand::GetPatrolParameters,
+            base_address,
+            size,
+            PatrolScrubSpeed::Slow,
+        )
+    }
+    /// Starts the patrol scrubber on the range at `base_address` of `size` bytes.
+    pub fn start(
+        &mut self,
+        base_address: u64,
+        size: u64,
+        speed: PatrolScrubSpeed,
+    ) -> Result<PatrolScrubState, RASFError> {
+        self.execute(
+            PatrolScrubCommand::StartPatrolScrubber,
+            base_address,
+            size,
+            speed,
+        )
+    }
+    pub fn stop(&mut self) -> Result<PatrolScrubState, RASFError> {
+        self.execute(
+            PatrolScrubCommand::StopPatrolScrubber,
+            0,
+            0,
+            PatrolScrubSpeed::Slow,
+        )
+    }
+}
+
+/// A simulated RASF/RAS2 shared memory region, on top of `pcct::fake`.
+#[cfg(test)]
+pub(crate) mod fake {
+    use super::*;
+    use crate::pcct::fake::*;
+
+    // Where things are in the simulated shared memory region.
+    pub(crate) const SET_CAPABILITIES: usize = 8 + SET_RAS_CAPABILITIES;
+    pub(crate) const STATUS: usize = 8 + SET_RAS_CAPABILITIES_STATUS;
+    /// The PATROL_SCRUB parameter block.
+    pub(crate) const BLOCK: usize = 8 + PARAMETER_BLOCKS;
+
+    pub(crate) fn read(memory: &[u8], offset: usize, bytes: usize) -> u64 {
+        let mut value = [0; 8];
+        value[..bytes].copy_from_slice(&memory[offset..offset + bytes]);
+        u64::from_le_bytes(value)
+    }
+    pub(crate) fn write(memory: &mut [u8], offset: usize, bytes: usize, value: u64) {
+        memory[offset..offset + bytes].copy_from_slice(&value.to_le_bytes()[..bytes]);
+    }
+
+    /// A shared memory region with `capabilities` and a PATROL_SCRUB parameter block of `length` bytes.
+    pub(crate) fn pcc(
+        platform: fn(&mut [u8; SHARED_MEMORY_SIZE]) -> bool,
+        capabilities: u64,
+        length: u64,
+    ) -> FakePCC {
+        let mut pcc = FakePCC::new(platform);
+        write(&mut pcc.memory, 8 + RAS_CAPABILITIES, 8, capabilities);
+        write(&mut pcc.memory, 8 + NUMBER_OF_PARAMETER_BLOCKS, 2, 1);
+        write(&mut pcc.memory, BLOCK + 2, 2, 1);
+        write(&mut pcc.memory, BLOCK + 4, 2, length);
+        pcc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::*;
+    use super::*;
+    use crate::pcct::fake::*;
+
+    /// Scrubs whatever it's asked to, refusing an empty range.
+    fn platform(memory: &mut [u8; SHARED_MEMORY_SIZE]) -> bool {
+        if read(memory, 0, 4) != RASF_PCC_SIGNATURE as u64
+            || memory[4] != RAS_EXECUTE_COMMAND
+            || read(memory, SET_CAPABILITIES, 8) != 0b10
+        {
+            return true;
+        }
+        let (base, size) = (read(memory, BLOCK + 8, 8), read(memory, BLOCK + 16, 8));
+        let speed = read(memory, BLOCK + 42, 1);
+        let status = match PatrolScrubCommand::from_u16(read(memory, BLOCK + 6, 2) as u16) {
+            PatrolScrubCommand::StartPatrolScrubber if size == 0 => RASCommandStatus::InvalidData,
+            PatrolScrubCommand::StartPatrolScrubber => {
+                write(memory, BLOCK + 40, 2, 1 | speed << 1);
+                RASCommandStatus::Success
+            }
+            PatrolScrubCommand::StopPatrolScrubber => {
+                write(memory, BLOCK + 40, 2, 0);
+                RASCommandStatus::Success
+            }
+            _ => RASCommandStatus::Success,
+        };
+        write(memory, BLOCK + 24, 8, base);
+        write(memory, BLOCK + 32, 8, size);
+        write(memory, STATUS, 4, status.as_u32() as u64);
+        false
+    }
+
+    fn scrubber(pcc: FakePCC) -> Result<PatrolScrubber<FakePCC>, RASFError> {
+        let mut buffer = [0u64; 6];
+        // SAFETY: the buffer is 48 bytes, exactly a RASF on PCC subspace 0.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 48) };
+        bytes[..4].copy_from_slice(b"RASF");
+        bytes[4..8].copy_from_slice(&48u32.to_le_bytes());
+        // SAFETY: the buffer is laid out like a RASF, and aligned.
+        let rasf = unsafe { &*(buffer.as_ptr() as *const RASF) };
+        PatrolScrubber::new(rasf, table(&pcct()), pcc)
+    }
+
+    #[test]
+    fn patrol_scrub() {
+        let mut scrubber = scrubber(pcc(platform, 0b10, 43)).unwrap();
+        let state = scrubber
+            .start(0x1_0000_0000, 0x4000_0000, PatrolScrubSpeed::Medium)
+            .unwrap();
+        assert_eq!(
+            (state.base_address, state.size),
+            (0x1_0000_0000, 0x4000_0000)
+        );
+        assert!(state.flags.scrubber_running());
+        assert_eq!(state.flags.current_speed(), PatrolScrubSpeed::Medium);
+        assert!(!scrubber.stop().unwrap().flags.scrubber_running());
+        assert!(matches!(
+            scrubber.start(0, 0, PatrolScrubSpeed::Fast),
+            Err(RASFError::Status(RASCommandStatus::InvalidData))
+        ));
+    }
+
+    #[test]
+    fn not_supported() {
+        // Only hardware-based patrol scrub, not exposed to software.
+        assert!(matches!(
+            scrubber(pcc(platform, 0b01, 43)),
+            Err(RASFError::NotSupported)
+        ));
+    }
+
+    #[test]
+    fn waits_for_the_previous_command() {
+        let mut scrubber = scrubber(pcc(platform, 0b10, 43)).unwrap();
+        scrubber.channel().channel().access_mut().in_flight = 3;
+        scrubber.get_parameters(0, 0).unwrap();
+        assert!(!scrubber.into_inner().clobbered);
+    }
+}
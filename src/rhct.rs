@@ -0,0 +1,327 @@
+use crate::SDTHeader;
+
+#[derive(Copy, Clone)]
+pub struct RHCTFlags(u32);
+impl RHCTFlags {
+    /// If set, the timer interrupt can't wake the hart up from a low power (idle) state.
+    pub const fn timer_cannot_wake_up_cpu(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct RHCTNodeHeader {
+    /// - **0x0000** - ISA String Node
+    /// - **0x0001** - CMO Node
+    /// - **0x0002** - MMU Node
+    /// - **0xFFFF** - Hart Info Node
+    ///
+    /// The rest of the values are reserved.
+    pub r#type: u16,
+    /// Length, in bytes, of the whole node.
+    pub length: u16,
+    pub revision: u16,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## ISA String Node
+///
+/// The ISA string of the harts pointing to this node, like the riscv,isa devicetree property ("rv64imafdc_zicsr_zifencei...").
+pub struct ISAStringNode {
+    /// - **Type** - 0x0000
+    /// - **Revision** - 1
+    pub header: RHCTNodeHeader,
+    /// Length, in bytes, of the ISA string (with its null terminator).
+    pub isa_length: u16,
+    pub isa_string: [u8; 0],
+}
+impl ISAStringNode {
+    /// The ISA string, without its null terminator (cut short at the node's length).
+    pub fn isa_string(&self) -> &[u8] {
+        let fits = (self.header.length as usize).saturating_sub(8);
+        // SAFETY: the string lies within the node's length, which `RHCTNode::from_ptr`'s callers checked against the table.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(8),
+                (self.isa_length as usize).min(fits),
+            )
+        };
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        &bytes[..len]
+    }
+    /// Returns true if the ISA string has the (multi-letter, like "zicbom") extension `extension`.
+    ///
+    /// Single-letter extensions are glued to the base ("rv64imafdc"), so look for those in the letters after "rv32"/"rv64" instead.
+    pub fn has_extension(&self, extension: &[u8]) -> bool {
+        self.isa_string()
+            .split(|&b| b == b'_')
+            .skip(1)
+            .any(|e| e.eq_ignore_ascii_case(extension))
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CMO Node
+///
+/// The cache block sizes of the Zicbom, Zicbop and Zicboz (cache management operation) extensions.
+pub struct CMONode {
+    /// - **Type** - 0x0001
+    /// - **Revision** - 1
+    pub header: RHCTNodeHeader,
+    reserved: u8,
+    /// Log2 of the cache block size of the Zicbom (cache block management) instructions.
+    pub cbom_block_size: u8,
+    /// Log2 of the cache block size of the Zicbop (cache block prefetch) instructions.
+    pub cbop_block_size: u8,
+    /// Log2 of the cache block size of the Zicboz (cache block zero) instructions.
+    pub cboz_block_size: u8,
+}
+impl CMONode {
+    /// The cache block size of the Zicbom instructions, in bytes.
+    pub const fn cbom_block_size_bytes(&self) -> usize {
+        1 << self.cbom_block_size
+    }
+    /// The cache block size of the Zicbop instructions, in bytes.
+    pub const fn cbop_block_size_bytes(&self) -> usize {
+        1 << self.cbop_block_size
+    }
+    /// The cache block size of the Zicboz instructions, in bytes.
+    pub const fn cboz_block_size_bytes(&self) -> usize {
+        1 << self.cboz_block_size
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MMUType {
+    Sv39,
+    Sv48,
+    Sv57,
+    Unknown(u8),
+}
+impl MMUType {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Sv39,
+            1 => Self::Sv48,
+            2 => Self::Sv57,
+            other => Self::Unknown(other),
+        }
+    }
+    pub const fn as_u8(&self) -> u8 {
+        match *self {
+            Self::Sv39 => 0,
+            Self::Sv48 => 1,
+            Self::Sv57 => 2,
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## MMU Node
+pub struct MMUNode {
+    /// - **Type** - 0x0002
+    /// - **Revision** - 1
+    pub header: RHCTNodeHeader,
+    reserved: u8,
+    /// The largest virtual memory scheme (satp mode) the harts support.
+    pub mmu_type: u8,
+}
+impl MMUNode {
+    pub const fn mmu(&self) -> MMUType {
+        MMUType::from_u8(self.mmu_type)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Hart Info Node
+///
+/// Ties one hart (by ACPI Processor UID) to the nodes describing it.
+pub struct HartInfoNode {
+    /// - **Type** - 0xFFFF
+    /// - **Revision** - 1
+    pub header: RHCTNodeHeader,
+    pub number_of_offsets: u16,
+    /// The ACPI Processor UID of the hart, as in its RINTC structure.
+    pub acpi_processor_uid: u32,
+    pub offsets: [u32; 0],
+}
+impl HartInfoNode {
+    /// Offsets, from the start of the RHCT, of the nodes describing the hart (the ones that fit in the node's length).
+    pub const fn offsets(&self) -> &[u32] {
+        let fits = (self.header.length as usize).saturating_sub(12) / 4;
+        let count = if (self.number_of_offsets as usize) < fits {
+            self.number_of_offsets as usize
+        } else {
+            fits
+        };
+        // SAFETY: the offsets lie within the node's length, which bounds the count, and `RHCTNode::from_ptr`'s callers checked that length against the table.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(12) as *const u32,
+                count,
+            )
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum RHCTNode<'a> {
+    ISAString(&'a ISAStringNode),
+    CMO(&'a CMONode),
+    MMU(&'a MMUNode),
+    HartInfo(&'a HartInfoNode),
+    /// A node type this library doesn't know about (or a reserved one).
+    Unknown(&'a RHCTNodeHeader),
+}
+impl<'a> RHCTNode<'a> {
+    /// Reads the node at `ptr`, which is `length` bytes long.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` has to point to a node, with `length` readable bytes, and `length` has to be at least the header's size.
+    unsafe fn from_ptr(ptr: *const u8, length: usize) -> Self {
+        // SAFETY: the header lies within the `length` readable bytes, as the caller promises.
+        let header = unsafe { &*(ptr as *const RHCTNodeHeader) };
+        let fits = |size: usize| length >= size;
+        // SAFETY: the caller promises `length` readable bytes, and every node is checked against its length before the cast.
+        unsafe {
+            match header.r#type {
+                0x0000 if fits(core::mem::size_of::<ISAStringNode>()) => {
+                    RHCTNode::ISAString(&*(ptr as *const _))
+                }
+                0x0001 if fits(core::mem::size_of::<CMONode>()) => {
+                    RHCTNode::CMO(&*(ptr as *const _))
+                }
+                0x0002 if fits(core::mem::size_of::<MMUNode>()) => {
+                    RHCTNode::MMU(&*(ptr as *const _))
+                }
+                0xFFFF if fits(core::mem::size_of::<HartInfoNode>()) => {
+                    RHCTNode::HartInfo(&*(ptr as *const _))
+                }
+                _ => RHCTNode::Unknown(header),
+            }
+        }
+    }
+}
+
+/// Iterator over the nodes of an RHCT.
+pub struct RHCTNodeIter<'a> {
+    next: *const u8,
+    remaining: usize,
+    count: u32,
+    _rhct: core::marker::PhantomData<&'a RHCT>,
+}
+impl<'a> Iterator for RHCTNodeIter<'a> {
+    type Item = RHCTNode<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 || self.remaining < core::mem::size_of::<RHCTNodeHeader>() {
+            return None;
+        }
+        // SAFETY: the header lies within the table, checked above.
+        let length = unsafe { (*(self.next as *const RHCTNodeHeader)).length as usize };
+        // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+        if length < core::mem::size_of::<RHCTNodeHeader>() || length > self.remaining {
+            self.count = 0;
+            return None;
+        }
+        let ptr = self.next;
+        self.next = self.next.wrapping_add(length);
+        self.remaining -= length;
+        self.count -= 1;
+        // SAFETY: the node lies within the table and covers its header, checked above.
+        Some(unsafe { RHCTNode::from_ptr(ptr, length) })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## RISC-V Hart Capabilities Table (RHCT)
+///
+/// Describes what the RISC-V harts can do (ISA string, cache management operations, MMU) that can't be discovered at runtime, and the timebase frequency.
+/// Each hart gets a Hart Info node pointing to the nodes that describe it, so harts that are the same can share them.
+pub struct RHCT {
+    /// - **Signature** - "RHCT"
+    /// - **Revision** - 1
+    pub header: SDTHeader,
+    pub flags: RHCTFlags,
+    /// Frequency, in Hz, of the time CSR (the timebase-frequency of the devicetree).
+    pub time_base_frequency: u64,
+    pub number_of_rhct_nodes: u32,
+    /// Offset, from the start of the RHCT, of the node array.
+    pub offset_to_rhct_node_array: u32,
+}
+impl RHCT {
+    pub const fn nodes(&self) -> RHCTNodeIter<'_> {
+        let offset = self.offset_to_rhct_node_array as usize;
+        RHCTNodeIter {
+            next: (self as *const _ as *const u8).wrapping_add(offset),
+            remaining: (self.header.length as usize).saturating_sub(offset),
+            count: self.number_of_rhct_nodes,
+            _rhct: core::marker::PhantomData,
+        }
+    }
+    /// The node at `offset` from the start of the table (as in a Hart Info node's offsets).
+    pub fn node_at(&self, offset: u32) -> Option<RHCTNode<'_>> {
+        let offset = offset as usize;
+        let end = self.header.length as usize;
+        if offset < core::mem::size_of::<RHCT>()
+            || offset + core::mem::size_of::<RHCTNodeHeader>() > end
+        {
+            return None;
+        }
+        let ptr = (self as *const _ as *const u8).wrapping_add(offset);
+        // SAFETY: the header lies within the table, checked above.
+        let length = unsafe { (*(ptr as *const RHCTNodeHeader)).length as usize };
+        if length < core::mem::size_of::<RHCTNodeHeader>() || offset + length > end {
+            return None;
+        }
+        // SAFETY: the node lies within the table and covers its header, checked above.
+        Some(unsafe { RHCTNode::from_ptr(ptr, length) })
+    }
+    /// The Hart Info node of the hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn hart_info(&self, acpi_processor_uid: u32) -> Option<&HartInfoNode> {
+        self.nodes().find_map(|node| match node {
+            RHCTNode::HartInfo(info) if info.acpi_processor_uid == acpi_processor_uid => Some(info),
+            _ => None,
+        })
+    }
+    /// The nodes describing the hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn hart_nodes(&self, acpi_processor_uid: u32) -> impl Iterator<Item = RHCTNode<'_>> {
+        self.hart_info(acpi_processor_uid)
+            .map(HartInfoNode::offsets)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(move |&offset| self.node_at(offset))
+    }
+    /// The ISA string node of the hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn isa_string(&self, acpi_processor_uid: u32) -> Option<&ISAStringNode> {
+        self.hart_nodes(acpi_processor_uid)
+            .find_map(|node| match node {
+                RHCTNode::ISAString(isa) => Some(isa),
+                _ => None,
+            })
+    }
+    /// The CMO node of the hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn cmo(&self, acpi_processor_uid: u32) -> Option<&CMONode> {
+        self.hart_nodes(acpi_processor_uid)
+            .find_map(|node| match node {
+                RHCTNode::CMO(cmo) => Some(cmo),
+                _ => None,
+            })
+    }
+    /// The MMU type of the hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn mmu(&self, acpi_processor_uid: u32) -> Option<MMUType> {
+        self.hart_nodes(acpi_processor_uid)
+            .find_map(|node| match node {
+                RHCTNode::MMU(mmu) => Some(mmu.mmu()),
+                _ => None,
+            })
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{GenericAddressStructure, SDTHeader};
+use core::ffi::CStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// ## Serial Port Interface Type
+///
+/// The serial port subtypes shared by the SPCR Interface Type field and the DBG2 Port Subtype of serial debug ports.
+pub enum SerialInterfaceType {
+    /// Full 16550 interface.
+    Full16550,
+    /// 16550-compatible, a subset of the 16550 interface.
+    Subset16550,
+    /// MAX311xE SPI UART.
+    MAX311xESPI,
+    /// Arm PL011 UART.
+    ArmPL011,
+    /// MSM8x60 (e.g. 8960).
+    MSM8x60,
+    /// Nvidia 16550.
+    Nvidia16550,
+    /// TI OMAP.
+    TIOMAP,
+    /// APM88xxxx.
+    APM88xxxx,
+    /// MSM8974.
+    MSM8974,
+    /// SAM5250.
+    SAM5250,
+    /// Intel USIF.
+    IntelUSIF,
+    /// i.MX 6.
+    IMX6,
+    /// (deprecated) Arm SBSA (2.x only) Generic UART supporting only 32-bit accesses.
+    ArmSBSA32Bit,
+    /// Arm SBSA Generic UART.
+    ArmSBSAGeneric,
+    /// Arm DCC.
+    ArmDCC,
+    /// BCM2835.
+    BCM2835,
+    /// SDM845 with a clock rate of 1.8432 MHz.
+    SDM845Clock18432,
+    /// 16550-compatible with parameters defined in the Generic Address Structure.
+    GAS16550,
+    /// SDM845 with a clock rate of 7.372 MHz.
+    SDM845Clock7372,
+    /// Intel LPSS.
+    IntelLPSS,
+    /// RISC-V SBI console (any supported SBI mechanism).
+    RISCVSBIConsole,
+    /// A value this library doesn't know about (or a reserved one).
+    Unknown(u16),
+}
+impl SerialInterfaceType {
+    pub const fn from_u16(value: u16) -> Self {
+        match value {
+            0x0000 => Self::Full16550,
+            0x0001 => Self::Subset16550,
+            0x0002 => Self::MAX311xESPI,
+            0x0003 => Self::ArmPL011,
+            0x0004 => Self::MSM8x60,
+            0x0005 => Self::Nvidia16550,
+            0x0006 => Self::TIOMAP,
+            0x0008 => Self::APM88xxxx,
+            0x0009 => Self::MSM8974,
+            0x000A => Self::SAM5250,
+            0x000B => Self::IntelUSIF,
+            0x000C => Self::IMX6,
+            0x000D => Self::ArmSBSA32Bit,
+            0x000E => Self::ArmSBSAGeneric,
+            0x000F => Self::ArmDCC,
+            0x0010 => Self::BCM2835,
+            0x0011 => Self::SDM845Clock18432,
+            0x0012 => Self::GAS16550,
+            0x0013 => Self::SDM845Clock7372,
+            0x0014 => Self::IntelLPSS,
+            0x0015 => Self::RISCVSBIConsole,
+            other => Self::Unknown(other),
+        }
+    }
+    /// Returns true for the UARTs that speak the 16550 register interface.
+    pub const fn is_16550(&self) -> bool {
+        matches!(
+            self,
+            Self::Full16550
+                | Self::Subset16550
+                | Self::Nvidia16550
+                | Self::GAS16550
+                | Self::IntelLPSS
+        )
+    }
+    /// Returns true for the UARTs that speak the PL011 register interface (PL011 itself and SBSA).
+    ///
+    /// The BCM2835 entry is the 8250-style mini UART (not its PL011), so it isn't one of them.
+    pub const fn is_pl011(&self) -> bool {
+        matches!(
+            self,
+            Self::ArmPL011 | Self::ArmSBSA32Bit | Self::ArmSBSAGeneric
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## SPCR Interrupt Type
+///
+/// More than one bit can be set; OSPM uses whichever matches the interrupt model it runs.
+pub struct SPCRInterruptType(u8);
+impl SPCRInterruptType {
+    /// PC-AT-compatible dual-8259 IRQ interrupt (the `irq` field).
+    pub const fn pcat_8259(&self) -> bool {
+        self.0 & 0b00001 != 0
+    }
+    /// I/O APIC interrupt (the `global_system_interrupt` field).
+    pub const fn io_apic(&self) -> bool {
+        self.0 & 0b00010 != 0
+    }
+    /// I/O SAPIC interrupt (the `global_system_interrupt` field).
+    pub const fn io_sapic(&self) -> bool {
+        self.0 & 0b00100 != 0
+    }
+    /// ARMH GIC interrupt (the `global_system_interrupt` field).
+    pub const fn gic(&self) -> bool {
+        self.0 & 0b01000 != 0
+    }
+    /// RISC-V PLIC/APLIC interrupt (the `global_system_interrupt` field).
+    pub const fn riscv_plic_aplic(&self) -> bool {
+        self.0 & 0b10000 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+/// ## SPCR Flow Control
+pub struct SPCRFlowControl(u8);
+impl SPCRFlowControl {
+    /// DCD required for transmit.
+    pub const fn dcd(&self) -> bool {
+        self.0 & 0b001 != 0
+    }
+    /// RTS/CTS hardware flow control.
+    pub const fn rts_cts(&self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    /// XON/XOFF software control.
+    pub const fn xon_xoff(&self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The terminal protocol the BIOS was using for console redirection.
+pub enum SPCRTerminalType {
+    VT100,
+    /// Extended VT100 (VT100+).
+    VT100Plus,
+    VTUTF8,
+    ANSI,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Serial Port Console Redirection Table (SPCR)
+///
+/// Describes the serial port the firmware used for console redirection, so the OS can keep using it as its console (or early console) right away.
+///
+/// Like the GICC structure, this table grew over time.  `uart_clock_frequency` only exists from revision 3,
+/// and `precise_baud_rate` and the namespace string fields from revision 4.  Use the methods, which check `header.revision`, instead of the raw fields when that matters.
+pub struct SerialPortConsoleRedirectionTable {
+    /// - **Signature** - "SPCR"
+    pub header: SDTHeader,
+    /// The type of the serial port register interface, using the DBG2 Serial Port Subtypes.
+    ///
+    /// Revision 1 used its own 0 (16550) / 1 (16450) numbering, which lines up with the first two subtypes anyway.
+    pub interface_type: u8,
+    reserved0: [u8; 3],
+    /// The base address of the Serial Port register set described using the Generic Address Structure, or 0 if console redirection is disabled.
+    ///
+    /// Note: The `address_space_id` must be System Memory or System I/O, unless the interface type is Arm DCC or RISC-V SBI console, in which case it's all zero.
+    pub base_address: GenericAddressStructure,
+    /// Interrupt type(s) used by the UART.
+    pub interrupt_type: SPCRInterruptType,
+    /// The PC-AT-compatible IRQ used by the UART. Valid only if `interrupt_type.pcat_8259()`.
+    pub irq: u8,
+    /// The Global System Interrupt (GSIV) used by the UART. Not valid if `interrupt_type` is PC-AT only.
+    pub global_system_interrupt: u32,
+    /// The baud rate the BIOS used for redirection:
+    ///
+    /// - **0** - As is, operating system relies on the current configuration of serial port until the full featured driver will be initialized.
+    /// - **3** - 9600
+    /// - **4** - 19200
+    /// - **6** - 57600
+    /// - **7** - 115200
+    ///
+    /// The rest of the values are reserved.
+    pub configured_baud_rate: u8,
+    /// - **0** - No Parity
+    ///
+    /// The rest of the values are reserved.
+    pub parity: u8,
+    /// - **1** - 1 Stop bit
+    ///
+    /// The rest of the values are reserved.
+    pub stop_bits: u8,
+    /// Flow control.
+    pub flow_control: SPCRFlowControl,
+    /// The terminal protocol the BIOS was using for console redirection:
+    ///
+    /// - **0** - VT100
+    /// - **1** - Extended VT100 (VT100+)
+    /// - **2** - VT-UTF8
+    /// - **3** - ANSI
+    ///
+    /// The rest of the values are reserved.
+    pub terminal_type: u8,
+    /// Language which the BIOS was redirecting. Must be 0.
+    pub language: u8,
+    /// Designates the Device ID of a PCI device that contains a UART to be used as a headless port. Must be 0xFFFF if it is not a PCI device.
+    pub pci_device_id: u16,
+    /// Designates the Vendor ID of a PCI device that contains a UART to be used as a headless port. Must be 0xFFFF if it is not a PCI device.
+    pub pci_vendor_id: u16,
+    /// PCI Bus Number if table describes a PCI device. Must be 0x00 if it is not a PCI device.
+    pub pci_bus_number: u8,
+    /// PCI Device Number if table describes a PCI device. Must be 0x00 if it is not a PCI device.
+    pub pci_device_number: u8,
+    /// PCI Function Number if table describes a PCI device. Must be 0x00 if it is not a PCI device.
+    pub pci_function_number: u8,
+    /// PCI Compatibility flags:
+    ///
+    /// - **Bit 0** - Operating System should NOT suppress PNP device enumeration or disable power management for this device.
+    ///
+    /// Must be 0 if it is not a PCI device. The rest of the bits are reserved.
+    pub pci_flags: u32,
+    /// PCI segment number. For systems with fewer than 255 PCI buses, this number must be 0.
+    pub pci_segment: u8,
+    /// Zero, indicating that the UART clock frequency is indeterminate, or the UART clock frequency in Hz.
+    ///
+    /// **Revision 3+ only.**
+    pub uart_clock_frequency: u32,
+    /// Zero, or the baud rate the UART was configured with, in bits per second. When non-zero, this overrides `configured_baud_rate`.
+    ///
+    /// **Revision 4+ only.**
+    pub precise_baud_rate: u32,
+    /// Length, in bytes, of the namespace string, including the null terminator.
+    ///
+    /// **Revision 4+ only.**
+    pub namespace_string_length: u16,
+    /// Offset, in bytes, from the beginning of this table to the namespace string.
+    ///
+    /// **Revision 4+ only.**
+    pub namespace_string_offset: u16,
+}
+impl SerialPortConsoleRedirectionTable {
+    pub const fn interface_type(&self) -> SerialInterfaceType {
+        SerialInterfaceType::from_u16(self.interface_type as u16)
+    }
+    /// Returns true if console redirection is enabled (the base address is non-zero).
+    ///
+    /// Arm DCC and RISC-V SBI consoles have no registers, so they always count as enabled.
+    pub const fn enabled(&self) -> bool {
+        let address = self.base_address.address;
+        address != 0
+            || matches!(
+                self.interface_type(),
+                SerialInterfaceType::ArmDCC | SerialInterfaceType::RISCVSBIConsole
+            )
+    }
+    /// The baud rate, or `None` if the port is left "as is".
+    ///
+    /// On revision 4+, a non-zero `precise_baud_rate` wins over `configured_baud_rate`.
+    pub const fn baud_rate(&self) -> Option<u32> {
+        if self.header.revision >= 4 && self.precise_baud_rate != 0 {
+            return Some(self.precise_baud_rate);
+        }
+        match self.configured_baud_rate {
+            3 => Some(9600),
+            4 => Some(19200),
+            6 => Some(57600),
+            7 => Some(115200),
+            _ => None,
+        }
+    }
+    /// The terminal type, or `None` for a reserved value.
+    pub const fn terminal_type(&self) -> Option<SPCRTerminalType> {
+        match self.terminal_type {
+            0 => Some(SPCRTerminalType::VT100),
+            1 => Some(SPCRTerminalType::VT100Plus),
+            2 => Some(SPCRTerminalType::VTUTF8),
+            3 => Some(SPCRTerminalType::ANSI),
+            _ => None,
+        }
+    }
+    /// The UART input clock, in Hz, if the table is revision 3+ and knows it.
+    pub const fn uart_clock_frequency(&self) -> Option<u32> {
+        if self.header.revision >= 3 && self.uart_clock_frequency != 0 {
+            Some(self.uart_clock_frequency)
+        } else {
+            None
+        }
+    }
+    /// Returns true if the UART is a PCI device.
+    pub const fn is_pci(&self) -> bool {
+        self.pci_device_id != 0xFFFF || self.pci_vendor_id != 0xFFFF
+    }
+    /// The full namespace path of the UART device (for example "\_SB.COM0"), or "." if it has no namespace device.
+    ///
+    /// Only revision 4+ tables have one.
+    pub const fn namespace_string(&self) -> Option<&CStr> {
+        let offset = self.namespace_string_offset as usize;
+        let length = self.namespace_string_length as usize;
+        if self.header.revision < 4 || length == 0 || offset + length > self.header.length as usize
+        {
+            return None;
+        }
+        // SAFETY: the string lies within the table's length, checked above.
+        let bytes = unsafe {
+            core::slice::from_raw_parts((self as *const _ as *const u8).add(offset), length)
+        };
+        match CStr::from_bytes_until_nul(bytes) {
+            Ok(string) => Some(string),
+            Err(_) => None,
+        }
+    }
+    /// Collects what a UART driver needs out of the table.
+    pub const fn uart_configuration(&self) -> UARTConfiguration {
+        UARTConfiguration {
+            interface_type: self.interface_type(),
+            base_address: self.base_address,
+            baud_rate: self.baud_rate(),
+            clock_frequency: self.uart_clock_frequency(),
+            // The only defined parity is "none" and the only defined stop bits is 1.
+            data_bits: 8,
+            stop_bits: 1,
+            flow_control: self.flow_control,
+            global_system_interrupt: self.global_system_interrupt,
+            irq: self.irq,
+            interrupt_type: self.interrupt_type,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+/// ## UART Configuration
+///
+/// A ready-to-use description of the console UART, as decoded from the SPCR.  The line is always 8N1, the only format the SPCR can describe.
+pub struct UARTConfiguration {
+    pub interface_type: SerialInterfaceType,
+    pub base_address: GenericAddressStructure,
+    /// `None` means keep the rate the firmware left the port at.
+    pub baud_rate: Option<u32>,
+    /// The UART input clock in Hz, if known.
+    pub clock_frequency: Option<u32>,
+    pub data_bits: u8,
+    pub stop_bits: u8,
+    pub flow_control: SPCRFlowControl,
+    pub global_system_interrupt: u32,
+    pub irq: u8,
+    pub interrupt_type: SPCRInterruptType,
+}
+impl UARTConfiguration {
+    /// The Linux-style `earlycon=` value for this UART (e.g. "uart8250,io,0x3f8,115200" or "pl011,mmio32,0x9000000"), without the "earlycon=" part.
+    ///
+    /// Returns `None` for interfaces Linux has no earlycon driver for.
+    pub const fn earlycon(&self) -> Option<EarlyCon> {
+        let address = self.base_address.address;
+        let io = self.base_address.address_space_id == 0x01;
+        let (driver, iotype) = if self.interface_type.is_16550() {
+            let iotype = if io {
+                "io"
+            } else if self.base_address.access_size == 3 || self.base_address.reg_bit_width == 32 {
+                "mmio32"
+            } else {
+                "mmio"
+            };
+            ("uart8250", iotype)
+        } else if self.interface_type.is_pl011() {
+            ("pl011", "mmio32")
+        } else if let SerialInterfaceType::BCM2835 = self.interface_type {
+            ("bcm2835aux", "mmio32")
+        } else {
+            return None;
+        };
+        Some(EarlyCon {
+            driver,
+            iotype,
+            address,
+            baud_rate: self.baud_rate,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+/// A Linux-style earlycon string, built by `UARTConfiguration::earlycon`.  Use it through `Display` (e.g. `write!(buf, "earlycon={}", con)`).
+pub struct EarlyCon {
+    pub driver: &'static str,
+    pub iotype: &'static str,
+    pub address: u64,
+    pub baud_rate: Option<u32>,
+}
+impl core::fmt::Display for EarlyCon {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{},{},{:#x}", self.driver, self.iotype, self.address)?;
+        if let Some(baud) = self.baud_rate {
+            write!(f, ",{}", baud)?;
+        }
+        Ok(())
+    }
+}
@@ -54,4 +54,17 @@ pub struct MemoryAffinity {
     /// Indicates whether the region of memory is enabled and can be hot plugged.
     pub flags: MemoryAffinityStructureFlags,
     reserved2: u64,
-}
\ No newline at end of file
+}
+impl MemoryAffinity {
+    pub const fn base_address(&self) -> u64 {
+        (self.base_address_high as u64) << 32 | self.base_address_low as u64
+    }
+    /// Length, in bytes, of the memory range.
+    pub const fn range_length(&self) -> u64 {
+        (self.length_high as u64) << 32 | self.length_low as u64
+    }
+    /// Returns true if `address` is in the memory range.
+    pub const fn contains(&self, address: u64) -> bool {
+        address >= self.base_address() && address - self.base_address() < self.range_length()
+    }
+}
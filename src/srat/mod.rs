@@ -1,6 +1,12 @@
 pub mod affinity;
 
-use crate::SDTHeader;
+use crate::{
+    SDT_HEADER_SIZE, SDTHeader,
+    device::DeviceId,
+    srat::affinity::{
+        generic_port::GenericPortAffinity, memory::MemoryAffinity, rintc::RINTCAffinity,
+    },
+};
 
 #[derive(Copy, Clone)]
 #[repr(C, packed)]
@@ -12,6 +18,22 @@ pub struct ACPIDeviceHandle {
     pub acpi_uid: u32,
     reserved: u32,
 }
+impl ACPIDeviceHandle {
+    /// The _HID value as a `DeviceId`.
+    ///
+    /// Firmware normally puts the _HID string itself in these 8 bytes ("ACPI0016", or "PNP0A08" with a null at the end).
+    /// A _HID string is at least 7 characters, so if the high 4 bytes are all zero, the low 4 bytes are a compressed EISA ID instead and it's handed back as one.
+    pub fn hid(&self) -> DeviceId<'_> {
+        // SAFETY: acpi_hid is the first field, and u8 has no alignment requirement.
+        let bytes = unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, 8) };
+        let hid = self.acpi_hid;
+        if hid != 0 && hid >> 32 == 0 {
+            return DeviceId::EisaId(hid as u32);
+        }
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(8);
+        DeviceId::String(&bytes[..len])
+    }
+}
 
 #[derive(Copy, Clone)]
 #[repr(C, packed)]
@@ -69,3 +91,49 @@ pub struct SRAT {
     /// A list of static resource allocation structures for the platform.
     pub static_resource_allocation_structure: [u8; 0],
 }
+impl SRAT {
+    /// The affinity structures of type `r#type` in the table, as `T`.
+    fn affinities<T: 'static>(&self, r#type: u8) -> impl Iterator<Item = &T> {
+        let start = SDT_HEADER_SIZE + 12;
+        let end = self.header.length as usize;
+        let mut offset = start;
+        core::iter::from_fn(move || {
+            while offset + 2 <= end {
+                let ptr = (self as *const _ as *const u8).wrapping_add(offset);
+                // SAFETY: the type and length lie within the table, checked by the loop condition.
+                let (structure_type, length) = unsafe { (*ptr, *ptr.add(1) as usize) };
+                // A zero length would loop forever, and a longer one runs off the table; treat either as the end of the list.
+                if length < 2 || offset + length > end {
+                    break;
+                }
+                offset += length;
+                if structure_type == r#type && length >= core::mem::size_of::<T>() {
+                    // SAFETY: the structure lies within the table (checked above), and is only cast to a type it's long enough for.
+                    return Some(unsafe { &*(ptr as *const T) });
+                }
+            }
+            offset = end;
+            None
+        })
+    }
+    /// The Memory Affinity structures of the table.
+    pub fn memory_affinities(&self) -> impl Iterator<Item = &MemoryAffinity> {
+        self.affinities(1)
+    }
+    /// The Generic Port Affinity structures of the table.
+    pub fn generic_port_affinities(&self) -> impl Iterator<Item = &GenericPortAffinity> {
+        self.affinities(6)
+    }
+    /// The RINTC Affinity structures of the table.
+    pub fn rintc_affinities(&self) -> impl Iterator<Item = &RINTCAffinity> {
+        self.affinities(7)
+    }
+    /// The proximity domain of the RISC-V hart with the ACPI Processor UID `acpi_processor_uid`.
+    pub fn hart_proximity_domain(&self, acpi_processor_uid: u32) -> Option<u32> {
+        self.rintc_affinities()
+            .find(|affinity| {
+                ({ affinity.flags }).enabled() && affinity.acpi_processor_uid == acpi_processor_uid
+            })
+            .map(|affinity| affinity.proximity_domain)
+    }
+}
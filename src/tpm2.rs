@@ -0,0 +1,300 @@
+use crate::{SDT_HEADER_SIZE, SDTHeader};
+
+/// The address of the locality 0 registers of a TPM with a FIFO (TIS) interface, unless the platform says otherwise.
+pub const TIS_BASE_ADDRESS: u64 = 0xFED4_0000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TPMPlatformClass {
+    Client,
+    Server,
+    Unknown(u16),
+}
+impl TPMPlatformClass {
+    pub const fn from_u16(value: u16) -> Self {
+        match value {
+            0 => Self::Client,
+            1 => Self::Server,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// How OSPM tells the TPM to start processing a command.
+pub enum TPMStartMethod {
+    /// Through the ACPI start method (the _DSM of the TPM device).
+    ACPI,
+    /// Memory-mapped FIFO interface (TIS); writing the command is enough.
+    MemoryMappedIO,
+    /// Command Response Buffer interface; write the Start register of the control area.
+    CommandResponseBuffer,
+    /// Command Response Buffer interface, with the ACPI start method run after writing the Start register.
+    CommandResponseBufferWithACPI,
+    /// Command Response Buffer interface, with an Arm SMC (see `ArmSMCParameters`) after writing the Start register.
+    CommandResponseBufferWithArmSMC,
+    /// Command Response Buffer interface to a Pluton security processor (see `PlutonParameters`).
+    CommandResponseBufferWithPluton,
+    /// Command Response Buffer interface, with an Arm FF-A message (see `ArmFFAParameters`) after writing the Start register.
+    CommandResponseBufferWithArmFFA,
+    Unknown(u32),
+}
+impl TPMStartMethod {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            2 => Self::ACPI,
+            6 => Self::MemoryMappedIO,
+            7 => Self::CommandResponseBuffer,
+            8 => Self::CommandResponseBufferWithACPI,
+            11 => Self::CommandResponseBufferWithArmSMC,
+            13 => Self::CommandResponseBufferWithPluton,
+            15 => Self::CommandResponseBufferWithArmFFA,
+            other => Self::Unknown(other),
+        }
+    }
+    /// Returns true if the TPM talks through a Command Response Buffer (even with the plain ACPI start method).
+    pub const fn is_crb(&self) -> bool {
+        matches!(
+            self,
+            Self::ACPI
+                | Self::CommandResponseBuffer
+                | Self::CommandResponseBufferWithACPI
+                | Self::CommandResponseBufferWithArmSMC
+                | Self::CommandResponseBufferWithPluton
+                | Self::CommandResponseBufferWithArmFFA
+        )
+    }
+    /// Returns true if starting a command also takes running the ACPI start method.
+    pub const fn uses_acpi_start(&self) -> bool {
+        matches!(self, Self::ACPI | Self::CommandResponseBufferWithACPI)
+    }
+    /// Length, in bytes, of the start method specific parameters.
+    ///
+    /// Every start method has 12 bytes of them, except Pluton, which has 16.
+    pub const fn parameters_length(&self) -> usize {
+        match self {
+            Self::CommandResponseBufferWithPluton => 16,
+            _ => 12,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Which register interface a TPM has.
+pub enum TPMInterface {
+    /// FIFO (TIS) interface, at `TPM2::tis_base_address`.
+    TIS,
+    /// Command Response Buffer interface, with its control area at `TPM2::crb_control_area`.
+    CRB,
+}
+
+#[derive(Copy, Clone)]
+pub struct ArmSMCInterruptFlags(u8);
+impl ArmSMCInterruptFlags {
+    /// If set, the interrupt is edge triggered; otherwise level triggered.
+    pub const fn edge_triggered(&self) -> bool {
+        self.0 & 0b01 != 0
+    }
+    /// If set, the interrupt is active low (or falling edge); otherwise active high (or rising edge).
+    pub const fn active_low(&self) -> bool {
+        self.0 & 0b10 != 0
+    }
+    // The rest of the bits are reserved.
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Start Method Specific Parameters for Arm SMC
+pub struct ArmSMCParameters {
+    /// The GSIV the TPM signals completion on (0 if it doesn't; poll instead).
+    pub global_interrupt: u32,
+    pub interrupt_flags: ArmSMCInterruptFlags,
+    /// - **Bit 0** - Set if the TPM's firmware expects an SMC for every locality change and idle transition as well.
+    pub operation_flags: u8,
+    reserved: u16,
+    /// The SMC function ID to call.
+    pub smc_function_id: u32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Start Method Specific Parameters for Pluton
+pub struct PlutonParameters {
+    /// Physical address of the doorbell OSPM writes to start a command.
+    pub start_address: u64,
+    /// Physical address of the doorbell OSPM writes once it has read the response.
+    pub reply_address: u64,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Start Method Specific Parameters for Arm FF-A
+pub struct ArmFFAParameters {
+    /// - **Bit 0** - Set if the TPM service supports notifications.
+    /// - **Bit 1** - Set if the TPM service signals completion with an interrupt.
+    pub flags: u8,
+    /// - **Bit 0** - Set if the CRB is in memory shared with the partition (instead of the partition's own memory).
+    pub attributes: u8,
+    /// The FF-A partition ID of the TPM service.
+    pub partition_id: u16,
+    reserved: [u8; 8],
+}
+
+#[derive(Copy, Clone)]
+/// The start method specific parameters, decoded by start method.
+pub enum TPMStartMethodParameters<'a> {
+    ArmSMC(&'a ArmSMCParameters),
+    Pluton(&'a PlutonParameters),
+    ArmFFA(&'a ArmFFAParameters),
+    /// The start method has no parameters to decode (they're reserved).
+    None,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## CRB Control Area
+///
+/// The registers `TPM2::address_of_crb_control_area` points at.
+///
+/// This is device memory: map it uncached and go through `read_volatile`/`write_volatile`, never through a plain reference.
+pub struct CRBControlArea {
+    /// - **Bit 0** - cmdReady: asks the TPM to get ready for a command.
+    /// - **Bit 1** - goIdle: asks the TPM to go idle.
+    pub request: u32,
+    /// - **Bit 0** - tpmSts: set if the TPM is in a fatal error state.
+    /// - **Bit 1** - tpmIdle: set if the TPM is idle.
+    pub status: u32,
+    /// Write 1 to cancel the command in progress.
+    pub cancel: u32,
+    /// Write 1 to start the command; the TPM clears it when the response is ready.
+    pub start: u32,
+    pub interrupt_enable: u32,
+    pub interrupt_status: u32,
+    /// Size, in bytes, of the command buffer.
+    pub command_size: u32,
+    pub command_address_low: u32,
+    pub command_address_high: u32,
+    /// Size, in bytes, of the response buffer.
+    pub response_size: u32,
+    pub response_address: u64,
+}
+impl CRBControlArea {
+    pub const fn command_address(&self) -> u64 {
+        (self.command_address_high as u64) << 32 | self.command_address_low as u64
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+/// ## Trusted Platform Module 2 Table (TPM2)
+///
+/// Describes the TPM 2.0 of the platform: which interface it has, where its registers are, how to start a command, and where the TCG event log goes.
+pub struct TPM2 {
+    /// - **Signature** - "TPM2"
+    /// - **Revision** - 4 (5 with the Pluton and FF-A start methods)
+    pub header: SDTHeader,
+    pub platform_class: u16,
+    reserved: u16,
+    /// Physical address of the CRB control area (see `CRBControlArea`).
+    /// For a FIFO interface it's either 0 or the base of the locality 0 registers.
+    pub address_of_crb_control_area: u64,
+    pub start_method: u32,
+    /// The start method specific parameters, then (optionally) the log area minimum length (u32) and the log area start address (u64).
+    pub start_method_specific_parameters: [u8; 0],
+}
+impl TPM2 {
+    pub const fn platform_class(&self) -> TPMPlatformClass {
+        TPMPlatformClass::from_u16(self.platform_class)
+    }
+    pub const fn start_method(&self) -> TPMStartMethod {
+        TPMStartMethod::from_u32(self.start_method)
+    }
+    pub const fn interface(&self) -> TPMInterface {
+        if self.start_method().is_crb() {
+            TPMInterface::CRB
+        } else {
+            TPMInterface::TIS
+        }
+    }
+    /// Physical address of the CRB control area, if the TPM has a CRB interface.
+    pub const fn crb_control_area(&self) -> Option<u64> {
+        match self.interface() {
+            TPMInterface::CRB => Some(self.address_of_crb_control_area),
+            TPMInterface::TIS => None,
+        }
+    }
+    /// Physical address of the locality 0 FIFO registers, if the TPM has a FIFO (TIS) interface.
+    pub const fn tis_base_address(&self) -> Option<u64> {
+        match (self.interface(), self.address_of_crb_control_area) {
+            (TPMInterface::TIS, 0) => Some(TIS_BASE_ADDRESS),
+            (TPMInterface::TIS, address) => Some(address),
+            (TPMInterface::CRB, _) => None,
+        }
+    }
+    /// The raw start method specific parameters (shorter than `parameters_length` if the table is).
+    pub const fn parameters(&self) -> &[u8] {
+        let available = (self.header.length as usize).saturating_sub(SDT_HEADER_SIZE + 16);
+        let length = self.start_method().parameters_length();
+        // SAFETY: the parameters lie within the table's length, which bounds the slice.
+        unsafe {
+            core::slice::from_raw_parts(
+                (self as *const _ as *const u8).add(SDT_HEADER_SIZE + 16),
+                if available < length {
+                    available
+                } else {
+                    length
+                },
+            )
+        }
+    }
+    pub fn start_method_parameters(&self) -> TPMStartMethodParameters<'_> {
+        let parameters = self.parameters();
+        if parameters.len() < self.start_method().parameters_length() {
+            return TPMStartMethodParameters::None;
+        }
+        let ptr = parameters.as_ptr();
+        // SAFETY: the parameters were checked to be long enough above.
+        unsafe {
+            match self.start_method() {
+                TPMStartMethod::CommandResponseBufferWithArmSMC => {
+                    TPMStartMethodParameters::ArmSMC(&*(ptr as *const _))
+                }
+                TPMStartMethod::CommandResponseBufferWithPluton => {
+                    TPMStartMethodParameters::Pluton(&*(ptr as *const _))
+                }
+                TPMStartMethod::CommandResponseBufferWithArmFFA => {
+                    TPMStartMethodParameters::ArmFFA(&*(ptr as *const _))
+                }
+                _ => TPMStartMethodParameters::None,
+            }
+        }
+    }
+    // Offset of the log area fields, if the table is long enough to have them.
+    const fn log_area_offset(&self) -> Option<usize> {
+        let offset = SDT_HEADER_SIZE + 16 + self.start_method().parameters_length();
+        if self.header.length as usize >= offset + 12 {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+    /// The minimum length, in bytes, of the TCG event log area.
+    pub const fn log_area_minimum_length(&self) -> Option<u32> {
+        match self.log_area_offset() {
+            // SAFETY: `log_area_offset` only hands out offsets with the log area fields within the table.
+            Some(offset) => Some(unsafe {
+                ((self as *const _ as *const u8).add(offset) as *const u32).read_unaligned()
+            }),
+            None => None,
+        }
+    }
+    /// Physical address of the TCG event log area.
+    pub const fn log_area_start_address(&self) -> Option<u64> {
+        match self.log_area_offset() {
+            // SAFETY: `log_area_offset` only hands out offsets with the log area fields within the table.
+            Some(offset) => Some(unsafe {
+                ((self as *const _ as *const u8).add(offset + 4) as *const u64).read_unaligned()
+            }),
+            None => None,
+        }
+    }
+}